@@ -27,6 +27,9 @@ pub enum ApiError {
     #[error("Proof not found for user{0}")]
     ProofNotFound(String),
 
+    #[error("Claim records not found for distributor {0}")]
+    ClaimRecordsNotFound(String),
+
     #[error("Parse Pubkey Error")]
     ParsePubkeyError(#[from] ParsePubkeyError),
 
@@ -59,6 +62,10 @@ impl IntoResponse for ApiError {
                 error!("Proof not found for user {u}");
                 (StatusCode::INTERNAL_SERVER_ERROR, "Proof not found")
             }
+            ApiError::ClaimRecordsNotFound(d) => {
+                error!("Claim records not found for distributor {d}");
+                (StatusCode::NOT_FOUND, "Claim records not found")
+            }
 
             ApiError::ParsePubkeyError(e) => {
                 error!("Parse pubkey error: {e}");
@@ -2,14 +2,26 @@ mod error;
 mod router;
 
 use std::{
-    collections::HashMap, fmt::Debug, fs, net::SocketAddr, path::PathBuf, str::FromStr, sync::Arc,
+    collections::{BTreeMap, HashMap},
+    fmt::Debug,
+    fs, mem,
+    net::SocketAddr,
+    path::PathBuf,
+    str::FromStr,
+    sync::Arc,
     thread, time,
 };
 
+use anchor_lang::Discriminator;
 use clap::Parser;
-use jito_merkle_tree::{airdrop_merkle_tree::AirdropMerkleTree, utils::get_merkle_distributor_pda};
-use router::RouterState;
+use jito_merkle_tree::utils::{get_merkle_distributor_pda, get_registry_pda};
+use merkle_distributor::state::{
+    merkle_distributor::MerkleDistributor,
+    registry::{Registry, RegistryEntry},
+};
+use router::{CompressedTree, RouterState, ServedTree};
 use solana_program::pubkey::Pubkey;
+use solana_rpc_client::nonblocking::rpc_client::RpcClient;
 use tracing::{info, instrument};
 
 use crate::{
@@ -18,6 +30,9 @@ use crate::{
 };
 pub type Result<T> = std::result::Result<T, ApiError>;
 
+/// Max number of accounts of a getMultipleAccounts request
+const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+
 #[derive(Parser, Debug)]
 #[clap(author, version, about, long_about = None)]
 pub struct Args {
@@ -25,7 +40,7 @@ pub struct Args {
     #[clap(long, env, default_value_t = SocketAddr::from_str("0.0.0.0:7001").unwrap())]
     bind_addr: SocketAddr,
 
-    /// Path of merkle tree
+    /// Directory of merkle trees, `.bin` trees are memory-mapped, any other file is read as JSON
     #[clap(long, env)]
     merkle_tree_path: PathBuf,
 
@@ -40,6 +55,15 @@ pub struct Args {
     /// Program ID
     #[clap(long, env)]
     program_id: Pubkey,
+
+    /// RPC url, when set only the trees of registered distributors are served
+    #[clap(long, env)]
+    rpc_url: Option<String>,
+
+    /// Directory of the local claim record indexes written by the CLI indexer, serves the
+    /// record proofs of the compressed distributors indexed at startup
+    #[clap(long, env)]
+    claim_records_path: Option<PathBuf>,
 }
 
 #[tokio::main]
@@ -59,44 +83,84 @@ async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
         .collect();
     paths.sort_by_key(|dir| dir.path());
 
-    let mut tree = HashMap::new();
-    let mut max_num_nodes = 0u64;
-    let mut max_total_claim = 0u64;
-    let mut distributors = vec![];
-    let one_sec = time::Duration::from_millis(1000);
+    let mut local_trees = BTreeMap::new();
     for file in paths {
-        let single_tree_path = file.path();
-        let single_tree = AirdropMerkleTree::new_from_file(&single_tree_path)?;
+        let single_tree = Arc::new(ServedTree::load(&file.path())?);
+        local_trees.insert(single_tree.airdrop_version(), single_tree);
+    }
 
-        let (distributor_pubkey, _bump) = get_merkle_distributor_pda(
-            &args.program_id,
-            &args.base,
-            &args.mint,
-            single_tree.airdrop_version,
-        );
+    let mut distributors = vec![];
+    // with a registry, the registered distributors are served, otherwise the distributor PDA
+    // of each local tree
+    let served_trees = match &args.rpc_url {
+        Some(rpc_url) => {
+            let client = RpcClient::new(rpc_url.to_string());
+            match get_registry_entries(&client, &args).await? {
+                Some(registry_entries) => {
+                    let mut served_trees = vec![];
+                    let mut entries_without_tree = vec![];
+                    for entry in registry_entries {
+                        match local_trees.remove(&entry.version) {
+                            Some(single_tree) if single_tree.merkle_root() == entry.root => {
+                                served_trees.push((entry.distributor, single_tree));
+                            }
+                            Some(_) => println!(
+                                "skip {}, not registered with this merkle root",
+                                entry.version
+                            ),
+                            None => entries_without_tree.push(entry),
+                        }
+                    }
+                    for version in local_trees.keys() {
+                        println!("skip {}, not registered", version);
+                    }
+                    distributors
+                        .extend(get_onchain_distributors(&client, &entries_without_tree).await?);
+                    served_trees
+                }
+                None => get_distributor_pdas(&args, local_trees),
+            }
+        }
+        None => get_distributor_pdas(&args, local_trees),
+    };
 
-        max_total_claim = max_total_claim
-            .checked_add(single_tree.get_max_total_claim())
-            .unwrap();
-        max_num_nodes = max_num_nodes
-            .checked_add(single_tree.max_num_nodes)
-            .unwrap();
+    let mut merkle_trees = vec![];
+    let mut compressed_trees = HashMap::new();
+    let one_sec = time::Duration::from_millis(1000);
+    for (distributor_pubkey, single_tree) in served_trees {
         distributors.push(SingleDistributor {
             distributor_pubkey: distributor_pubkey.to_string(),
             // merkle_root: single_tree.merkle_root.clone(),
-            airdrop_version: single_tree.airdrop_version,
-            max_num_nodes: single_tree.max_num_nodes,
+            airdrop_version: single_tree.airdrop_version(),
+            max_num_nodes: single_tree.max_num_nodes(),
             max_total_claim: single_tree.get_max_total_claim(),
         });
-        for node in single_tree.tree_nodes.iter() {
-            tree.insert(node.claimant, (distributor_pubkey, node.clone()));
+        println!("done {}", single_tree.airdrop_version());
+
+        if let Some(claim_records_path) = &args.claim_records_path {
+            let index_path = claim_records_path.join(format!("{}.json", distributor_pubkey));
+            if index_path.exists() {
+                println!("serving claim records of {}", single_tree.airdrop_version());
+                compressed_trees.insert(distributor_pubkey, CompressedTree::new(index_path));
+            }
         }
-        println!("done {}", single_tree.airdrop_version);
+        merkle_trees.push((distributor_pubkey, single_tree));
         thread::sleep(one_sec);
     }
 
     println!("Done all tree");
 
+    let mut max_num_nodes = 0u64;
+    let mut max_total_claim = 0u64;
+    for distributor in distributors.iter() {
+        max_total_claim = max_total_claim
+            .checked_add(distributor.max_total_claim)
+            .unwrap();
+        max_num_nodes = max_num_nodes
+            .checked_add(distributor.max_num_nodes)
+            .unwrap();
+    }
+
     distributors.sort_unstable_by(|a, b| a.airdrop_version.cmp(&b.airdrop_version));
 
     let state = Arc::new(RouterState {
@@ -105,7 +169,8 @@ async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
             max_total_claim,
             trees: distributors,
         },
-        tree,
+        merkle_trees,
+        compressed_trees,
         program_id: args.program_id,
     });
 
@@ -118,3 +183,85 @@ async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
     info!("done");
     Ok(())
 }
+
+/// Distributor PDA of each local tree
+fn get_distributor_pdas(
+    args: &Args,
+    local_trees: BTreeMap<u64, Arc<ServedTree>>,
+) -> Vec<(Pubkey, Arc<ServedTree>)> {
+    local_trees
+        .into_values()
+        .map(|single_tree| {
+            let (distributor_pubkey, _bump) = get_merkle_distributor_pda(
+                &args.program_id,
+                &args.base,
+                &args.mint,
+                single_tree.airdrop_version(),
+            );
+            (distributor_pubkey, single_tree)
+        })
+        .collect()
+}
+
+/// Returns the distributors registered for the base and mint, if the registry exists
+async fn get_registry_entries(
+    client: &RpcClient,
+    args: &Args,
+) -> Result<Option<Vec<RegistryEntry>>> {
+    let (registry, _bump) = get_registry_pda(&args.program_id, &args.base, &args.mint);
+    let account = match client
+        .get_multiple_accounts(&[registry])
+        .await?
+        .pop()
+        .flatten()
+    {
+        Some(account) => account,
+        None => return Ok(None),
+    };
+    let (_registry, entries) = Registry::read_unaligned(&account.data)
+        .map_err(|e| ApiError::MerkleDistributorError(e.to_string()))?;
+    Ok(Some(entries))
+}
+
+/// Reads the registered distributors without a local tree on-chain, they are listed but no
+/// proof is served for them
+async fn get_onchain_distributors(
+    client: &RpcClient,
+    entries: &[RegistryEntry],
+) -> Result<Vec<SingleDistributor>> {
+    let mut distributors = vec![];
+    for chunk in entries.chunks(MAX_MULTIPLE_ACCOUNTS) {
+        let keys: Vec<Pubkey> = chunk.iter().map(|entry| entry.distributor).collect();
+        let accounts = client.get_multiple_accounts(&keys).await?;
+        for (entry, account) in chunk.iter().zip(accounts) {
+            match account.and_then(|account| read_distributor(&account.data)) {
+                Some(distributor) => {
+                    println!(
+                        "registered distributor {} version {} has no merkle tree",
+                        entry.distributor, entry.version
+                    );
+                    distributors.push(SingleDistributor {
+                        distributor_pubkey: entry.distributor.to_string(),
+                        airdrop_version: entry.version,
+                        max_num_nodes: distributor.max_num_nodes,
+                        max_total_claim: distributor.max_total_claim,
+                    });
+                }
+                None => println!(
+                    "skip registered distributor {} version {}, closed or not migrated",
+                    entry.distributor, entry.version
+                ),
+            }
+        }
+    }
+    Ok(distributors)
+}
+
+/// Copies a distributor out of account data fetched over RPC, which may not be aligned
+fn read_distributor(data: &[u8]) -> Option<MerkleDistributor> {
+    let end = 8 + mem::size_of::<MerkleDistributor>();
+    if data.len() < end || data[..8] != MerkleDistributor::DISCRIMINATOR {
+        return None;
+    }
+    bytemuck::try_pod_read_unaligned(&data[8..end]).ok()
+}
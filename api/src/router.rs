@@ -1,9 +1,11 @@
 use std::{
     collections::HashMap,
     fmt::{Debug, Formatter},
+    fs,
+    path::PathBuf,
     str::FromStr,
-    sync::Arc,
-    time::Duration,
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime},
 };
 
 use axum::{
@@ -15,7 +17,13 @@ use axum::{
     Json, Router,
 };
 use http::Request;
-use jito_merkle_tree::{airdrop_merkle_tree::UserProof, tree_node::TreeNode};
+use jito_merkle_tree::{
+    airdrop_merkle_tree::{self, AirdropMerkleTree, UserProof},
+    binary_tree::BinaryMerkleTree,
+    claim_record_tree::{get_record_index, ClaimRecord, ClaimRecordIndex, ClaimRecordMerkleTree},
+    hasher::HashType,
+    tree_node::TreeNode,
+};
 use serde_derive::{Deserialize, Serialize};
 use solana_program::pubkey::Pubkey;
 
@@ -34,14 +42,158 @@ use crate::{error, error::ApiError, Result};
 pub struct RouterState {
     pub program_id: Pubkey,
     pub distributors: Distributors,
-    pub tree: HashMap<Pubkey, (Pubkey, TreeNode)>,
+    /// Merkle tree of each distributor in serving order, claimants are looked up in the trees
+    /// and proofs are built from their levels on request
+    pub merkle_trees: Vec<(Pubkey, Arc<ServedTree>)>,
+    /// Compressed distributors with a local claim record index, by distributor
+    pub compressed_trees: HashMap<Pubkey, CompressedTree>,
+}
+
+/// Airdrop merkle tree of a distributor, binary trees are memory-mapped and read in place
+#[derive(Debug)]
+pub enum ServedTree {
+    Json(Arc<AirdropMerkleTree>),
+    Binary(BinaryMerkleTree),
+}
+
+impl ServedTree {
+    /// Maps a `.bin` file as a binary tree, reads any other file as a JSON tree and indexes its
+    /// claimants
+    pub fn load(path: &PathBuf) -> airdrop_merkle_tree::Result<Self> {
+        if path.extension().is_some_and(|extension| extension == "bin") {
+            Ok(ServedTree::Binary(BinaryMerkleTree::open(path)?))
+        } else {
+            let mut tree = AirdropMerkleTree::new_from_file(path)?;
+            tree.build_claimant_index();
+            Ok(ServedTree::Json(Arc::new(tree)))
+        }
+    }
+
+    pub fn airdrop_version(&self) -> u64 {
+        match self {
+            ServedTree::Json(tree) => tree.airdrop_version,
+            ServedTree::Binary(tree) => tree.header().airdrop_version,
+        }
+    }
+
+    pub fn max_num_nodes(&self) -> u64 {
+        match self {
+            ServedTree::Json(tree) => tree.max_num_nodes,
+            ServedTree::Binary(tree) => tree.header().max_num_nodes,
+        }
+    }
+
+    pub fn hash_type(&self) -> HashType {
+        match self {
+            ServedTree::Json(tree) => tree.hash_type,
+            ServedTree::Binary(tree) => tree.header().hash_type,
+        }
+    }
+
+    pub fn merkle_root(&self) -> [u8; 32] {
+        match self {
+            ServedTree::Json(tree) => tree.merkle_root,
+            ServedTree::Binary(tree) => tree.header().merkle_root,
+        }
+    }
+
+    pub fn get_max_total_claim(&self) -> u64 {
+        match self {
+            ServedTree::Json(tree) => tree.get_max_total_claim(),
+            ServedTree::Binary(tree) => tree
+                .header()
+                .total_unlocked_amount
+                .checked_add(tree.header().total_locked_amount)
+                .unwrap(),
+        }
+    }
+
+    pub fn num_nodes(&self) -> usize {
+        match self {
+            ServedTree::Json(tree) => tree.tree_nodes.len(),
+            ServedTree::Binary(tree) => tree.num_nodes(),
+        }
+    }
+
+    /// Leaf index of the claimant, binary searched in the claimant index of a binary tree
+    pub fn find_index(&self, claimant: &Pubkey) -> Option<usize> {
+        match self {
+            ServedTree::Json(tree) => tree.get_index(claimant),
+            ServedTree::Binary(tree) => tree.find_index(claimant),
+        }
+    }
+
+    pub fn get_node_at(&self, index: usize) -> Option<TreeNode> {
+        match self {
+            ServedTree::Json(tree) => tree.tree_nodes.get(index).cloned(),
+            ServedTree::Binary(tree) => tree.get_node_at(index),
+        }
+    }
+
+    pub fn proof_for_index(&self, index: usize) -> Option<Vec<[u8; 32]>> {
+        match self {
+            ServedTree::Json(tree) => tree.proof_for_index(index),
+            ServedTree::Binary(tree) => tree.proof_for_index(index),
+        }
+    }
+}
+
+/// File of the local claim record index of a compressed distributor
+pub struct CompressedTree {
+    pub index_path: PathBuf,
+    /// Claim record tree rebuilt from the index, with the modification time of the index file
+    pub cache: Mutex<Option<(SystemTime, Arc<(ClaimRecordIndex, ClaimRecordMerkleTree)>)>>,
+}
+
+impl CompressedTree {
+    pub fn new(index_path: PathBuf) -> Self {
+        Self {
+            index_path,
+            cache: Mutex::new(None),
+        }
+    }
+
+    /// Returns the claim record tree of the latest index written by the indexer
+    fn get_claim_record_tree(&self) -> Result<Arc<(ClaimRecordIndex, ClaimRecordMerkleTree)>> {
+        let modified = fs::metadata(&self.index_path)
+            .and_then(|metadata| metadata.modified())
+            .map_err(|_| ApiError::ClaimRecordsNotFound(self.index_path.display().to_string()))?;
+
+        let mut cache = self.cache.lock().map_err(|_| ApiError::InternalError)?;
+        if let Some((cached_at, claim_record_tree)) = cache.as_ref() {
+            if *cached_at == modified {
+                return Ok(claim_record_tree.clone());
+            }
+        }
+
+        let index = ClaimRecordIndex::new_from_file(&self.index_path)?;
+        let claim_record_tree = ClaimRecordMerkleTree::new_from_index(&index);
+        let claim_record_tree = Arc::new((index, claim_record_tree));
+        *cache = Some((modified, claim_record_tree.clone()));
+        Ok(claim_record_tree)
+    }
+}
+
+impl RouterState {
+    /// Distributor, tree and leaf index of the claimant, the last served tree wins if the
+    /// claimant is in several
+    fn find_claimant(&self, claimant: &Pubkey) -> Option<(Pubkey, &ServedTree, usize)> {
+        self.merkle_trees
+            .iter()
+            .rev()
+            .find_map(|(distributor, merkle_tree)| {
+                let index = merkle_tree.find_index(claimant)?;
+                Some((*distributor, merkle_tree.as_ref(), index))
+            })
+    }
 }
 
 impl Debug for RouterState {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("RouterState")
             .field("program_id", &self.program_id)
-            .field("tree", &self.tree.len())
+            .field("merkle_trees", &self.merkle_trees.len())
+            .field("compressed_trees", &self.compressed_trees.len())
             .finish()
     }
 }
@@ -69,7 +221,8 @@ pub fn get_routes(state: Arc<RouterState>) -> Router {
     let router = Router::new()
         .route("/", get(root))
         .route("/distributors", get(get_distributors))
-        .route("/user/:user_pubkey", get(get_user_info));
+        .route("/user/:user_pubkey", get(get_user_info))
+        .route("/record/:user_pubkey", get(get_user_record));
 
     router.layer(middleware).with_state(state)
 }
@@ -80,27 +233,95 @@ async fn get_user_info(
     State(state): State<Arc<RouterState>>,
     Path(user_pubkey): Path<String>,
 ) -> Result<impl IntoResponse> {
-    let merkle_tree = &state.tree;
-
     let user_pubkey: Pubkey = Pubkey::from_str(user_pubkey.as_str())?;
-    let node = merkle_tree
-        .get(&user_pubkey)
+    let (distributor, merkle_tree, index) = state
+        .find_claimant(&user_pubkey)
         .ok_or(ApiError::UserNotFound(user_pubkey.to_string()))?;
+    let node = merkle_tree
+        .get_node_at(index)
+        .ok_or(ApiError::InternalError)?;
 
     let proof = UserProof {
-        merkle_tree: node.0.to_string(),
-        amount: node.1.unlocked_amount(),
-        locked_amount: node.1.locked_amount(),
-        proof: node
-            .1
-            .proof
-            .to_owned()
+        merkle_tree: distributor.to_string(),
+        amount: node.unlocked_amount(),
+        locked_amount: node.locked_amount(),
+        proof: merkle_tree
+            .proof_for_index(index)
             .ok_or(ApiError::ProofNotFound(user_pubkey.to_string()))?,
     };
 
     Ok(Json(proof))
 }
 
+#[derive(Serialize, Deserialize, Debug)]
+pub struct UserRecordProof {
+    /// merkle tree that user belongs
+    pub merkle_tree: String,
+    /// claim record tree of the distributor
+    pub claim_record_tree: String,
+    /// unlocked amount
+    pub amount: u64,
+    /// locked amount
+    pub locked_amount: u64,
+    /// index of the user record, the path of the user leaf in the airdrop merkle tree
+    pub index: u32,
+    /// root the proof is built against
+    pub root: [u8; 32],
+    /// proof of the user record in the claim record tree, up to the canopy
+    pub proof: Vec<[u8; 32]>,
+    /// proof of the user leaf in the airdrop merkle tree, for a new claim
+    pub airdrop_proof: Vec<[u8; 32]>,
+    /// claim record, none if the user has not claimed yet
+    pub record: Option<ClaimRecord>,
+}
+
+/// Retrieve the claim record proof of a user of a compressed distributor
+#[instrument(ret)]
+async fn get_user_record(
+    State(state): State<Arc<RouterState>>,
+    Path(user_pubkey): Path<String>,
+) -> Result<impl IntoResponse> {
+    let user_pubkey: Pubkey = Pubkey::from_str(user_pubkey.as_str())?;
+    let (distributor, merkle_tree, index) = state
+        .find_claimant(&user_pubkey)
+        .ok_or(ApiError::UserNotFound(user_pubkey.to_string()))?;
+
+    let compressed_tree = state
+        .compressed_trees
+        .get(&distributor)
+        .ok_or(ApiError::ClaimRecordsNotFound(distributor.to_string()))?;
+    let node = merkle_tree
+        .get_node_at(index)
+        .ok_or(ApiError::InternalError)?;
+    let airdrop_proof = merkle_tree
+        .proof_for_index(index)
+        .ok_or(ApiError::ProofNotFound(user_pubkey.to_string()))?;
+    let record_index = get_record_index(
+        &node,
+        airdrop_proof.clone(),
+        merkle_tree.merkle_root(),
+        merkle_tree.hash_type(),
+    )
+    .ok_or(ApiError::ProofNotFound(user_pubkey.to_string()))?;
+
+    let claim_record_tree = compressed_tree.get_claim_record_tree()?;
+    let (claim_record_index, claim_record_tree) = claim_record_tree.as_ref();
+
+    let proof = UserRecordProof {
+        merkle_tree: distributor.to_string(),
+        claim_record_tree: claim_record_index.claim_record_tree.to_string(),
+        amount: node.unlocked_amount(),
+        locked_amount: node.locked_amount(),
+        index: record_index,
+        root: claim_record_tree.get_root(),
+        proof: claim_record_tree.get_claim_proof(record_index as usize),
+        airdrop_proof,
+        record: claim_record_index.get_record(record_index).cloned(),
+    };
+
+    Ok(Json(proof))
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct SingleDistributor {
     pub distributor_pubkey: String,
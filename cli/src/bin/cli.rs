@@ -20,9 +20,18 @@ use csv::Writer;
 use jito_merkle_tree::{
     airdrop_merkle_tree::AirdropMerkleTree,
     csv_entry::CsvEntry,
-    utils::{get_claim_status_pda, get_merkle_distributor_pda},
+    hasher::{parse_hash_type, HashType},
+    merge_policy::MergePolicy,
+    tree_node::DecimalMode,
+    utils::{
+        get_bonus_vault_pda, get_claim_status_pda, get_denylist_pda, get_merkle_distributor_pda,
+        get_pending_action_pda,
+    },
+};
+use merkle_distributor::state::{
+    merkle_distributor::{ClaimFeeType, MerkleDistributor},
+    registry::{Registry, RegistryEntry},
 };
-use merkle_distributor::state::merkle_distributor::MerkleDistributor;
 use solana_program::{clock::DEFAULT_MS_PER_SLOT, instruction::Instruction};
 use solana_rpc_client::rpc_client::{RpcClient, SerializableTransaction};
 use solana_sdk::{
@@ -93,7 +102,7 @@ pub enum Commands {
 
     CloseDistributor(CloseDistributorArgs),
     CloseClaimStatus(CloseClaimStatusArgs),
-    /// Clawback tokens from merkle distributor
+    /// Clawback tokens, and the bonus vault if any, from merkle distributor
     #[clap(hide = true)]
     Clawback(ClawbackArgs),
     /// Create a Merkle tree, given a CSV of recipients
@@ -102,6 +111,8 @@ pub enum Commands {
 
     SetActivationPoint(SetActivationArgs),
     SetActivationSlotByTime(SetActivationSlotByTimeArgs),
+    /// Set cap on new claims or claimed amount per window
+    SetClaimCap(SetClaimCapArgs),
 
     CreateTestList(CreateTestListArgs),
     CreateDummyCsv(CreateDummyCsv),
@@ -126,6 +137,56 @@ pub enum Commands {
 
     ViewDistributors(ViewDistributorsArgs),
     ViewDistributorByPubkey(ViewDistributorByPubkeyArgs),
+
+    /// Queue a timelocked admin action
+    QueueAction(QueueActionArgs),
+    /// Execute a queued admin action after the timelock delay
+    ExecuteAction(PendingActionArgs),
+    /// Cancel a queued admin action
+    CancelAction(PendingActionArgs),
+    /// View queued admin actions of a distributor
+    ViewPendingActions(ViewPendingActionsArgs),
+
+    /// Create the denylist of a distributor
+    NewDenylist(NewDenylistArgs),
+    /// Add a claimant to the denylist of a distributor
+    AddToDenylist(DenylistClaimantArgs),
+    /// Remove a claimant from the denylist of a distributor
+    RemoveFromDenylist(DenylistClaimantArgs),
+    /// View denylisted claimants of a distributor
+    ViewDenylist(NewDenylistArgs),
+
+    /// Revoke the unvested locked tokens of a claimant
+    RevokeVesting(RevokeVestingArgs),
+
+    /// Migrate distributors to the current account layout
+    MigrateDistributor(MigrateDistributorArgs),
+
+    /// Create the bonus vault of a distributor, before any claim
+    NewBonusVault(NewBonusVaultArgs),
+
+    /// Set the relayer paying claim status rent on behalf of claimants
+    SetRelayer(SetRelayerArgs),
+
+    /// Create the claim record tree of a distributor, before any claim, claims are then compressed
+    NewClaimRecordTree(NewClaimRecordTreeArgs),
+    /// Index the claim records of a compressed distributor into a local file
+    IndexClaimRecords(IndexClaimRecordsArgs),
+
+    /// Convert json merkle trees to the compact binary format
+    TreeToBinary(TreeToBinaryArgs),
+    /// Convert binary merkle trees back to json
+    BinaryToTree(BinaryToTreeArgs),
+
+    /// Compare two merkle trees, or two directories of trees, by claimant
+    DiffTrees(DiffTreesArgs),
+
+    /// Claim several leaves whose claimants are token accounts of the signer, with a multiproof
+    ClaimBatch(ClaimBatchArgs),
+
+    /// Verify the signed manifest of a tree against the tree, and optionally the csv and the
+    /// distributor
+    VerifyManifest(VerifyManifestArgs),
 }
 
 #[derive(Parser, Debug)]
@@ -145,6 +206,9 @@ pub struct ClaimArgs {
     /// Merkle distributor path
     #[clap(long, env)]
     pub merkle_tree_path: PathBuf,
+    /// Directory of the local claim record indexes, required by compressed distributors
+    #[clap(long, env)]
+    pub claim_records_path: Option<PathBuf>,
 }
 
 #[derive(Parser, Debug)]
@@ -207,16 +271,34 @@ pub struct VerifyArgs {
 
     #[clap(long, env)]
     pub locker: Pubkey,
+
+    #[clap(long, env, default_value_t = 0)]
+    pub min_lock_duration: u32,
+
+    #[clap(long, env, default_value_t = 0)]
+    pub timelock_delay: u32,
+
+    #[clap(long, env, default_value_t = 0)]
+    pub claim_fee_type: u8,
+
+    #[clap(long, env, default_value_t = 0)]
+    pub claim_fee: u64,
+
+    #[clap(long, env, default_value_t = Pubkey::default())]
+    pub fee_receiver: Pubkey,
+
+    #[clap(long, env, default_value_t = 0)]
+    pub vesting_type: u8,
 }
 
 // NewDistributor subcommand args
 #[derive(Parser, Debug)]
 pub struct NewDistributorArgs {
-    /// Lockup timestamp start
+    /// Lockup timestamp start, or slot with slot vesting
     #[clap(long, env)]
     pub start_vesting_ts: i64,
 
-    /// Lockup timestamp end (unix timestamp)
+    /// Lockup timestamp end (unix timestamp), or slot with slot vesting
     #[clap(long, env)]
     pub end_vesting_ts: i64,
 
@@ -265,14 +347,44 @@ pub struct NewDistributorArgs {
 
     #[clap(long, env)]
     pub locker: Pubkey,
+
+    /// Minimum remaining lock duration (seconds) of the escrow when claim and stake, 0 requires max lock
+    #[clap(long, env, default_value_t = 0)]
+    pub min_lock_duration: u32,
+
+    /// Delay (seconds) before queued admin actions can be executed, 0 disables the timelock
+    #[clap(long, env, default_value_t = 0)]
+    pub timelock_delay: u32,
+
+    /// Claim fee type, 0 no fee, 1 lamports, 2 basis points of claimed tokens
+    #[clap(long, env, default_value_t = 0)]
+    pub claim_fee_type: u8,
+
+    /// Claim fee, in lamports or basis points depending on claim fee type
+    #[clap(long, env, default_value_t = 0)]
+    pub claim_fee: u64,
+
+    /// Fee receiver wallet, token fees are paid to its ATA
+    #[clap(long, env, default_value_t = Pubkey::default())]
+    pub fee_receiver: Pubkey,
+
+    /// Vesting type, 0 timestamp, 1 slot (use slot-by-time to get the vesting slots)
+    #[clap(long, env, default_value_t = 0)]
+    pub vesting_type: u8,
+
+    /// Hold the bonus in a separate bonus vault, funded and clawed back independently
+    #[clap(long, env)]
+    pub bonus_vault: bool,
 }
 
 #[derive(Parser, Debug)]
 pub struct ClawbackArgs {
+    /// First version, read from the registry when a bound is omitted
     #[clap(long, env)]
-    pub from_version: u64,
+    pub from_version: Option<u64>,
+    /// Last version, read from the registry when a bound is omitted
     #[clap(long, env)]
-    pub to_version: u64,
+    pub to_version: Option<u64>,
 }
 
 #[derive(Parser, Debug)]
@@ -296,28 +408,74 @@ pub struct CreateMerkleTreeArgs {
     pub amount: u64,
     #[clap(long, env)]
     pub decimals: u32,
+
+    /// strict rejects amounts with more decimals than the mint, floor floors them and reports
+    /// the dust lost
+    #[clap(long, env, default_value = "strict")]
+    pub decimal_mode: DecimalMode,
+
+    /// How the rows of the same claimant are merged: sum, max, first, last, or error to reject
+    /// the list
+    #[clap(long, env, default_value = "sum")]
+    pub merge_policy: MergePolicy,
+
+    /// Hash function of the trees, keccak256 for trees also published on EVM chains
+    #[clap(long, env, default_value = "sha256", parse(try_from_str = parse_hash_type))]
+    pub hash_type: HashType,
+
+    /// Write the csv report to this path, as json with the dust if it ends with .json else the
+    /// invalid rows as csv. Invalid rows are printed when omitted
+    #[clap(long, env)]
+    pub report_path: Option<PathBuf>,
+
+    /// Write a manifest of each tree, signed by the keypair as admin, to this directory. The
+    /// test tree has no manifest, it isn't built from the csv
+    #[clap(long, env)]
+    pub manifest_path: Option<PathBuf>,
 }
 
 #[derive(Parser, Debug)]
 pub struct SetAdminArgs {
     #[clap(long, env)]
     pub new_admin: Pubkey,
+    /// First version, read from the registry when a bound is omitted
     #[clap(long, env)]
-    pub from_version: u64,
+    pub from_version: Option<u64>,
+    /// Last version, read from the registry when a bound is omitted
     #[clap(long, env)]
-    pub to_version: u64,
+    pub to_version: Option<u64>,
 }
 
 #[derive(Parser, Debug)]
 pub struct SetActivationArgs {
+    /// First version, read from the registry when a bound is omitted
     #[clap(long, env)]
-    pub from_version: u64,
+    pub from_version: Option<u64>,
+    /// Last version, read from the registry when a bound is omitted
     #[clap(long, env)]
-    pub to_version: u64,
+    pub to_version: Option<u64>,
     #[clap(long, env)]
     pub activation_point: u64, // can be timestamp or slot
 }
 
+#[derive(Parser, Debug)]
+pub struct SetClaimCapArgs {
+    /// First version, read from the registry when a bound is omitted
+    #[clap(long, env)]
+    pub from_version: Option<u64>,
+    /// Last version, read from the registry when a bound is omitted
+    #[clap(long, env)]
+    pub to_version: Option<u64>,
+    /// 0 no cap, 1 number of new claims, 2 claimed amount
+    #[clap(long, env)]
+    pub claim_cap_type: u8,
+    #[clap(long, env, default_value_t = 0)]
+    pub claim_cap: u64,
+    /// Window length, in slots or seconds depending on activation type
+    #[clap(long, env, default_value_t = 0)]
+    pub claim_window_length: u32,
+}
+
 #[derive(Parser, Debug)]
 pub struct SetActivationSlotByTimeArgs {
     /// Merkle tree out path
@@ -333,6 +491,12 @@ pub struct SetActivationSlotByTimeArgs {
 pub struct SlotByTimeArgsArgs {
     #[clap(long, env)]
     pub timestamp: u64,
+    /// Lockup timestamp start, to get the start slot of a slot vesting
+    #[clap(long, env)]
+    pub start_vesting_ts: Option<u64>,
+    /// Lockup timestamp end, to get the end slot of a slot vesting
+    #[clap(long, env)]
+    pub end_vesting_ts: Option<u64>,
 }
 
 #[derive(Parser, Debug)]
@@ -482,10 +646,12 @@ pub struct ClawbackReceiverArgs {
 
 #[derive(Parser, Debug)]
 pub struct ViewDistributorsArgs {
+    /// First version, read from the registry when a bound is omitted
     #[clap(long, env)]
-    pub from_version: u64,
+    pub from_version: Option<u64>,
+    /// Last version, read from the registry when a bound is omitted
     #[clap(long, env)]
-    pub to_version: u64,
+    pub to_version: Option<u64>,
 }
 
 #[derive(Parser, Debug)]
@@ -494,6 +660,166 @@ pub struct ViewDistributorByPubkeyArgs {
     pub pubkey: Pubkey,
 }
 
+#[derive(Parser, Debug)]
+pub struct QueueActionArgs {
+    #[clap(long, env)]
+    pub airdrop_version: u64,
+    /// 0 set activation point, 1 set clawback receiver, 2 set operator, 3 set admin, 4 set timelock delay, 5 set relayer, 6 set claim cap
+    #[clap(long, env)]
+    pub action_type: u8,
+    /// New clawback receiver owner, operator or admin
+    #[clap(long, env, default_value_t = Pubkey::default())]
+    pub new_pubkey: Pubkey,
+    /// New activation point, timelock delay or claim cap
+    #[clap(long, env, default_value_t = 0)]
+    pub new_value: u64,
+    /// New claim cap type for set claim cap, 0 no cap, 1 number of new claims, 2 claimed amount
+    #[clap(long, env, default_value_t = 0)]
+    pub claim_cap_type: u8,
+    /// New claim window length for set claim cap, in slots or seconds depending on activation type
+    #[clap(long, env, default_value_t = 0)]
+    pub claim_window_length: u32,
+}
+
+#[derive(Parser, Debug)]
+pub struct PendingActionArgs {
+    #[clap(long, env)]
+    pub pending_action: Pubkey,
+}
+
+#[derive(Parser, Debug)]
+pub struct ViewPendingActionsArgs {
+    #[clap(long, env)]
+    pub airdrop_version: u64,
+}
+
+#[derive(Parser, Debug)]
+pub struct NewDenylistArgs {
+    #[clap(long, env)]
+    pub airdrop_version: u64,
+}
+
+#[derive(Parser, Debug)]
+pub struct NewBonusVaultArgs {
+    #[clap(long, env)]
+    pub airdrop_version: u64,
+}
+
+#[derive(Parser, Debug)]
+pub struct SetRelayerArgs {
+    #[clap(long, env)]
+    pub airdrop_version: u64,
+    /// Relayer, default pubkey to remove it
+    #[clap(long, env)]
+    pub relayer: Pubkey,
+}
+
+#[derive(Parser, Debug)]
+pub struct NewClaimRecordTreeArgs {
+    /// Merkle distributor path
+    #[clap(long, env)]
+    pub merkle_tree_path: PathBuf,
+    /// Levels of the tree stored on-chain to shorten the record proofs, by default the deepest
+    /// one leaving 3 nodes to the proofs
+    #[clap(long, env)]
+    pub canopy_depth: Option<u32>,
+}
+
+#[derive(Parser, Debug)]
+pub struct IndexClaimRecordsArgs {
+    #[clap(long, env)]
+    pub airdrop_version: u64,
+    /// Directory of the local claim record indexes, one file per distributor
+    #[clap(long, env)]
+    pub claim_records_path: PathBuf,
+}
+
+#[derive(Parser, Debug)]
+pub struct TreeToBinaryArgs {
+    /// Json merkle tree, or directory of trees
+    #[clap(long, env)]
+    pub merkle_tree_path: PathBuf,
+    /// Binary merkle tree out path, a directory if merkle_tree_path is one
+    #[clap(long, env)]
+    pub binary_path: PathBuf,
+}
+
+#[derive(Parser, Debug)]
+pub struct BinaryToTreeArgs {
+    /// Binary merkle tree, or directory of trees
+    #[clap(long, env)]
+    pub binary_path: PathBuf,
+    /// Json merkle tree out path, a directory if binary_path is one
+    #[clap(long, env)]
+    pub merkle_tree_path: PathBuf,
+}
+
+#[derive(Parser, Debug)]
+pub struct DiffTreesArgs {
+    /// Old merkle tree, or directory of trees
+    #[clap(long, env)]
+    pub old_path: PathBuf,
+    /// New merkle tree, or directory of trees
+    #[clap(long, env)]
+    pub new_path: PathBuf,
+    /// Json report of the added, removed and changed claimants
+    #[clap(long, env)]
+    pub report_path: Option<PathBuf>,
+}
+
+#[derive(Parser, Debug)]
+pub struct ClaimBatchArgs {
+    /// Merkle distributor path
+    #[clap(long, env)]
+    pub merkle_tree_path: PathBuf,
+    /// Claimants of the tree, comma separated. The signer must own or be the delegate of their
+    /// associated token accounts, which receive the claimed tokens
+    #[clap(long, env, use_value_delimiter = true)]
+    pub claimants: Vec<Pubkey>,
+}
+
+#[derive(Parser, Debug)]
+pub struct VerifyManifestArgs {
+    /// Signed manifest path
+    #[clap(long, env)]
+    pub manifest_path: PathBuf,
+    /// Merkle tree path
+    #[clap(long, env)]
+    pub merkle_tree_path: PathBuf,
+    /// Csv the tree was built from
+    #[clap(long, env)]
+    pub csv_path: Option<PathBuf>,
+    /// Also check the on-chain distributor of the tree
+    #[clap(long, env)]
+    pub on_chain: bool,
+}
+
+#[derive(Parser, Debug)]
+pub struct DenylistClaimantArgs {
+    #[clap(long, env)]
+    pub airdrop_version: u64,
+    #[clap(long, env)]
+    pub claimant: Pubkey,
+}
+
+#[derive(Parser, Debug)]
+pub struct RevokeVestingArgs {
+    #[clap(long, env)]
+    pub airdrop_version: u64,
+    #[clap(long, env)]
+    pub claimant: Pubkey,
+}
+
+#[derive(Parser, Debug)]
+pub struct MigrateDistributorArgs {
+    /// First version, read from the registry when a bound is omitted
+    #[clap(long, env)]
+    pub from_version: Option<u64>,
+    /// Last version, read from the registry when a bound is omitted
+    #[clap(long, env)]
+    pub to_version: Option<u64>,
+}
+
 fn main() {
     let args = Args::parse();
 
@@ -512,7 +838,7 @@ fn main() {
         }
         Commands::Clawback(clawback_args) => process_clawback(&args, clawback_args),
         Commands::CreateMerkleTree(merkle_tree_args) => {
-            process_create_merkle_tree(merkle_tree_args);
+            process_create_merkle_tree(&args, merkle_tree_args);
         }
         Commands::SetAdmin(set_admin_args) => {
             process_set_admin(&args, set_admin_args);
@@ -523,6 +849,9 @@ fn main() {
         Commands::SetActivationSlotByTime(sub_args) => {
             process_set_activation_slot_by_time(&args, sub_args);
         }
+        Commands::SetClaimCap(sub_args) => {
+            process_set_claim_cap(&args, sub_args);
+        }
         Commands::CreateDummyCsv(test_args) => {
             process_create_dummy_csv(test_args);
         }
@@ -570,9 +899,102 @@ fn main() {
         Commands::SetClawbackReceiver(set_clawback_receiver_argrs) => {
             process_set_clawback_receiver(&args, set_clawback_receiver_argrs)
         }
+        Commands::QueueAction(sub_args) => process_queue_action(&args, sub_args),
+        Commands::ExecuteAction(sub_args) => process_execute_action(&args, sub_args),
+        Commands::CancelAction(sub_args) => process_cancel_action(&args, sub_args),
+        Commands::ViewPendingActions(sub_args) => view_pending_actions(&args, sub_args),
+        Commands::NewDenylist(sub_args) => process_new_denylist(&args, sub_args),
+        Commands::AddToDenylist(sub_args) => process_add_to_denylist(&args, sub_args),
+        Commands::RemoveFromDenylist(sub_args) => process_remove_from_denylist(&args, sub_args),
+        Commands::ViewDenylist(sub_args) => view_denylist(&args, sub_args),
+        Commands::RevokeVesting(sub_args) => process_revoke_vesting(&args, sub_args),
+        Commands::MigrateDistributor(sub_args) => process_migrate_distributor(&args, sub_args),
+        Commands::NewBonusVault(sub_args) => process_new_bonus_vault(&args, sub_args),
+        Commands::SetRelayer(sub_args) => process_set_relayer(&args, sub_args),
+        Commands::NewClaimRecordTree(sub_args) => process_new_claim_record_tree(&args, sub_args),
+        Commands::IndexClaimRecords(sub_args) => process_index_claim_records(&args, sub_args),
+        Commands::TreeToBinary(sub_args) => process_tree_to_binary(sub_args),
+        Commands::BinaryToTree(sub_args) => process_binary_to_tree(sub_args),
+        Commands::DiffTrees(sub_args) => process_diff_trees(sub_args),
+        Commands::ClaimBatch(sub_args) => process_claim_batch(&args, sub_args),
+        Commands::VerifyManifest(sub_args) => process_verify_manifest(&args, sub_args),
     }
 }
 
+/// Returns the fee receiver account expected by the claim instructions of the distributor
+fn get_fee_receiver_account(distributor: &MerkleDistributor) -> Option<Pubkey> {
+    match ClaimFeeType::try_from(distributor.claim_fee_type).unwrap() {
+        ClaimFeeType::None => None,
+        ClaimFeeType::Lamports => Some(distributor.fee_receiver),
+        ClaimFeeType::Bps => Some(get_associated_token_address(
+            &distributor.fee_receiver,
+            &distributor.mint,
+        )),
+    }
+}
+
+/// Returns the denylist account expected by the claim instructions of the distributor
+fn get_denylist_account(
+    program_id: &Pubkey,
+    distributor: &Pubkey,
+    distributor_state: &MerkleDistributor,
+) -> Option<Pubkey> {
+    if distributor_state.has_denylist() {
+        Some(get_denylist_pda(program_id, distributor).0)
+    } else {
+        None
+    }
+}
+
+/// Returns the bonus vault account expected by the new claim instructions of the distributor
+fn get_bonus_vault_account(distributor_state: &MerkleDistributor) -> Option<Pubkey> {
+    if distributor_state.has_bonus_vault() {
+        Some(distributor_state.bonus_vault)
+    } else {
+        None
+    }
+}
+
+/// Returns the (version, distributor) of the base and mint. A full version range is derived
+/// as is, which also covers distributors created before the registry. Otherwise versions
+/// are read from the on-chain registry, within the given bound if any.
+fn get_distributors(
+    client: &RpcClient,
+    args: &Args,
+    from_version: Option<u64>,
+    to_version: Option<u64>,
+) -> Vec<(u64, Pubkey)> {
+    if let (Some(from_version), Some(to_version)) = (from_version, to_version) {
+        return (from_version..=to_version)
+            .map(|version| {
+                let (distributor, _bump) =
+                    get_merkle_distributor_pda(&args.program_id, &args.base, &args.mint, version);
+                (version, distributor)
+            })
+            .collect();
+    }
+    get_registry_entries(client, args)
+        .expect("registry not found, pass --from-version and --to-version")
+        .iter()
+        .filter(|entry| {
+            from_version.map_or(true, |from| entry.version >= from)
+                && to_version.map_or(true, |to| entry.version <= to)
+        })
+        .map(|entry| (entry.version, entry.distributor))
+        .collect()
+}
+
+/// Returns the entries of the registry of the base and mint, if it exists
+fn get_registry_entries(client: &RpcClient, args: &Args) -> Option<Vec<RegistryEntry>> {
+    let (registry, _bump) = get_registry_pda(&args.program_id, &args.base, &args.mint);
+    let account = client
+        .get_account_with_commitment(&registry, CommitmentConfig::confirmed())
+        .unwrap()
+        .value?;
+    let (_registry, entries) = Registry::read_unaligned(&account.data).unwrap();
+    Some(entries)
+}
+
 fn check_distributor_onchain_matches(
     account: &Account,
     merkle_tree: &AirdropMerkleTree,
@@ -641,6 +1063,48 @@ fn check_distributor_onchain_matches(
             return Err("locker mismatch");
         }
 
+        if distributor.min_lock_duration != new_distributor_args.min_lock_duration {
+            return Err("min_lock_duration mismatch");
+        }
+
+        if distributor.timelock_delay != new_distributor_args.timelock_delay {
+            return Err("timelock_delay mismatch");
+        }
+
+        if distributor.claim_fee_type != new_distributor_args.claim_fee_type {
+            return Err("claim_fee_type mismatch");
+        }
+
+        if distributor.claim_fee != new_distributor_args.claim_fee {
+            return Err("claim_fee mismatch");
+        }
+
+        if distributor.fee_receiver != new_distributor_args.fee_receiver {
+            return Err("fee_receiver mismatch");
+        }
+
+        if distributor.vesting_type != new_distributor_args.vesting_type {
+            return Err("vesting_type mismatch");
+        }
+
+        if distributor.hash_type != u8::from(merkle_tree.hash_type) {
+            return Err("hash_type mismatch");
+        }
+
+        if new_distributor_args.bonus_vault && total_bonus > 0 {
+            let (distributor_pubkey, _bump) = get_merkle_distributor_pda(
+                &args.program_id,
+                &base,
+                &args.mint,
+                merkle_tree.airdrop_version,
+            );
+            if distributor.bonus_vault
+                != get_bonus_vault_pda(&args.program_id, &distributor_pubkey).0
+            {
+                return Err("bonus_vault mismatch");
+            }
+        }
+
         // TODO fix code
         let clawback_receiver_token_account =
             spl_associated_token_account::get_associated_token_address(
@@ -46,3 +46,31 @@ pub mod view_distributor;
 pub use view_distributor::*;
 pub mod process_claim_from_api;
 pub use process_claim_from_api::*;
+pub mod process_timelock;
+pub use process_timelock::*;
+pub mod process_set_claim_cap;
+pub use process_set_claim_cap::*;
+pub mod process_denylist;
+pub use process_denylist::*;
+pub mod process_revoke_vesting;
+pub use process_revoke_vesting::*;
+pub mod process_migrate_distributor;
+pub use process_migrate_distributor::*;
+pub mod process_new_bonus_vault;
+pub use process_new_bonus_vault::*;
+pub mod process_set_relayer;
+pub use process_set_relayer::*;
+pub mod process_claim_compressed;
+pub mod process_index_claim_records;
+pub mod process_new_claim_record_tree;
+pub use process_claim_compressed::*;
+pub use process_index_claim_records::*;
+pub use process_new_claim_record_tree::*;
+pub mod process_convert_tree;
+pub use process_convert_tree::*;
+pub mod process_diff_trees;
+pub use process_diff_trees::*;
+pub mod process_claim_batch;
+pub use process_claim_batch::*;
+pub mod process_verify_manifest;
+pub use process_verify_manifest::*;
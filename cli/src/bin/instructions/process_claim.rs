@@ -9,6 +9,13 @@ pub fn process_claim(args: &Args, claim_args: &ClaimArgs) {
 
     let merkle_tree = AirdropMerkleTree::new_from_file(&claim_args.merkle_tree_path)
         .expect("failed to load merkle tree from file");
+    if merkle_tree.get_node(&claimant).is_none() {
+        println!(
+            "{} is not eligible for airdrop version {}",
+            claimant, merkle_tree.airdrop_version
+        );
+        return;
+    }
 
     let (distributor, bump) = get_merkle_distributor_pda(
         &args.program_id,
@@ -21,6 +28,19 @@ pub fn process_claim(args: &Args, claim_args: &ClaimArgs) {
     let (claim_status_pda, _bump) = get_claim_status_pda(&args.program_id, &claimant, &distributor);
     println!("claim pda: {claim_status_pda}, bump: {bump}");
 
+    let program = args.get_program_client();
+    let distributor_state = program.account::<MerkleDistributor>(distributor).unwrap();
+    if distributor_state.is_compressed() {
+        process_claim_compressed(
+            args,
+            claim_args,
+            &merkle_tree,
+            &distributor,
+            &distributor_state,
+        );
+        return;
+    }
+
     let client = RpcClient::new_with_commitment(&args.rpc_url, CommitmentConfig::confirmed());
 
     match client.get_account(&claim_status_pda) {
@@ -56,6 +76,9 @@ pub fn process_claim(args: &Args, claim_args: &ClaimArgs) {
             claimant,
             token_program: token::ID,
             operator: None,
+            system_program: Some(solana_program::system_program::ID),
+            fee_receiver: get_fee_receiver_account(&distributor_state),
+            denylist: get_denylist_account(&args.program_id, &distributor, &distributor_state),
         }
         .to_account_metas(None),
         data: merkle_distributor::instruction::ClaimLocked {}.data(),
@@ -0,0 +1,112 @@
+use merkle_distributor::instructions::BatchClaimLeaf;
+use solana_program::instruction::AccountMeta;
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+
+use crate::*;
+
+pub fn process_claim_batch(args: &Args, claim_batch_args: &ClaimBatchArgs) {
+    let keypair = read_keypair_file(&args.keypair_path.clone().unwrap())
+        .expect("Failed reading keypair file");
+    let claimant = keypair.pubkey();
+
+    let merkle_tree = AirdropMerkleTree::new_from_file(&claim_batch_args.merkle_tree_path)
+        .expect("failed to load merkle tree from file");
+
+    let (distributor, _bump) = get_merkle_distributor_pda(
+        &args.program_id,
+        &args.base,
+        &args.mint,
+        merkle_tree.airdrop_version,
+    );
+
+    let mut indices = vec![];
+    for (leaf_claimant, index) in claim_batch_args
+        .claimants
+        .iter()
+        .zip(merkle_tree.get_indices(&claim_batch_args.claimants))
+    {
+        match index {
+            Some(index) => indices.push(index),
+            None => {
+                println!(
+                    "{} is not eligible for airdrop version {}",
+                    leaf_claimant, merkle_tree.airdrop_version
+                );
+                return;
+            }
+        }
+    }
+    // the leaves of a multiproof are in the order of the tree
+    indices.sort_unstable();
+    indices.dedup();
+    println!("Claiming {} leaves for {}...", indices.len(), claimant);
+
+    let (proof, proof_flags) = merkle_tree
+        .multiproof_for_indices(&indices)
+        .expect("multiproof not found");
+
+    let client = RpcClient::new_with_commitment(&args.rpc_url, CommitmentConfig::confirmed());
+    let program = args.get_program_client();
+    let distributor_state = program.account::<MerkleDistributor>(distributor).unwrap();
+
+    let mut ixs = vec![];
+
+    // check priority fee
+    if let Some(priority_fee) = args.priority_fee {
+        ixs.push(ComputeBudgetInstruction::set_compute_unit_price(
+            priority_fee,
+        ));
+    }
+    // every leaf creates a claim status and transfers tokens
+    ixs.push(ComputeBudgetInstruction::set_compute_unit_limit(1_400_000));
+
+    let mut accounts = merkle_distributor::accounts::NewClaimBatch {
+        distributor,
+        from: get_associated_token_address(&distributor, &args.mint),
+        claimant,
+        payer: claimant,
+        operator: None,
+        token_program: token::ID,
+        system_program: solana_program::system_program::ID,
+        fee_receiver: get_fee_receiver_account(&distributor_state),
+        denylist: get_denylist_account(&args.program_id, &distributor, &distributor_state),
+        bonus_vault: get_bonus_vault_account(&distributor_state),
+    }
+    .to_account_metas(None);
+    let mut leaves = vec![];
+    for index in indices {
+        let node = &merkle_tree.tree_nodes[index];
+        let (claim_status_pda, claim_status_bump) =
+            get_claim_status_pda(&args.program_id, &node.claimant, &distributor);
+        accounts.push(AccountMeta::new(claim_status_pda, false));
+        accounts.push(AccountMeta::new(
+            get_associated_token_address(&node.claimant, &args.mint),
+            false,
+        ));
+        leaves.push(BatchClaimLeaf {
+            amount_unlocked: node.unlocked_amount(),
+            amount_locked: node.locked_amount(),
+            claim_status_bump,
+        });
+    }
+
+    ixs.push(Instruction {
+        program_id: args.program_id,
+        accounts,
+        data: merkle_distributor::instruction::NewClaimBatch {
+            leaves,
+            proof,
+            proof_flags,
+        }
+        .data(),
+    });
+
+    let blockhash = client.get_latest_blockhash().unwrap();
+    let tx =
+        Transaction::new_signed_with_payer(&ixs, Some(&claimant.key()), &[&keypair], blockhash);
+
+    let signature = client
+        .send_and_confirm_transaction_with_spinner(&tx)
+        .unwrap();
+    println!("successfully created batch claim with signature {signature:#?}");
+}
@@ -0,0 +1,175 @@
+use jito_merkle_tree::claim_record_tree::{get_record_index, ClaimRecordMerkleTree};
+use merkle_distributor::state::claim_record_tree::ClaimRecordTree;
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+
+use crate::*;
+
+/// Claims from a compressed distributor, a new claim if the claimant has no record yet, else
+/// the locked tokens vested since the last claim
+pub fn process_claim_compressed(
+    args: &Args,
+    claim_args: &ClaimArgs,
+    merkle_tree: &AirdropMerkleTree,
+    distributor: &Pubkey,
+    distributor_state: &MerkleDistributor,
+) {
+    let keypair = read_keypair_file(&args.keypair_path.clone().unwrap())
+        .expect("Failed reading keypair file");
+    let claimant = keypair.pubkey();
+
+    let claim_records_path = claim_args
+        .claim_records_path
+        .as_ref()
+        .expect("claim records path is required to claim from a compressed distributor");
+
+    // refresh the local index so the proof is built against a recent root
+    let index = index_claim_records(
+        args,
+        distributor,
+        &distributor_state.claim_record_tree,
+        claim_records_path,
+    );
+
+    let program = args.get_program_client();
+    let claim_record_tree_state = program
+        .account::<ClaimRecordTree>(distributor_state.claim_record_tree)
+        .unwrap();
+    let claim_record_tree = ClaimRecordMerkleTree::new_from_index(&index);
+    let root = claim_record_tree.get_root();
+    assert_eq!(
+        root,
+        claim_record_tree_state.get_root(),
+        "local claim record index is behind the claim record tree, index again"
+    );
+
+    let leaf_index = match merkle_tree.get_index(&claimant) {
+        Some(leaf_index) => leaf_index,
+        None => {
+            println!(
+                "{} is not eligible for airdrop version {}",
+                claimant, merkle_tree.airdrop_version
+            );
+            return;
+        }
+    };
+    let node = &merkle_tree.tree_nodes[leaf_index];
+    let airdrop_proof = merkle_tree
+        .proof_for_index(leaf_index)
+        .expect("proof not found");
+    let record_index = get_record_index(
+        node,
+        airdrop_proof.clone(),
+        merkle_tree.merkle_root,
+        merkle_tree.hash_type,
+    )
+    .expect("invalid airdrop proof");
+    let proof = claim_record_tree.get_claim_proof(record_index as usize);
+
+    let client = RpcClient::new_with_commitment(&args.rpc_url, CommitmentConfig::confirmed());
+
+    let claimant_ata = get_associated_token_address(&claimant, &args.mint);
+
+    let mut ixs = vec![];
+
+    // check priority fee
+    if let Some(priority_fee) = args.priority_fee {
+        ixs.push(ComputeBudgetInstruction::set_compute_unit_price(
+            priority_fee,
+        ));
+    }
+
+    match client.get_account(&claimant_ata) {
+        Ok(_) => {}
+        Err(e) => {
+            // TODO: directly pattern match on error kind
+            if e.to_string().contains("AccountNotFound") {
+                println!("PDA does not exist. creating.");
+                ixs.push(create_associated_token_account(
+                    &claimant,
+                    &claimant,
+                    &args.mint,
+                    &token::ID,
+                ));
+            } else {
+                panic!("Error fetching PDA: {e}")
+            }
+        }
+    }
+
+    match index.get_record(record_index) {
+        None => {
+            println!("Claiming tokens for user {}...", claimant);
+            ixs.push(Instruction {
+                program_id: args.program_id,
+                accounts: merkle_distributor::accounts::NewClaimCompressed {
+                    distributor: *distributor,
+                    claim_record_tree: distributor_state.claim_record_tree,
+                    from: get_associated_token_address(distributor, &args.mint),
+                    to: claimant_ata,
+                    claimant,
+                    operator: None,
+                    token_program: token::ID,
+                    system_program: Some(solana_program::system_program::ID),
+                    fee_receiver: get_fee_receiver_account(distributor_state),
+                    denylist: get_denylist_account(
+                        &args.program_id,
+                        distributor,
+                        distributor_state,
+                    ),
+                    bonus_vault: get_bonus_vault_account(distributor_state),
+                }
+                .to_account_metas(None),
+                data: merkle_distributor::instruction::NewClaimCompressed {
+                    amount_unlocked: node.unlocked_amount(),
+                    amount_locked: node.locked_amount(),
+                    root,
+                    proof,
+                    airdrop_proof,
+                }
+                .data(),
+            });
+        }
+        Some(record) => {
+            println!("Claiming locked tokens for user {}...", claimant);
+            ixs.push(Instruction {
+                program_id: args.program_id,
+                accounts: merkle_distributor::accounts::ClaimLockedCompressed {
+                    distributor: *distributor,
+                    claim_record_tree: distributor_state.claim_record_tree,
+                    from: get_associated_token_address(distributor, &args.mint),
+                    to: claimant_ata,
+                    claimant,
+                    operator: None,
+                    token_program: token::ID,
+                    system_program: Some(solana_program::system_program::ID),
+                    fee_receiver: get_fee_receiver_account(distributor_state),
+                    denylist: get_denylist_account(
+                        &args.program_id,
+                        distributor,
+                        distributor_state,
+                    ),
+                }
+                .to_account_metas(None),
+                data: merkle_distributor::instruction::ClaimLockedCompressed {
+                    amount_unlocked: record.unlocked_amount,
+                    amount_locked: record.locked_amount,
+                    locked_amount_withdrawn: record.locked_amount_withdrawn,
+                    bonus_amount: record.bonus_amount,
+                    root,
+                    index: record_index,
+                    proof,
+                }
+                .data(),
+            });
+        }
+    }
+
+    let blockhash = client.get_latest_blockhash().unwrap();
+    let tx =
+        Transaction::new_signed_with_payer(&ixs, Some(&claimant.key()), &[&keypair], blockhash);
+
+    let signature = client
+        .send_and_confirm_transaction_with_spinner(&tx)
+        .unwrap();
+    println!("successfully claimed tokens with signature {signature:#?}");
+}
@@ -8,15 +8,18 @@ pub fn process_claim_from_api(args: &Args, claim_args: &ClaimFromApiArgs) {
         .expect("Failed reading keypair file");
     let claimant = keypair.pubkey();
 
-    let kv_proof: UserProof = reqwest::blocking::get(format!(
+    let response = reqwest::blocking::get(format!(
         "{}/{}/{}",
         claim_args.root_api,
         args.mint.to_string(),
         claimant.to_string()
     ))
-    .unwrap()
-    .json()
     .unwrap();
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        println!("{} is not eligible", claimant);
+        return;
+    }
+    let kv_proof: UserProof = response.json().unwrap();
 
     let distributor = Pubkey::from_str(&kv_proof.merkle_tree).unwrap();
 
@@ -34,6 +37,9 @@ pub fn process_claim_from_api(args: &Args, claim_args: &ClaimFromApiArgs) {
 
     let claimant_ata = get_associated_token_address(&claimant, &args.mint);
 
+    let program = args.get_program_client();
+    let distributor_state = program.account::<MerkleDistributor>(distributor).unwrap();
+
     if client.get_account_data(&claimant_ata).is_err() {
         ixs.push(
             spl_associated_token_account::instruction::create_associated_token_account(
@@ -53,9 +59,13 @@ pub fn process_claim_from_api(args: &Args, claim_args: &ClaimFromApiArgs) {
             from: get_associated_token_address(&distributor, &args.mint),
             to: claimant_ata,
             claimant,
+            payer: claimant,
             token_program: token::ID,
             system_program: solana_program::system_program::ID,
             operator: None,
+            fee_receiver: get_fee_receiver_account(&distributor_state),
+            denylist: get_denylist_account(&args.program_id, &distributor, &distributor_state),
+            bonus_vault: get_bonus_vault_account(&distributor_state),
         }
         .to_account_metas(None),
         data: merkle_distributor::instruction::NewClaim {
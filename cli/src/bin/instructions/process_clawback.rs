@@ -30,22 +30,26 @@ fn clawback(args: &Args, clawback_args: &ClawbackArgs) -> Result<()> {
 
     let mut is_error = false;
 
-    let from_version = clawback_args.from_version;
-    let to_version = clawback_args.to_version;
+    let distributors = get_distributors(
+        &client,
+        args,
+        clawback_args.from_version,
+        clawback_args.to_version,
+    );
 
-    for version in from_version..=to_version {
-        let (distributor, _bump) =
-            get_merkle_distributor_pda(&args.program_id, &args.base, &args.mint, version);
+    for (version, distributor) in distributors {
         if client
             .get_account_with_commitment(&distributor, CommitmentConfig::confirmed())?
             .value
             .is_none()
         {
-            // distributor is not existed, exit
-            break;
+            println!("distributor {} is not existed, skip", version);
+            continue;
         }
         let distributor_state = program.account::<MerkleDistributor>(distributor)?;
-        if distributor_state.clawed_back() {
+        let should_clawback_bonus =
+            distributor_state.has_bonus_vault() && !distributor_state.bonus_clawed_back();
+        if distributor_state.clawed_back() && !should_clawback_bonus {
             println!("already clawback {}", version);
             continue;
         }
@@ -58,17 +62,33 @@ fn clawback(args: &Args, clawback_args: &ClawbackArgs) -> Result<()> {
             ));
         }
 
-        ixs.push(Instruction {
-            program_id: args.program_id,
-            accounts: merkle_distributor::accounts::Clawback {
-                distributor,
-                from: distributor_state.token_vault,
-                token_program: spl_token::ID,
-                clawback_receiver: distributor_state.clawback_receiver,
-            }
-            .to_account_metas(None),
-            data: merkle_distributor::instruction::Clawback {}.data(),
-        });
+        if !distributor_state.clawed_back() {
+            ixs.push(Instruction {
+                program_id: args.program_id,
+                accounts: merkle_distributor::accounts::Clawback {
+                    distributor,
+                    from: distributor_state.token_vault,
+                    token_program: spl_token::ID,
+                    clawback_receiver: distributor_state.clawback_receiver,
+                }
+                .to_account_metas(None),
+                data: merkle_distributor::instruction::Clawback {}.data(),
+            });
+        }
+
+        if should_clawback_bonus {
+            ixs.push(Instruction {
+                program_id: args.program_id,
+                accounts: merkle_distributor::accounts::ClawbackBonus {
+                    distributor,
+                    bonus_vault: distributor_state.bonus_vault,
+                    clawback_receiver: distributor_state.clawback_receiver,
+                    token_program: spl_token::ID,
+                }
+                .to_account_metas(None),
+                data: merkle_distributor::instruction::ClawbackBonus {}.data(),
+            });
+        }
 
         let tx = Transaction::new_signed_with_payer(
             &ixs,
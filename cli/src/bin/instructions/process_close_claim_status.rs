@@ -58,12 +58,16 @@ pub fn process_close_claim_status(args: &Args) {
     let mut close_ixs = vec![];
     loop {
         if let Some(value) = current_status_account {
+            // rent paid by a relayer is refunded to it
+            let rent_receiver = value.1.rent_receiver();
             close_ixs.push(Instruction {
                 program_id: args.program_id,
                 accounts: merkle_distributor::accounts::CloseClaimStatus {
                     admin: keypair.pubkey(),
                     claimant: value.1.claimant,
                     claim_status: value.0,
+                    distributor: value.1.distributor,
+                    rent_payer: (rent_receiver != value.1.claimant).then_some(rent_receiver),
                 }
                 .to_account_metas(None),
                 data: merkle_distributor::instruction::CloseClaimStatus {}.data(),
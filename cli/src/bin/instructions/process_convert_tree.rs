@@ -0,0 +1,69 @@
+use std::path::Path;
+
+use jito_merkle_tree::binary_tree::{write_binary_tree_to_file, BinaryMerkleTree};
+
+use crate::*;
+
+/// Pairs each file of the source, a single file or a directory, with its destination. A
+/// directory is converted file by file into the destination directory
+fn get_conversion_paths(
+    source_path: &Path,
+    destination_path: &Path,
+    extension: &str,
+) -> Vec<(PathBuf, PathBuf)> {
+    if !source_path.is_dir() {
+        return vec![(source_path.to_path_buf(), destination_path.to_path_buf())];
+    }
+
+    fs::create_dir_all(destination_path).unwrap();
+    let mut paths: Vec<_> = fs::read_dir(source_path)
+        .unwrap()
+        .map(|r| r.unwrap().path())
+        .collect();
+    paths.sort();
+    paths
+        .into_iter()
+        .map(|path| {
+            let destination = destination_path
+                .join(path.file_name().unwrap())
+                .with_extension(extension);
+            (path, destination)
+        })
+        .collect()
+}
+
+pub fn process_tree_to_binary(tree_to_binary_args: &TreeToBinaryArgs) {
+    for (merkle_tree_path, binary_path) in get_conversion_paths(
+        &tree_to_binary_args.merkle_tree_path,
+        &tree_to_binary_args.binary_path,
+        "bin",
+    ) {
+        let merkle_tree =
+            AirdropMerkleTree::new_from_file(&merkle_tree_path).expect("failed to read");
+        write_binary_tree_to_file(&merkle_tree, &binary_path).unwrap();
+        println!(
+            "converted {:?} to {:?}, {} nodes",
+            merkle_tree_path, binary_path, merkle_tree.max_num_nodes
+        );
+    }
+}
+
+pub fn process_binary_to_tree(binary_to_tree_args: &BinaryToTreeArgs) {
+    for (binary_path, merkle_tree_path) in get_conversion_paths(
+        &binary_to_tree_args.binary_path,
+        &binary_to_tree_args.merkle_tree_path,
+        "json",
+    ) {
+        let binary_tree = BinaryMerkleTree::open(&binary_path).expect("failed to read");
+        binary_tree.verify().unwrap();
+        binary_tree
+            .to_airdrop_merkle_tree()
+            .write_to_file(&merkle_tree_path);
+        println!(
+            "converted {:?} to {:?}, {} nodes",
+            binary_path,
+            merkle_tree_path,
+            binary_tree.num_nodes()
+        );
+    }
+}
@@ -1,9 +1,56 @@
 use std::collections::HashMap;
 
+use jito_merkle_tree::{
+    airdrop_merkle_tree::AirdropMerkleTreeBuilder, csv_validation::validate_csv_file,
+    manifest::TreeManifest,
+};
+
 use crate::*;
 
-pub fn process_create_merkle_tree(merkle_tree_args: &CreateMerkleTreeArgs) {
-    let mut csv_entries = CsvEntry::new_from_file(&merkle_tree_args.csv_path).unwrap();
+pub fn process_create_merkle_tree(args: &Args, merkle_tree_args: &CreateMerkleTreeArgs) {
+    // the manifests are signed by the admin
+    let manifest_keypair = merkle_tree_args
+        .manifest_path
+        .as_ref()
+        .map(|manifest_path| {
+            fs::create_dir_all(manifest_path).unwrap();
+            read_keypair_file(
+                args.keypair_path
+                    .as_ref()
+                    .expect("keypair path is required to sign the manifests"),
+            )
+            .expect("Failed reading keypair file")
+        });
+
+    let (mut csv_entries, report) = validate_csv_file(
+        &merkle_tree_args.csv_path,
+        merkle_tree_args.decimals,
+        merkle_tree_args.decimal_mode,
+        merkle_tree_args.merge_policy,
+    )
+    .unwrap();
+    if let Some(report_path) = &merkle_tree_args.report_path {
+        report.write_to_file(report_path).unwrap();
+        println!("csv report written to {}", report_path.display());
+    }
+    // no tree is created from a partially valid list
+    if !report.is_valid() {
+        if merkle_tree_args.report_path.is_none() {
+            report.print();
+        }
+        println!(
+            "{} of {} rows are invalid, no merkle tree created",
+            report.num_rows - report.num_valid_rows,
+            report.num_rows
+        );
+        return;
+    }
+    if merkle_tree_args.decimal_mode == DecimalMode::Floor {
+        println!(
+            "floored {} amounts to {} decimals, total dust lost {}",
+            report.dust.num_floored_amounts, merkle_tree_args.decimals, report.dust.total_dust
+        );
+    }
     // create merkle tree folder if not existed
     fs::create_dir_all(merkle_tree_args.merkle_tree_path.clone()).unwrap();
     // exclude test address if have
@@ -38,9 +85,21 @@ pub fn process_create_merkle_tree(merkle_tree_args: &CreateMerkleTreeArgs) {
         csv_entries = csv_entries[last_index..csv_entries.len()].to_vec();
 
         // use index as version
-        let merkle_tree =
-            AirdropMerkleTree::new_from_entries(sub_tree, index, merkle_tree_args.decimals)
+        let builder = AirdropMerkleTreeBuilder {
+            airdrop_version: index,
+            hash_type: merkle_tree_args.hash_type,
+            merge_policy: merkle_tree_args.merge_policy,
+        };
+        let build =
+            AirdropMerkleTree::new_from_entries(sub_tree, merkle_tree_args.decimals, &builder)
                 .unwrap();
+        for merged in build.merged_claimants.iter() {
+            println!(
+                "merged {} entries of claimant {} into amount {} locked_amount {}",
+                merged.num_entries, merged.claimant, merged.amount, merged.locked_amount
+            );
+        }
+        let merkle_tree = build.tree;
 
         let base_path_clone = base_path.clone();
         let path = base_path_clone
@@ -48,6 +107,24 @@ pub fn process_create_merkle_tree(merkle_tree_args: &CreateMerkleTreeArgs) {
             .join(format!("tree_{}.json", index));
 
         merkle_tree.write_to_file(&path);
+
+        if let (Some(manifest_path), Some(keypair)) =
+            (&merkle_tree_args.manifest_path, &manifest_keypair)
+        {
+            let manifest = TreeManifest::new(
+                &merkle_tree,
+                &merkle_tree_args.csv_path,
+                merkle_tree_args.decimals,
+                merkle_tree_args.decimal_mode,
+                merkle_tree_args.merge_policy,
+            )
+            .unwrap()
+            .sign(keypair)
+            .unwrap();
+            manifest
+                .write_to_file(&manifest_path.join(format!("tree_{}.json", index)))
+                .unwrap();
+        }
         index += 1;
     }
 
@@ -62,9 +139,17 @@ pub fn process_create_merkle_tree(merkle_tree_args: &CreateMerkleTreeArgs) {
             })
             .collect::<Vec<CsvEntry>>();
 
-        let merkle_tree =
-            AirdropMerkleTree::new_from_entries(test_list, index, merkle_tree_args.decimals as u32)
-                .unwrap();
+        let merkle_tree = AirdropMerkleTree::new_from_entries(
+            test_list,
+            merkle_tree_args.decimals as u32,
+            &AirdropMerkleTreeBuilder {
+                airdrop_version: index,
+                hash_type: merkle_tree_args.hash_type,
+                merge_policy: merkle_tree_args.merge_policy,
+            },
+        )
+        .unwrap()
+        .tree;
         let base_path_clone = base_path.clone();
         let path = base_path_clone
             .as_path()
@@ -0,0 +1,117 @@
+use merkle_distributor::state::denylist::Denylist;
+
+use crate::*;
+
+pub fn process_new_denylist(args: &Args, new_denylist_args: &NewDenylistArgs) {
+    let keypair = read_keypair_file(&args.keypair_path.clone().unwrap())
+        .expect("Failed reading keypair file");
+
+    let (distributor, _bump) = get_merkle_distributor_pda(
+        &args.program_id,
+        &args.base,
+        &args.mint,
+        new_denylist_args.airdrop_version,
+    );
+    let (denylist, _bump) = get_denylist_pda(&args.program_id, &distributor);
+
+    let ix = Instruction {
+        program_id: args.program_id,
+        accounts: merkle_distributor::accounts::NewDenylist {
+            distributor,
+            denylist,
+            admin: keypair.pubkey(),
+            system_program: solana_program::system_program::id(),
+        }
+        .to_account_metas(None),
+        data: merkle_distributor::instruction::NewDenylist {}.data(),
+    };
+
+    send_admin_ix(args, &keypair, ix);
+    println!("created denylist {denylist}");
+}
+
+pub fn process_add_to_denylist(args: &Args, denylist_args: &DenylistClaimantArgs) {
+    let keypair = read_keypair_file(&args.keypair_path.clone().unwrap())
+        .expect("Failed reading keypair file");
+
+    let (distributor, _bump) = get_merkle_distributor_pda(
+        &args.program_id,
+        &args.base,
+        &args.mint,
+        denylist_args.airdrop_version,
+    );
+    let (denylist, _bump) = get_denylist_pda(&args.program_id, &distributor);
+
+    let ix = Instruction {
+        program_id: args.program_id,
+        accounts: merkle_distributor::accounts::AddToDenylist {
+            distributor,
+            denylist,
+            admin: keypair.pubkey(),
+            system_program: solana_program::system_program::id(),
+        }
+        .to_account_metas(None),
+        data: merkle_distributor::instruction::AddToDenylist {
+            claimant: denylist_args.claimant,
+        }
+        .data(),
+    };
+
+    send_admin_ix(args, &keypair, ix);
+    println!("added {} to denylist {}", denylist_args.claimant, denylist);
+}
+
+pub fn process_remove_from_denylist(args: &Args, denylist_args: &DenylistClaimantArgs) {
+    let keypair = read_keypair_file(&args.keypair_path.clone().unwrap())
+        .expect("Failed reading keypair file");
+
+    let (distributor, _bump) = get_merkle_distributor_pda(
+        &args.program_id,
+        &args.base,
+        &args.mint,
+        denylist_args.airdrop_version,
+    );
+    let (denylist, _bump) = get_denylist_pda(&args.program_id, &distributor);
+
+    let ix = Instruction {
+        program_id: args.program_id,
+        accounts: merkle_distributor::accounts::RemoveFromDenylist {
+            distributor,
+            denylist,
+            admin: keypair.pubkey(),
+        }
+        .to_account_metas(None),
+        data: merkle_distributor::instruction::RemoveFromDenylist {
+            claimant: denylist_args.claimant,
+        }
+        .data(),
+    };
+
+    send_admin_ix(args, &keypair, ix);
+    println!(
+        "removed {} from denylist {}",
+        denylist_args.claimant, denylist
+    );
+}
+
+pub fn view_denylist(args: &Args, denylist_args: &NewDenylistArgs) {
+    let client = RpcClient::new_with_commitment(&args.rpc_url, CommitmentConfig::confirmed());
+
+    let (distributor, _bump) = get_merkle_distributor_pda(
+        &args.program_id,
+        &args.base,
+        &args.mint,
+        denylist_args.airdrop_version,
+    );
+    let (denylist, _bump) = get_denylist_pda(&args.program_id, &distributor);
+
+    // entries are stored after the header, so read the raw account data
+    let account = client.get_account(&denylist).unwrap();
+    let header = Denylist::try_deserialize(&mut account.data.as_slice()).unwrap();
+    let entries = Denylist::entries(&account.data, header.len).unwrap();
+
+    println!("num denylisted claimants {}", entries.len());
+    for claimant in entries {
+        println!("{claimant}");
+    }
+}
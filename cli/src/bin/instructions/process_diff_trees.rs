@@ -0,0 +1,21 @@
+use jito_merkle_tree::tree_diff::{diff_tree_dirs, diff_trees};
+
+use crate::*;
+
+pub fn process_diff_trees(diff_trees_args: &DiffTreesArgs) {
+    let diff = if diff_trees_args.old_path.is_dir() {
+        diff_tree_dirs(&diff_trees_args.old_path, &diff_trees_args.new_path).unwrap()
+    } else {
+        let old_tree = AirdropMerkleTree::new_from_file(&diff_trees_args.old_path)
+            .expect("failed to read old tree");
+        let new_tree = AirdropMerkleTree::new_from_file(&diff_trees_args.new_path)
+            .expect("failed to read new tree");
+        diff_trees(&old_tree, &new_tree)
+    };
+
+    diff.print();
+    if let Some(report_path) = &diff_trees_args.report_path {
+        diff.write_to_file(report_path).unwrap();
+        println!("wrote diff to {:?}", report_path);
+    }
+}
@@ -55,13 +55,20 @@ fn fund_all(args: &Args, fund_all_args: &FundAllArgs) -> Result<()> {
 
         let token_vault = get_associated_token_address(&distributor_pubkey, &args.mint);
 
-        let token_vault_state: TokenAccount = program.account(token_vault)?;
-        if token_vault_state.amount >= distributor_state.max_total_claim {
-            println!(
-                "already fund airdrop version {}!",
-                merkle_tree.airdrop_version
-            );
-            continue;
+        // the bonus vault, if any, is funded with the bonus and the token vault with the rest
+        let mut vaults = vec![];
+        if distributor_state.has_bonus_vault() {
+            let total_bonus = distributor_state.airdrop_bonus.total_bonus;
+            vaults.push((
+                token_vault,
+                distributor_state
+                    .max_total_claim
+                    .checked_sub(total_bonus)
+                    .unwrap(),
+            ));
+            vaults.push((distributor_state.bonus_vault, total_bonus));
+        } else {
+            vaults.push((token_vault, distributor_state.max_total_claim));
         }
 
         let mut ixs = vec![];
@@ -71,17 +78,33 @@ fn fund_all(args: &Args, fund_all_args: &FundAllArgs) -> Result<()> {
                 priority_fee,
             ));
         }
-        ixs.push(
-            spl_token::instruction::transfer(
-                &spl_token::id(),
-                &source_vault,
-                &token_vault,
-                &keypair.pubkey(),
-                &[],
-                distributor_state.max_total_claim,
-            )
-            .unwrap(),
-        );
+        let mut is_funded = true;
+        for (vault, amount) in vaults {
+            let vault_state: TokenAccount = program.account(vault)?;
+            if vault_state.amount >= amount {
+                continue;
+            }
+            is_funded = false;
+            ixs.push(
+                spl_token::instruction::transfer(
+                    &spl_token::id(),
+                    &source_vault,
+                    &vault,
+                    &keypair.pubkey(),
+                    &[],
+                    amount,
+                )
+                .unwrap(),
+            );
+        }
+
+        if is_funded {
+            println!(
+                "already fund airdrop version {}!",
+                merkle_tree.airdrop_version
+            );
+            continue;
+        }
 
         let tx = Transaction::new_signed_with_payer(
             &ixs,
@@ -41,7 +41,7 @@ pub fn process_generate_kv_proof(args: &Args, generate_kv_proof_args: &GenerateK
             merkle_tree.airdrop_version, distributor_pubkey
         );
 
-        for node in merkle_tree.tree_nodes.iter() {
+        for (i, node) in merkle_tree.tree_nodes.iter().enumerate() {
             let user_pk = Pubkey::from(node.claimant);
             proofs.insert(
                 user_pk.to_string(),
@@ -49,7 +49,7 @@ pub fn process_generate_kv_proof(args: &Args, generate_kv_proof_args: &GenerateK
                     merkle_tree: distributor_pubkey.to_string(),
                     amount: node.amount,
                     locked_amount: node.locked_amount,
-                    proof: node.proof.clone().unwrap(),
+                    proof: merkle_tree.proof_for_index(i).unwrap(),
                 },
             );
 
@@ -0,0 +1,162 @@
+use std::path::Path;
+
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use jito_merkle_tree::claim_record_tree::{ClaimRecord, ClaimRecordIndex};
+use merkle_distributor::state::{
+    claim_record_tree::ClaimRecordTree, claimed_event::ClaimRecordEvent,
+};
+use serde_json::{json, Value};
+use solana_rpc_client::rpc_client::GetConfirmedSignaturesForAddress2Config;
+use solana_rpc_client_api::request::RpcRequest;
+use solana_sdk::signature::Signature;
+
+use crate::*;
+
+pub fn process_index_claim_records(args: &Args, index_claim_records_args: &IndexClaimRecordsArgs) {
+    let (distributor, _bump) = get_merkle_distributor_pda(
+        &args.program_id,
+        &args.base,
+        &args.mint,
+        index_claim_records_args.airdrop_version,
+    );
+
+    let program = args.get_program_client();
+    let distributor_state = program.account::<MerkleDistributor>(distributor).unwrap();
+    if !distributor_state.is_compressed() {
+        println!("distributor {} is not compressed", distributor);
+        return;
+    }
+
+    let index = index_claim_records(
+        args,
+        &distributor,
+        &distributor_state.claim_record_tree,
+        &index_claim_records_args.claim_records_path,
+    );
+    println!(
+        "indexed {} claim records of distributor {} up to sequence number {}",
+        index.records.len(),
+        distributor,
+        index.sequence_number
+    );
+}
+
+/// Returns the file of the local claim record index of a distributor
+pub fn get_claim_record_index_path(claim_records_path: &Path, distributor: &Pubkey) -> PathBuf {
+    claim_records_path.join(format!("{}.json", distributor))
+}
+
+/// Replays the claim record events of the transactions since the last indexed one and saves
+/// the updated index
+pub fn index_claim_records(
+    args: &Args,
+    distributor: &Pubkey,
+    claim_record_tree: &Pubkey,
+    claim_records_path: &Path,
+) -> ClaimRecordIndex {
+    let client = RpcClient::new_with_commitment(&args.rpc_url, CommitmentConfig::confirmed());
+
+    let path = get_claim_record_index_path(claim_records_path, distributor);
+    let mut index = if path.exists() {
+        ClaimRecordIndex::new_from_file(&path).expect("failed to load claim record index")
+    } else {
+        let claim_record_tree_state = args
+            .get_program_client()
+            .account::<ClaimRecordTree>(*claim_record_tree)
+            .unwrap();
+        ClaimRecordIndex::new(
+            *claim_record_tree,
+            claim_record_tree_state.max_depth,
+            claim_record_tree_state.canopy_depth,
+        )
+    };
+    assert_eq!(
+        index.claim_record_tree, *claim_record_tree,
+        "claim record index of another tree"
+    );
+
+    let until = index
+        .last_signature
+        .as_ref()
+        .map(|signature| Signature::from_str(signature).unwrap());
+
+    // signatures are returned newest first
+    let mut signatures = vec![];
+    let mut before = None;
+    loop {
+        let page = client
+            .get_signatures_for_address_with_config(
+                claim_record_tree,
+                GetConfirmedSignaturesForAddress2Config {
+                    before,
+                    until,
+                    limit: None,
+                    commitment: Some(CommitmentConfig::confirmed()),
+                },
+            )
+            .unwrap();
+        let last = match page.last() {
+            Some(last) => Signature::from_str(&last.signature).unwrap(),
+            None => break,
+        };
+        before = Some(last);
+        signatures.extend(page);
+    }
+
+    for status in signatures.iter().rev() {
+        if status.err.is_some() {
+            continue;
+        }
+        for event in get_claim_record_events(&client, &status.signature) {
+            if event.claim_record_tree != *claim_record_tree {
+                continue;
+            }
+            index.apply(
+                event.sequence_number,
+                event.index,
+                ClaimRecord {
+                    claimant: event.claimant,
+                    unlocked_amount: event.unlocked_amount,
+                    locked_amount: event.locked_amount,
+                    locked_amount_withdrawn: event.locked_amount_withdrawn,
+                    bonus_amount: event.bonus_amount,
+                },
+            );
+        }
+    }
+
+    if let Some(newest) = signatures.first() {
+        index.last_signature = Some(newest.signature.clone());
+    }
+    index.write_to_file(&path);
+    index
+}
+
+/// Returns the claim record events emitted by a transaction
+fn get_claim_record_events(client: &RpcClient, signature: &str) -> Vec<ClaimRecordEvent> {
+    let transaction: Value = client
+        .send(
+            RpcRequest::GetTransaction,
+            json!([
+                signature,
+                {
+                    "encoding": "json",
+                    "commitment": "confirmed",
+                    "maxSupportedTransactionVersion": 0
+                }
+            ]),
+        )
+        .unwrap();
+
+    let logs = match transaction["meta"]["logMessages"].as_array() {
+        Some(logs) => logs,
+        None => return vec![],
+    };
+
+    logs.iter()
+        .filter_map(|log| log.as_str()?.strip_prefix("Program data: "))
+        .filter_map(|data| base64::decode(data).ok())
+        .filter(|data| data.starts_with(&ClaimRecordEvent::DISCRIMINATOR))
+        .filter_map(|data| ClaimRecordEvent::try_from_slice(&data[8..]).ok())
+        .collect()
+}
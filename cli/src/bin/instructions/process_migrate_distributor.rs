@@ -0,0 +1,72 @@
+use anchor_lang::Space;
+use merkle_distributor::state::merkle_distributor::CURRENT_LAYOUT_VERSION;
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+
+use crate::*;
+
+pub fn process_migrate_distributor(args: &Args, migrate_distributor_args: &MigrateDistributorArgs) {
+    let keypair = read_keypair_file(&args.keypair_path.clone().unwrap())
+        .expect("Failed reading keypair file");
+
+    let client = RpcClient::new_with_commitment(&args.rpc_url, CommitmentConfig::confirmed());
+
+    let distributors = get_distributors(
+        &client,
+        args,
+        migrate_distributor_args.from_version,
+        migrate_distributor_args.to_version,
+    );
+    for (version, distributor) in distributors {
+        loop {
+            let account = client.get_account(&distributor).unwrap();
+            // older layouts are shorter, only a migrated account can be deserialized
+            if account.data.len() >= MerkleDistributor::INIT_SPACE + 8 {
+                let distributor_state =
+                    MerkleDistributor::try_deserialize(&mut account.data.as_slice()).unwrap();
+                if distributor_state.layout_version == CURRENT_LAYOUT_VERSION {
+                    println!("already migrated skip airdrop version {}", version);
+                    break;
+                }
+            }
+            let mut ixs = vec![];
+
+            // check priority fee
+            if let Some(priority_fee) = args.priority_fee {
+                ixs.push(ComputeBudgetInstruction::set_compute_unit_price(
+                    priority_fee,
+                ));
+            }
+
+            ixs.push(Instruction {
+                program_id: args.program_id,
+                accounts: merkle_distributor::accounts::MigrateDistributor {
+                    distributor,
+                    admin: keypair.pubkey(),
+                    system_program: solana_program::system_program::id(),
+                }
+                .to_account_metas(None),
+                data: merkle_distributor::instruction::MigrateDistributor {}.data(),
+            });
+
+            let tx = Transaction::new_signed_with_payer(
+                &ixs,
+                Some(&keypair.pubkey()),
+                &[&keypair],
+                client.get_latest_blockhash().unwrap(),
+            );
+
+            match client.send_and_confirm_transaction_with_spinner(&tx) {
+                Ok(signature) => {
+                    println!(
+                        "Successfully migrated airdrop version {} to layout version {} ! signature: {signature:#?}",
+                        version, CURRENT_LAYOUT_VERSION
+                    );
+                    break;
+                }
+                Err(err) => {
+                    println!("airdrop version {} {}", version, err);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use crate::*;
+
+pub fn process_new_bonus_vault(args: &Args, new_bonus_vault_args: &NewBonusVaultArgs) {
+    let keypair = read_keypair_file(&args.keypair_path.clone().unwrap())
+        .expect("Failed reading keypair file");
+
+    let (distributor, _bump) = get_merkle_distributor_pda(
+        &args.program_id,
+        &args.base,
+        &args.mint,
+        new_bonus_vault_args.airdrop_version,
+    );
+
+    let ix = new_bonus_vault_ix(args, &distributor, &keypair.pubkey());
+
+    send_admin_ix(args, &keypair, ix);
+    println!(
+        "created bonus vault {}",
+        get_bonus_vault_pda(&args.program_id, &distributor).0
+    );
+}
+
+pub fn new_bonus_vault_ix(args: &Args, distributor: &Pubkey, admin: &Pubkey) -> Instruction {
+    let (bonus_vault, _bump) = get_bonus_vault_pda(&args.program_id, distributor);
+
+    Instruction {
+        program_id: args.program_id,
+        accounts: merkle_distributor::accounts::NewBonusVault {
+            distributor: *distributor,
+            bonus_vault,
+            mint: args.mint,
+            admin: *admin,
+            system_program: solana_program::system_program::id(),
+            token_program: spl_token::ID,
+        }
+        .to_account_metas(None),
+        data: merkle_distributor::instruction::NewBonusVault {}.data(),
+    }
+}
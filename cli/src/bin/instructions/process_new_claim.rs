@@ -19,7 +19,17 @@ pub fn process_new_claim(args: &Args, claim_args: &ClaimArgs) {
     );
 
     // Get user's node in claim
-    let node = merkle_tree.get_node(&claimant);
+    let index = match merkle_tree.get_index(&claimant) {
+        Some(index) => index,
+        None => {
+            println!(
+                "{} is not eligible for airdrop version {}",
+                claimant, merkle_tree.airdrop_version
+            );
+            return;
+        }
+    };
+    let node = &merkle_tree.tree_nodes[index];
 
     let (claim_status_pda, _bump) = get_claim_status_pda(&args.program_id, &claimant, &distributor);
 
@@ -27,6 +37,9 @@ pub fn process_new_claim(args: &Args, claim_args: &ClaimArgs) {
 
     let claimant_ata = get_associated_token_address(&claimant, &args.mint);
 
+    let program = args.get_program_client();
+    let distributor_state = program.account::<MerkleDistributor>(distributor).unwrap();
+
     let mut ixs = vec![];
 
     // check priority fee
@@ -62,15 +75,19 @@ pub fn process_new_claim(args: &Args, claim_args: &ClaimArgs) {
             from: get_associated_token_address(&distributor, &args.mint),
             to: claimant_ata,
             claimant,
+            payer: claimant,
             token_program: token::ID,
             system_program: solana_program::system_program::ID,
             operator: None,
+            fee_receiver: get_fee_receiver_account(&distributor_state),
+            denylist: get_denylist_account(&args.program_id, &distributor, &distributor_state),
+            bonus_vault: get_bonus_vault_account(&distributor_state),
         }
         .to_account_metas(None),
         data: merkle_distributor::instruction::NewClaim {
             amount_unlocked: node.unlocked_amount(),
             amount_locked: node.locked_amount(),
-            proof: node.proof.expect("proof not found"),
+            proof: merkle_tree.proof_for_index(index).expect("proof not found"),
         }
         .data(),
     });
@@ -0,0 +1,86 @@
+use jito_merkle_tree::claim_record_tree::{get_canopy_depth, get_record_tree_depth};
+use merkle_distributor::state::claim_record_tree::ClaimRecordTree;
+use solana_program::system_instruction;
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+
+use crate::*;
+
+pub fn process_new_claim_record_tree(
+    args: &Args,
+    new_claim_record_tree_args: &NewClaimRecordTreeArgs,
+) {
+    let keypair = read_keypair_file(&args.keypair_path.clone().unwrap())
+        .expect("Failed reading keypair file");
+
+    let merkle_tree =
+        AirdropMerkleTree::new_from_file(&new_claim_record_tree_args.merkle_tree_path)
+            .expect("failed to load merkle tree from file");
+
+    let (distributor, _bump) = get_merkle_distributor_pda(
+        &args.program_id,
+        &args.base,
+        &args.mint,
+        merkle_tree.airdrop_version,
+    );
+
+    // the tree starts empty, the program derives its root
+    let max_depth = get_record_tree_depth(merkle_tree.max_num_nodes);
+    let canopy_depth = new_claim_record_tree_args
+        .canopy_depth
+        .unwrap_or_else(|| get_canopy_depth(max_depth));
+    let space = ClaimRecordTree::space(canopy_depth);
+
+    let client = RpcClient::new_with_commitment(&args.rpc_url, CommitmentConfig::confirmed());
+
+    let claim_record_tree = Keypair::new();
+    let lamports = client
+        .get_minimum_balance_for_rent_exemption(space)
+        .unwrap();
+
+    let mut ixs = vec![];
+    // check priority fee
+    if let Some(priority_fee) = args.priority_fee {
+        ixs.push(ComputeBudgetInstruction::set_compute_unit_price(
+            priority_fee,
+        ));
+    }
+    ixs.push(system_instruction::create_account(
+        &keypair.pubkey(),
+        &claim_record_tree.pubkey(),
+        lamports,
+        space as u64,
+        &args.program_id,
+    ));
+    ixs.push(Instruction {
+        program_id: args.program_id,
+        accounts: merkle_distributor::accounts::NewClaimRecordTree {
+            distributor,
+            claim_record_tree: claim_record_tree.pubkey(),
+            admin: keypair.pubkey(),
+        }
+        .to_account_metas(None),
+        data: merkle_distributor::instruction::NewClaimRecordTree {
+            max_depth,
+            canopy_depth,
+        }
+        .data(),
+    });
+
+    let tx = Transaction::new_signed_with_payer(
+        &ixs,
+        Some(&keypair.pubkey()),
+        &[&keypair, &claim_record_tree],
+        client.get_latest_blockhash().unwrap(),
+    );
+
+    let signature = client
+        .send_and_confirm_transaction_with_spinner(&tx)
+        .unwrap();
+    println!(
+        "created claim record tree {} of depth {} and canopy depth {} for distributor {} with signature {signature:#?}",
+        claim_record_tree.pubkey(),
+        max_depth,
+        canopy_depth,
+        distributor
+    );
+}
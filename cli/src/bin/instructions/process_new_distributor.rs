@@ -126,6 +126,7 @@ fn create_new_distributor(args: &Args, new_distributor_args: &NewDistributorArgs
         ixs.push(Instruction {
             program_id: args.program_id,
             accounts: merkle_distributor::accounts::NewDistributor {
+                registry: get_registry_pda(&args.program_id, &base.pubkey(), &args.mint).0,
                 base: base.pubkey(),
                 clawback_receiver,
                 mint: args.mint,
@@ -134,6 +135,8 @@ fn create_new_distributor(args: &Args, new_distributor_args: &NewDistributorArgs
                 system_program: solana_program::system_program::id(),
                 token_program: token::ID,
                 admin: keypair.pubkey(),
+                locker: (new_distributor_args.min_lock_duration > 0)
+                    .then_some(new_distributor_args.locker),
             }
             .to_account_metas(None),
             data: merkle_distributor::instruction::NewDistributor {
@@ -153,11 +156,26 @@ fn create_new_distributor(args: &Args, new_distributor_args: &NewDistributorArgs
                     operator: new_distributor_args.operator,
                     claim_type: new_distributor_args.claim_type,
                     bonus_vesting_duration: new_distributor_args.bonus_vesting_duration,
+                    min_lock_duration: new_distributor_args.min_lock_duration,
+                    timelock_delay: new_distributor_args.timelock_delay,
+                    claim_fee_type: new_distributor_args.claim_fee_type,
+                    claim_fee: new_distributor_args.claim_fee,
+                    fee_receiver: new_distributor_args.fee_receiver,
+                    vesting_type: new_distributor_args.vesting_type,
+                    hash_type: merkle_tree.hash_type.into(),
                 },
             }
             .data(),
         });
 
+        if new_distributor_args.bonus_vault && total_bonus > 0 {
+            ixs.push(new_bonus_vault_ix(
+                args,
+                &distributor_pubkey,
+                &keypair.pubkey(),
+            ));
+        }
+
         let blockhash = client.get_latest_blockhash().unwrap();
         let tx = Transaction::new_signed_with_payer(
             &ixs,
@@ -0,0 +1,44 @@
+use merkle_distributor::state::claim_status::ClaimStatus;
+
+use crate::*;
+
+pub fn process_revoke_vesting(args: &Args, revoke_vesting_args: &RevokeVestingArgs) {
+    let keypair = read_keypair_file(&args.keypair_path.clone().unwrap())
+        .expect("Failed reading keypair file");
+    let program = args.get_program_client();
+
+    let (distributor, _bump) = get_merkle_distributor_pda(
+        &args.program_id,
+        &args.base,
+        &args.mint,
+        revoke_vesting_args.airdrop_version,
+    );
+    let distributor_state = program.account::<MerkleDistributor>(distributor).unwrap();
+    let (claim_status, _bump) = get_claim_status_pda(
+        &args.program_id,
+        &revoke_vesting_args.claimant,
+        &distributor,
+    );
+
+    let ix = Instruction {
+        program_id: args.program_id,
+        accounts: merkle_distributor::accounts::RevokeVesting {
+            distributor,
+            claim_status,
+            from: distributor_state.token_vault,
+            clawback_receiver: distributor_state.clawback_receiver,
+            admin: keypair.pubkey(),
+            token_program: spl_token::ID,
+        }
+        .to_account_metas(None),
+        data: merkle_distributor::instruction::RevokeVesting {}.data(),
+    };
+
+    send_admin_ix(args, &keypair, ix);
+
+    let claim_status_state = program.account::<ClaimStatus>(claim_status).unwrap();
+    println!(
+        "revoked vesting of {}, locked amount frozen at {}",
+        revoke_vesting_args.claimant, claim_status_state.locked_amount
+    );
+}
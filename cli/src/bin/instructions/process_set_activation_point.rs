@@ -9,12 +9,13 @@ pub fn process_set_activation_point(args: &Args, set_activation_slot_args: &SetA
     let client = RpcClient::new_with_commitment(&args.rpc_url, CommitmentConfig::confirmed());
     let program = args.get_program_client();
 
-    let from_version = set_activation_slot_args.from_version;
-    let to_version = set_activation_slot_args.to_version;
-    for version in from_version..=to_version {
-        let (distributor, _bump) =
-            get_merkle_distributor_pda(&args.program_id, &args.base, &args.mint, version);
-
+    let distributors = get_distributors(
+        &client,
+        args,
+        set_activation_slot_args.from_version,
+        set_activation_slot_args.to_version,
+    );
+    for (version, distributor) in distributors {
         loop {
             let distributor_state = program.account::<MerkleDistributor>(distributor).unwrap();
             if distributor_state.activation_point == set_activation_slot_args.activation_point {
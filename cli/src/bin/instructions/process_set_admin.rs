@@ -8,12 +8,13 @@ pub fn process_set_admin(args: &Args, set_admin_args: &SetAdminArgs) {
     let client = RpcClient::new_with_commitment(&args.rpc_url, CommitmentConfig::confirmed());
     let program = args.get_program_client();
 
-    let from_version = set_admin_args.from_version;
-    let to_version = set_admin_args.to_version;
-    for i in from_version..=to_version {
-        let (distributor, _bump) =
-            get_merkle_distributor_pda(&args.program_id, &args.base, &args.mint, i);
-
+    let distributors = get_distributors(
+        &client,
+        args,
+        set_admin_args.from_version,
+        set_admin_args.to_version,
+    );
+    for (i, distributor) in distributors {
         loop {
             let distributor_state = program.account::<MerkleDistributor>(distributor).unwrap();
             if distributor_state.admin == set_admin_args.new_admin {
@@ -0,0 +1,76 @@
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+
+use crate::*;
+
+pub fn process_set_claim_cap(args: &Args, set_claim_cap_args: &SetClaimCapArgs) {
+    let keypair = read_keypair_file(&args.keypair_path.clone().unwrap())
+        .expect("Failed reading keypair file");
+
+    let client = RpcClient::new_with_commitment(&args.rpc_url, CommitmentConfig::confirmed());
+    let program = args.get_program_client();
+
+    let distributors = get_distributors(
+        &client,
+        args,
+        set_claim_cap_args.from_version,
+        set_claim_cap_args.to_version,
+    );
+    for (version, distributor) in distributors {
+        loop {
+            let distributor_state = program.account::<MerkleDistributor>(distributor).unwrap();
+            if distributor_state.claim_cap_type == set_claim_cap_args.claim_cap_type
+                && distributor_state.claim_cap == set_claim_cap_args.claim_cap
+                && distributor_state.claim_window_length == set_claim_cap_args.claim_window_length
+            {
+                println!("already set claim cap skip airdrop version {}", version);
+                break;
+            }
+            let mut ixs = vec![];
+
+            // check priority fee
+            if let Some(priority_fee) = args.priority_fee {
+                ixs.push(ComputeBudgetInstruction::set_compute_unit_price(
+                    priority_fee,
+                ));
+            }
+
+            ixs.push(Instruction {
+                program_id: args.program_id,
+                accounts: merkle_distributor::accounts::SetClaimCap {
+                    distributor,
+                    admin: keypair.pubkey(),
+                }
+                .to_account_metas(None),
+                data: merkle_distributor::instruction::SetClaimCap {
+                    claim_cap_type: set_claim_cap_args.claim_cap_type,
+                    claim_cap: set_claim_cap_args.claim_cap,
+                    claim_window_length: set_claim_cap_args.claim_window_length,
+                }
+                .data(),
+            });
+
+            let tx = Transaction::new_signed_with_payer(
+                &ixs,
+                Some(&keypair.pubkey()),
+                &[&keypair],
+                client.get_latest_blockhash().unwrap(),
+            );
+
+            match client.send_and_confirm_transaction_with_spinner(&tx) {
+                Ok(signature) => {
+                    println!(
+                        "Successfully set claim cap type {} cap {} window length {} airdrop version {} ! signature: {signature:#?}",
+                        set_claim_cap_args.claim_cap_type,
+                        set_claim_cap_args.claim_cap,
+                        set_claim_cap_args.claim_window_length,
+                        version
+                    );
+                    break;
+                }
+                Err(err) => {
+                    println!("airdrop version {} {}", version, err);
+                }
+            }
+        }
+    }
+}
@@ -21,19 +21,8 @@ pub fn process_set_activation_slot_by_time(
 
     let enable_time = set_enable_slot_by_time_args.timestamp;
 
-    let clock_account = client.get_account(&sysvar::clock::id()).unwrap();
-    let clock = deserialize::<Clock>(&clock_account.data).unwrap();
-    let current_time = u64::try_from(clock.unix_timestamp).unwrap();
-    let current_slot = clock.slot;
-    let average_slot_time = get_average_slot_time(&client).unwrap();
-
-    println!("average slot time {}", average_slot_time);
-
-    let slot = if enable_time > current_time {
-        current_slot + (enable_time - current_time) * 1000 / average_slot_time
-    } else {
-        current_slot - (current_time - enable_time) * 1000 / average_slot_time
-    };
+    let slot_by_time = SlotByTime::new(&client).unwrap();
+    let slot = slot_by_time.get_slot(enable_time);
 
     println!("slot activate {}", slot);
 
@@ -140,6 +129,37 @@ pub fn process_set_activation_slot_by_time(
     }
 }
 
+/// Estimates slots from timestamps, based on the current clock and the average slot time
+pub struct SlotByTime {
+    pub current_time: u64,
+    pub current_slot: u64,
+    pub average_slot_time: u64,
+}
+
+impl SlotByTime {
+    pub fn new(client: &RpcClient) -> Result<Self> {
+        let clock_account = client.get_account(&sysvar::clock::id())?;
+        let clock = deserialize::<Clock>(&clock_account.data)?;
+        let average_slot_time = get_average_slot_time(client)?;
+
+        println!("average slot time {}", average_slot_time);
+
+        Ok(SlotByTime {
+            current_time: u64::try_from(clock.unix_timestamp)?,
+            current_slot: clock.slot,
+            average_slot_time,
+        })
+    }
+
+    pub fn get_slot(&self, timestamp: u64) -> u64 {
+        if timestamp > self.current_time {
+            self.current_slot + (timestamp - self.current_time) * 1000 / self.average_slot_time
+        } else {
+            self.current_slot - (self.current_time - timestamp) * 1000 / self.average_slot_time
+        }
+    }
+}
+
 pub fn get_average_slot_time(client: &RpcClient) -> Result<u64> {
     let samples = client.get_recent_performance_samples(Some(720))?;
     let num_samples = samples.len() as u64;
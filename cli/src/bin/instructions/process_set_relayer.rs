@@ -0,0 +1,32 @@
+use crate::*;
+
+pub fn process_set_relayer(args: &Args, set_relayer_args: &SetRelayerArgs) {
+    let keypair = read_keypair_file(&args.keypair_path.clone().unwrap())
+        .expect("Failed reading keypair file");
+
+    let (distributor, _bump) = get_merkle_distributor_pda(
+        &args.program_id,
+        &args.base,
+        &args.mint,
+        set_relayer_args.airdrop_version,
+    );
+
+    let ix = Instruction {
+        program_id: args.program_id,
+        accounts: merkle_distributor::accounts::SetRelayer {
+            distributor,
+            admin: keypair.pubkey(),
+        }
+        .to_account_metas(None),
+        data: merkle_distributor::instruction::SetRelayer {
+            new_relayer: set_relayer_args.relayer,
+        }
+        .data(),
+    };
+
+    send_admin_ix(args, &keypair, ix);
+    println!(
+        "set relayer {} airdrop version {}",
+        set_relayer_args.relayer, set_relayer_args.airdrop_version
+    );
+}
@@ -0,0 +1,181 @@
+use anchor_client::solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use anchor_lang::Space;
+use merkle_distributor::state::pending_action::{ActionType, PendingAction};
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+
+use crate::*;
+
+pub fn process_queue_action(args: &Args, queue_action_args: &QueueActionArgs) {
+    let keypair = read_keypair_file(&args.keypair_path.clone().unwrap())
+        .expect("Failed reading keypair file");
+    let program = args.get_program_client();
+
+    let action_type =
+        ActionType::try_from(queue_action_args.action_type).expect("invalid action type");
+
+    let (distributor, _bump) = get_merkle_distributor_pda(
+        &args.program_id,
+        &args.base,
+        &args.mint,
+        queue_action_args.airdrop_version,
+    );
+    let distributor_state = program.account::<MerkleDistributor>(distributor).unwrap();
+    let (pending_action, _bump) = get_pending_action_pda(
+        &args.program_id,
+        &distributor,
+        distributor_state.num_queued_actions,
+    );
+
+    // clawback receiver is set to the ATA of the given owner, same as set-clawback-receiver
+    let (new_pubkey, new_clawback_account) = if action_type == ActionType::SetClawbackReceiver {
+        let new_clawback_account =
+            get_associated_token_address(&queue_action_args.new_pubkey, &args.mint);
+        (new_clawback_account, Some(new_clawback_account))
+    } else {
+        (queue_action_args.new_pubkey, None)
+    };
+
+    let ix = Instruction {
+        program_id: args.program_id,
+        accounts: merkle_distributor::accounts::QueueAction {
+            distributor,
+            pending_action,
+            new_clawback_account,
+            admin: keypair.pubkey(),
+            system_program: solana_program::system_program::id(),
+        }
+        .to_account_metas(None),
+        data: merkle_distributor::instruction::QueueAction {
+            action_type: queue_action_args.action_type,
+            new_pubkey,
+            new_value: queue_action_args.new_value,
+            claim_cap_type: queue_action_args.claim_cap_type,
+            claim_window_length: queue_action_args.claim_window_length,
+        }
+        .data(),
+    };
+
+    send_admin_ix(args, &keypair, ix);
+    println!(
+        "queued {:?} as pending action {} executable after {} seconds",
+        action_type, pending_action, distributor_state.timelock_delay
+    );
+}
+
+pub fn process_execute_action(args: &Args, pending_action_args: &PendingActionArgs) {
+    let keypair = read_keypair_file(&args.keypair_path.clone().unwrap())
+        .expect("Failed reading keypair file");
+    let program = args.get_program_client();
+
+    let pending_action_state = program
+        .account::<PendingAction>(pending_action_args.pending_action)
+        .unwrap();
+
+    let is_set_clawback_receiver =
+        pending_action_state.action_type == u8::from(ActionType::SetClawbackReceiver);
+    let new_clawback_account = is_set_clawback_receiver.then_some(pending_action_state.new_pubkey);
+
+    let ix = Instruction {
+        program_id: args.program_id,
+        accounts: merkle_distributor::accounts::ExecuteAction {
+            distributor: pending_action_state.distributor,
+            pending_action: pending_action_args.pending_action,
+            payer: pending_action_state.payer,
+            new_clawback_account,
+        }
+        .to_account_metas(None),
+        data: merkle_distributor::instruction::ExecuteAction {}.data(),
+    };
+
+    send_admin_ix(args, &keypair, ix);
+    println!(
+        "executed pending action {}",
+        pending_action_args.pending_action
+    );
+}
+
+pub fn process_cancel_action(args: &Args, pending_action_args: &PendingActionArgs) {
+    let keypair = read_keypair_file(&args.keypair_path.clone().unwrap())
+        .expect("Failed reading keypair file");
+    let program = args.get_program_client();
+
+    let pending_action_state = program
+        .account::<PendingAction>(pending_action_args.pending_action)
+        .unwrap();
+
+    let ix = Instruction {
+        program_id: args.program_id,
+        accounts: merkle_distributor::accounts::CancelAction {
+            distributor: pending_action_state.distributor,
+            pending_action: pending_action_args.pending_action,
+            payer: pending_action_state.payer,
+            admin: keypair.pubkey(),
+        }
+        .to_account_metas(None),
+        data: merkle_distributor::instruction::CancelAction {}.data(),
+    };
+
+    send_admin_ix(args, &keypair, ix);
+    println!(
+        "cancelled pending action {}",
+        pending_action_args.pending_action
+    );
+}
+
+pub fn view_pending_actions(args: &Args, view_pending_actions_args: &ViewPendingActionsArgs) {
+    let program = args.get_program_client();
+
+    let (distributor, _bump) = get_merkle_distributor_pda(
+        &args.program_id,
+        &args.base,
+        &args.mint,
+        view_pending_actions_args.airdrop_version,
+    );
+
+    let mut pending_actions: Vec<(Pubkey, PendingAction)> = program
+        .accounts(vec![
+            RpcFilterType::DataSize((PendingAction::INIT_SPACE) as u64 + 8),
+            RpcFilterType::Memcmp(Memcmp::new_raw_bytes(8, distributor.to_bytes().to_vec())),
+        ])
+        .unwrap();
+    pending_actions.sort_by_key(|(_, action)| action.action_id);
+
+    println!("num pending actions {}", pending_actions.len());
+    for (pubkey, action) in pending_actions {
+        println!(
+            "pk {} id {} type {:?} new_pubkey {} new_value {} queued_ts {} executable_ts {}",
+            pubkey,
+            action.action_id,
+            ActionType::try_from(action.action_type),
+            action.new_pubkey,
+            action.new_value,
+            action.queued_ts,
+            action.executable_ts,
+        );
+    }
+}
+
+pub fn send_admin_ix(args: &Args, keypair: &Keypair, ix: Instruction) {
+    let client = RpcClient::new_with_commitment(&args.rpc_url, CommitmentConfig::confirmed());
+
+    let mut ixs = vec![];
+    // check priority fee
+    if let Some(priority_fee) = args.priority_fee {
+        ixs.push(ComputeBudgetInstruction::set_compute_unit_price(
+            priority_fee,
+        ));
+    }
+    ixs.push(ix);
+
+    let tx = Transaction::new_signed_with_payer(
+        &ixs,
+        Some(&keypair.pubkey()),
+        &[keypair],
+        client.get_latest_blockhash().unwrap(),
+    );
+
+    let signature = client
+        .send_and_confirm_transaction_with_spinner(&tx)
+        .unwrap();
+    println!("signature: {signature:#?}");
+}
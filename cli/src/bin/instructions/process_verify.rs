@@ -1,3 +1,5 @@
+use std::collections::HashSet;
+
 use crate::*;
 
 pub fn process_verify(args: &Args, verfify_args: &VerifyArgs) {
@@ -9,6 +11,9 @@ pub fn process_verify(args: &Args, verfify_args: &VerifyArgs) {
 
     // TODO fix code
     let program = args.get_program_client();
+    let client = RpcClient::new_with_commitment(&args.rpc_url, CommitmentConfig::finalized());
+    let registry_entries = get_registry_entries(&client, args);
+    let mut verified_versions = HashSet::new();
 
     for file in paths {
         let single_tree_path = file.path();
@@ -39,19 +44,34 @@ pub fn process_verify(args: &Args, verfify_args: &VerifyArgs) {
             merkle_tree.airdrop_version, distributor_pubkey
         );
 
+        let merke_tree_state: MerkleDistributor = program.account(distributor_pubkey).unwrap();
+
         if !verfify_args.skip_verify_amount {
             let token_vault = get_associated_token_address(&distributor_pubkey, &args.mint);
             let token_vault_account: TokenAccount = program.account(token_vault).unwrap();
-            assert_eq!(
-                token_vault_account.amount,
-                merkle_tree
-                    .get_max_total_claim()
-                    .checked_add(total_bonus)
-                    .unwrap()
-            );
+            if merke_tree_state.has_bonus_vault() {
+                let bonus_vault_account: TokenAccount =
+                    program.account(merke_tree_state.bonus_vault).unwrap();
+                println!(
+                    "token vault {} bonus vault {}",
+                    token_vault_account.amount, bonus_vault_account.amount
+                );
+                assert_eq!(
+                    token_vault_account.amount,
+                    merkle_tree.get_max_total_claim()
+                );
+                assert_eq!(bonus_vault_account.amount, total_bonus);
+            } else {
+                assert_eq!(
+                    token_vault_account.amount,
+                    merkle_tree
+                        .get_max_total_claim()
+                        .checked_add(total_bonus)
+                        .unwrap()
+                );
+            }
         }
 
-        let merke_tree_state: MerkleDistributor = program.account(distributor_pubkey).unwrap();
         assert_eq!(merke_tree_state.root, merkle_tree.merkle_root);
 
         assert_eq!(
@@ -77,8 +97,46 @@ pub fn process_verify(args: &Args, verfify_args: &VerifyArgs) {
 
         assert_eq!(merke_tree_state.locker, verfify_args.locker);
 
+        assert_eq!(
+            merke_tree_state.min_lock_duration,
+            verfify_args.min_lock_duration
+        );
+
+        assert_eq!(merke_tree_state.timelock_delay, verfify_args.timelock_delay);
+
+        assert_eq!(merke_tree_state.claim_fee_type, verfify_args.claim_fee_type);
+        assert_eq!(merke_tree_state.claim_fee, verfify_args.claim_fee);
+        assert_eq!(merke_tree_state.fee_receiver, verfify_args.fee_receiver);
+        assert_eq!(merke_tree_state.vesting_type, verfify_args.vesting_type);
+        assert_eq!(merke_tree_state.hash_type, u8::from(merkle_tree.hash_type));
+
         let clawback_receiver =
             get_associated_token_address(&verfify_args.clawback_receiver_owner, &args.mint);
         assert_eq!(merke_tree_state.clawback_receiver, clawback_receiver);
+
+        if let Some(registry_entries) = &registry_entries {
+            let entry = registry_entries
+                .iter()
+                .find(|entry| entry.version == merkle_tree.airdrop_version)
+                .expect("distributor is not in the registry");
+            assert_eq!(entry.distributor, distributor_pubkey);
+            assert_eq!(entry.root, merkle_tree.merkle_root);
+        }
+        verified_versions.insert(merkle_tree.airdrop_version);
+    }
+
+    // every registered distributor should have a merkle tree
+    if let Some(registry_entries) = &registry_entries {
+        for entry in registry_entries.iter() {
+            let in_scope = verfify_args
+                .airdrop_version
+                .map_or(true, |version| version == entry.version);
+            if in_scope && !verified_versions.contains(&entry.version) {
+                println!(
+                    "registered distributor version {} {} has no merkle tree",
+                    entry.version, entry.distributor
+                );
+            }
+        }
     }
 }
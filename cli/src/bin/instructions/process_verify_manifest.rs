@@ -0,0 +1,57 @@
+use jito_merkle_tree::manifest::{DistributorParams, SignedTreeManifest};
+
+use crate::*;
+
+pub fn process_verify_manifest(args: &Args, verify_manifest_args: &VerifyManifestArgs) {
+    let signed_manifest = SignedTreeManifest::new_from_file(&verify_manifest_args.manifest_path)
+        .expect("failed to read manifest");
+    let merkle_tree = AirdropMerkleTree::new_from_file(&verify_manifest_args.merkle_tree_path)
+        .expect("failed to read merkle tree");
+    let manifest = &signed_manifest.manifest;
+
+    let distributor_params = if verify_manifest_args.on_chain {
+        let (distributor, _bump) = get_merkle_distributor_pda(
+            &args.program_id,
+            &args.base,
+            &args.mint,
+            manifest.airdrop_version,
+        );
+        let program = args.get_program_client();
+        let distributor_state = program.account::<MerkleDistributor>(distributor).unwrap();
+        Some(DistributorParams {
+            admin: distributor_state.admin,
+            version: distributor_state.version,
+            root: distributor_state.root,
+            max_total_claim: distributor_state.max_total_claim,
+            total_bonus: distributor_state.airdrop_bonus.total_bonus,
+            max_num_nodes: distributor_state.max_num_nodes,
+            hash_type: distributor_state.hash_type,
+        })
+    } else {
+        None
+    };
+
+    signed_manifest
+        .verify(&merkle_tree, distributor_params.as_ref())
+        .unwrap();
+    if let Some(csv_path) = &verify_manifest_args.csv_path {
+        signed_manifest.verify_csv(csv_path).unwrap();
+    }
+
+    println!(
+        "manifest of airdrop version {} signed by {}: csv {}, {} nodes, unlocked {}, locked {}, built by {} with {} decimals, {:?} decimal mode and {:?} merge policy",
+        manifest.airdrop_version,
+        signed_manifest.signer,
+        manifest.csv_hash,
+        manifest.num_nodes,
+        manifest.total_unlocked_amount,
+        manifest.total_locked_amount,
+        manifest.builder_version,
+        manifest.decimals,
+        manifest.decimal_mode,
+        manifest.merge_policy
+    );
+    if distributor_params.is_some() {
+        println!("matches the on-chain distributor");
+    }
+}
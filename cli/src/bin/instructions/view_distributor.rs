@@ -1,22 +1,35 @@
+use anchor_lang::Space;
+use merkle_distributor::state::merkle_distributor::ClaimCapType;
+
 use crate::*;
 
 pub fn view_distributors(args: &Args, view_distributor_args: &ViewDistributorsArgs) {
     let client = RpcClient::new_with_commitment(&args.rpc_url, CommitmentConfig::finalized());
 
-    let from_version = view_distributor_args.from_version;
-    let to_version = view_distributor_args.to_version;
-    for i in from_version..=to_version {
-        let (distributor_pubkey, _bump) =
-            get_merkle_distributor_pda(&args.program_id, &args.base, &args.mint, i);
-
+    let distributors = get_distributors(
+        &client,
+        args,
+        view_distributor_args.from_version,
+        view_distributor_args.to_version,
+    );
+    for (i, distributor_pubkey) in distributors {
         if let Some(account) = client
             .get_account_with_commitment(&distributor_pubkey, CommitmentConfig::confirmed())
             .unwrap()
             .value
         {
+            if account.data.len() < MerkleDistributor::INIT_SPACE + 8 {
+                println!(
+                    "pk {} version {} has a legacy layout, run migrate-distributor",
+                    distributor_pubkey, i
+                );
+                continue;
+            }
             let distributor =
                 MerkleDistributor::try_deserialize(&mut account.data.as_slice()).unwrap();
             println!("pk {} version {} {:?}", distributor_pubkey, i, distributor);
+            print_claim_cap(&distributor);
+            print_bonus_vault(&distributor);
         } else {
             println!("merkle distributor {} doesn't exist", i);
         }
@@ -31,9 +44,51 @@ pub fn view_distributor_by_pubkey(args: &Args, distributor_pubkey: &Pubkey) {
         .unwrap()
         .value
     {
+        if account.data.len() < MerkleDistributor::INIT_SPACE + 8 {
+            println!(
+                "merkle distributor {} has a legacy layout, run migrate-distributor",
+                distributor_pubkey
+            );
+            return;
+        }
         let distributor = MerkleDistributor::try_deserialize(&mut account.data.as_slice()).unwrap();
         println!("{:?}", distributor);
+        print_claim_cap(&distributor);
+        print_bonus_vault(&distributor);
     } else {
         println!("merkle distributor {} doesn't exist", distributor_pubkey);
     }
 }
+
+fn print_claim_cap(distributor: &MerkleDistributor) {
+    match ClaimCapType::try_from(distributor.claim_cap_type) {
+        Ok(ClaimCapType::None) => println!("claim cap: none"),
+        Ok(claim_cap_type) => println!(
+            "claim cap: {:?} {} per {} {}, {} in window {}",
+            claim_cap_type,
+            distributor.claim_cap,
+            distributor.claim_window_length,
+            if distributor.activation_type == 0 {
+                "slots"
+            } else {
+                "seconds"
+            },
+            distributor.claimed_in_window,
+            distributor.claim_window_index,
+        ),
+        Err(_) => println!("claim cap: invalid type {}", distributor.claim_cap_type),
+    }
+}
+
+fn print_bonus_vault(distributor: &MerkleDistributor) {
+    if distributor.has_bonus_vault() {
+        println!(
+            "bonus vault: {} total bonus {} clawed back {}",
+            distributor.bonus_vault,
+            distributor.airdrop_bonus.total_bonus,
+            distributor.bonus_clawed_back()
+        );
+    } else {
+        println!("bonus vault: none, bonus is held in the token vault");
+    }
+}
@@ -0,0 +1,61 @@
+//! Measures the speedup of the parallel tree build over thread counts.
+//!
+//! cargo bench -p jito-merkle-tree --features parallel --bench build_tree -- [num_nodes]
+use std::time::{Duration, Instant};
+
+use jito_merkle_tree::{airdrop_merkle_tree::AirdropMerkleTree, tree_node::TreeNode};
+use solana_program::pubkey::Pubkey;
+
+const DEFAULT_NUM_NODES: usize = 1_000_000;
+
+fn main() {
+    // cargo bench passes --bench, the first other argument is the number of nodes
+    let num_nodes = std::env::args()
+        .skip(1)
+        .find(|arg| !arg.starts_with("--"))
+        .map(|arg| arg.parse().expect("invalid number of nodes"))
+        .unwrap_or(DEFAULT_NUM_NODES);
+
+    let tree_nodes = (0..num_nodes as u64)
+        .map(|i| TreeNode {
+            claimant: Pubkey::new_unique(),
+            amount: i,
+            locked_amount: i,
+        })
+        .collect::<Vec<_>>();
+
+    let max_num_threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let mut num_threads_list = vec![1];
+    while num_threads_list.last().unwrap() * 2 <= max_num_threads {
+        num_threads_list.push(num_threads_list.last().unwrap() * 2);
+    }
+    if *num_threads_list.last().unwrap() != max_num_threads {
+        num_threads_list.push(max_num_threads);
+    }
+
+    let mut baseline: Option<Duration> = None;
+    let mut results = vec![];
+    for num_threads in num_threads_list {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(num_threads)
+            .build()
+            .unwrap();
+        let tree_nodes = tree_nodes.clone();
+        let start = Instant::now();
+        // the build includes the validation of every proof
+        pool.install(|| AirdropMerkleTree::new(tree_nodes, 0).unwrap());
+        let elapsed = start.elapsed();
+
+        let baseline = *baseline.get_or_insert(elapsed);
+        results.push((
+            num_threads,
+            elapsed,
+            baseline.as_secs_f64() / elapsed.as_secs_f64(),
+        ));
+    }
+
+    println!("build of {num_nodes} nodes");
+    for (num_threads, elapsed, speedup) in results {
+        println!("{num_threads:>3} threads: {elapsed:>12.3?} speedup {speedup:.2}x");
+    }
+}
@@ -6,17 +6,21 @@ use std::{
     result,
 };
 
-use indexmap::IndexMap;
-use jito_merkle_verify::verify;
+use jito_merkle_verify::verify_with_hash_type;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
-use solana_program::{hash::hashv, pubkey::Pubkey};
+use solana_program::pubkey::Pubkey;
 
 use crate::{
     csv_entry::CsvEntry,
+    csv_validation::{validate_csv_file, DustReport},
     error::MerkleTreeError::{self, MerkleValidationError},
+    hasher::{serde_hash_type, HashType, Hasher},
+    merge_policy::{merge_tree_nodes, MergePolicy, MergedClaimant},
     merkle_tree::MerkleTree,
-    tree_node::TreeNode,
-    utils::{get_proof, get_total_locked_amount, get_total_unlocked_amount},
+    tree_node::{DecimalMode, TreeNode},
+    utils::{get_total_locked_amount, get_total_unlocked_amount},
 };
 
 // proof struct
@@ -32,26 +36,108 @@ pub struct UserProof {
     pub proof: Vec<[u8; 32]>,
 }
 
-// We need to discern between leaf and intermediate nodes to prevent trivial second
-// pre-image attacks.
-// https://flawed.net.nz/2018/02/21/attacking-merkle-trees-with-a-second-preimage-attack
-const LEAF_PREFIX: &[u8] = &[0];
-
 /// Merkle Tree which will be used to distribute tokens to claimants.
 /// Contains all the information necessary to verify claims against the Merkle Tree.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "SerializedAirdropMerkleTree")]
 pub struct AirdropMerkleTree {
     /// The merkle root, which is uploaded on-chain
     pub merkle_root: [u8; 32],
     pub airdrop_version: u64,
+    /// Hash function of the leaves and intermediate nodes, set on the distributor
+    #[serde(default, with = "serde_hash_type")]
+    pub hash_type: HashType,
     pub max_num_nodes: u64,
     pub total_unlocked_amount: u64,
     pub total_locked_amount: u64,
     pub tree_nodes: Vec<TreeNode>,
+    /// Hashes of all levels of the tree, see [MerkleTree::get_nodes]. Proofs are built from
+    /// them, they are not serialized but rebuilt from the nodes when a tree is loaded
+    #[serde(skip)]
+    pub(crate) levels: Vec<[u8; 32]>,
+    /// Index of each claimant in the nodes, lookups scan the nodes without it, see
+    /// [AirdropMerkleTree::build_claimant_index]
+    #[serde(skip)]
+    pub(crate) claimant_index: Option<HashMap<Pubkey, usize>>,
+}
+
+/// Serialized form of [AirdropMerkleTree]
+#[derive(Deserialize)]
+struct SerializedAirdropMerkleTree {
+    merkle_root: [u8; 32],
+    airdrop_version: u64,
+    #[serde(default, with = "serde_hash_type")]
+    hash_type: HashType,
+    max_num_nodes: u64,
+    total_unlocked_amount: u64,
+    total_locked_amount: u64,
+    tree_nodes: Vec<TreeNode>,
+}
+
+impl TryFrom<SerializedAirdropMerkleTree> for AirdropMerkleTree {
+    type Error = MerkleTreeError;
+
+    /// Rebuilds the levels from the nodes, fails if they don't hash to the merkle root of the
+    /// file. The proof per node of files written before the levels were kept is ignored
+    fn try_from(tree: SerializedAirdropMerkleTree) -> Result<Self> {
+        let tree_nodes = tree.tree_nodes;
+        let levels = build_levels(&tree_nodes, tree.hash_type);
+        if levels.last() != Some(&tree.merkle_root) {
+            return Err(MerkleValidationError(
+                "Merkle root does not match the root rebuilt from the nodes".to_string(),
+            ));
+        }
+        Ok(AirdropMerkleTree {
+            merkle_root: tree.merkle_root,
+            airdrop_version: tree.airdrop_version,
+            hash_type: tree.hash_type,
+            max_num_nodes: tree.max_num_nodes,
+            total_unlocked_amount: tree.total_unlocked_amount,
+            total_locked_amount: tree.total_locked_amount,
+            tree_nodes,
+            levels,
+            claimant_index: None,
+        })
+    }
 }
 
 pub type Result<T> = result::Result<T, MerkleTreeError>;
 
+/// Options of a merkle tree build
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AirdropMerkleTreeBuilder {
+    pub airdrop_version: u64,
+    pub hash_type: HashType,
+    /// How the entries of the same claimant are merged
+    pub merge_policy: MergePolicy,
+}
+
+/// Merkle tree built by [AirdropMerkleTreeBuilder]
+#[derive(Debug, Clone)]
+pub struct AirdropMerkleTreeBuild {
+    pub tree: AirdropMerkleTree,
+    /// Claimants with several entries, merged according to the merge policy
+    pub merged_claimants: Vec<MergedClaimant>,
+    /// Dust floored away from the csv amounts, empty unless built from a csv in floor mode
+    pub dust: DustReport,
+}
+
+impl AirdropMerkleTreeBuilder {
+    pub fn build(&self, tree_nodes: Vec<TreeNode>) -> Result<AirdropMerkleTreeBuild> {
+        let (tree_nodes, merged_claimants) = merge_tree_nodes(tree_nodes, self.merge_policy)?;
+        let tree = AirdropMerkleTree::new_from_unique_nodes(
+            tree_nodes,
+            self.airdrop_version,
+            self.hash_type,
+        )?;
+        Ok(AirdropMerkleTreeBuild {
+            tree,
+            merged_claimants,
+            dust: DustReport::default(),
+        })
+    }
+}
+
 impl AirdropMerkleTree {
     pub fn get_max_total_claim(&self) -> u64 {
         self.total_unlocked_amount
@@ -59,49 +145,43 @@ impl AirdropMerkleTree {
             .unwrap()
     }
     pub fn new(tree_nodes: Vec<TreeNode>, airdrop_version: u64) -> Result<Self> {
-        // Combine tree nodes with the same claimant, while retaining original order
-        let mut tree_nodes_map: IndexMap<Pubkey, TreeNode> = IndexMap::new();
-        for tree_node in tree_nodes {
-            let claimant = tree_node.claimant;
-            tree_nodes_map
-                .entry(claimant)
-                .and_modify(|n| {
-                    println!("duplicate claimant {} found, combining", n.claimant);
-                    n.amount = n.amount.checked_add(tree_node.amount).unwrap();
-                    n.locked_amount = n
-                        .locked_amount
-                        .checked_add(tree_node.locked_amount)
-                        .unwrap();
-                })
-                .or_insert_with(|| tree_node); // If not exists, insert a new entry
-        }
-
-        // Convert IndexMap back to Vec while preserving the order
-        let mut tree_nodes: Vec<TreeNode> = tree_nodes_map.values().cloned().collect();
-
-        let hashed_nodes = tree_nodes
-            .iter()
-            .map(|claim_info| claim_info.hash().to_bytes())
-            .collect::<Vec<_>>();
+        Self::new_with_hash_type(tree_nodes, airdrop_version, HashType::Sha256)
+    }
 
-        let tree = MerkleTree::new(&hashed_nodes[..], true);
+    /// Builds the tree with the given hash function, keccak256 for trees shared with EVM chains
+    pub fn new_with_hash_type(
+        tree_nodes: Vec<TreeNode>,
+        airdrop_version: u64,
+        hash_type: HashType,
+    ) -> Result<Self> {
+        let builder = AirdropMerkleTreeBuilder {
+            airdrop_version,
+            hash_type,
+            ..Default::default()
+        };
+        Ok(builder.build(tree_nodes)?.tree)
+    }
 
-        for (i, tree_node) in tree_nodes.iter_mut().enumerate() {
-            tree_node.proof = Some(get_proof(&tree, i));
-        }
+    /// Builds the tree from nodes with unique claimants
+    fn new_from_unique_nodes(
+        tree_nodes: Vec<TreeNode>,
+        airdrop_version: u64,
+        hash_type: HashType,
+    ) -> Result<Self> {
+        let levels = build_levels(&tree_nodes, hash_type);
 
         let total_unlocked_amount = get_total_unlocked_amount(tree_nodes.as_ref());
         let total_locked_amount = get_total_locked_amount(tree_nodes.as_ref());
         let tree = AirdropMerkleTree {
-            merkle_root: tree
-                .get_root()
-                .ok_or(MerkleTreeError::MerkleRootError)?
-                .to_bytes(),
+            merkle_root: *levels.last().ok_or(MerkleTreeError::MerkleRootError)?,
             airdrop_version,
+            hash_type,
             max_num_nodes: tree_nodes.len() as u64,
             total_unlocked_amount,
             total_locked_amount,
             tree_nodes,
+            levels,
+            claimant_index: None,
         };
 
         println!(
@@ -112,28 +192,34 @@ impl AirdropMerkleTree {
         Ok(tree)
     }
 
-    /// Load a merkle tree from a csv path
-    pub fn new_from_csv(path: &PathBuf, version: u64, decimals: u32) -> Result<Self> {
-        let csv_entries = CsvEntry::new_from_file(path)?;
-        let tree_nodes: Vec<TreeNode> = csv_entries
-            .into_iter()
-            .map(|x| TreeNode::from_csv(x, decimals))
-            .collect();
-        let tree = Self::new(tree_nodes, version)?;
-        Ok(tree)
+    /// Load a merkle tree from a csv path. Fails with the validation report if any row is
+    /// invalid, the build reports the dust floored away in floor mode
+    pub fn new_from_csv(
+        path: &PathBuf,
+        decimals: u32,
+        decimal_mode: DecimalMode,
+        builder: &AirdropMerkleTreeBuilder,
+    ) -> Result<AirdropMerkleTreeBuild> {
+        let (csv_entries, report) =
+            validate_csv_file(path, decimals, decimal_mode, builder.merge_policy)?;
+        if !report.is_valid() {
+            return Err(MerkleTreeError::CsvValidationError(Box::new(report)));
+        }
+        let mut build = Self::new_from_entries(csv_entries, decimals, builder)?;
+        build.dust = report.dust;
+        Ok(build)
     }
 
     pub fn new_from_entries(
         csv_entries: Vec<CsvEntry>,
-        version: u64,
         decimals: u32,
-    ) -> Result<Self> {
+        builder: &AirdropMerkleTreeBuilder,
+    ) -> Result<AirdropMerkleTreeBuild> {
         let tree_nodes: Vec<TreeNode> = csv_entries
             .into_iter()
             .map(|x| TreeNode::from_csv(x, decimals))
             .collect();
-        let tree = Self::new(tree_nodes, version)?;
-        Ok(tree)
+        builder.build(tree_nodes)
     }
 
     /// Load a serialized merkle tree from file path
@@ -152,14 +238,77 @@ impl AirdropMerkleTree {
         file.write_all(serialized.as_bytes()).unwrap();
     }
 
-    pub fn get_node(&self, claimant: &Pubkey) -> TreeNode {
-        for i in self.tree_nodes.iter() {
-            if i.claimant == *claimant {
-                return i.clone();
+    /// Proof of the node at the index, built from the levels of the tree
+    pub fn proof_for_index(&self, index: usize) -> Option<Vec<[u8; 32]>> {
+        let sibling_indices = MerkleTree::get_sibling_indices(self.tree_nodes.len(), index)?;
+        Some(
+            sibling_indices
+                .into_iter()
+                .map(|i| self.levels[i])
+                .collect(),
+        )
+    }
+
+    /// Multiproof of the nodes at the indices, which must be strictly increasing: the proof hashes
+    /// and flags to verify all of them at once with [jito_merkle_verify::verify_multiproof]
+    pub fn multiproof_for_indices(&self, indices: &[usize]) -> Option<(Vec<[u8; 32]>, Vec<bool>)> {
+        let (proof_indices, proof_flags) =
+            MerkleTree::get_multiproof_indices(self.tree_nodes.len(), indices)?;
+        let proof = proof_indices.into_iter().map(|i| self.levels[i]).collect();
+        Some((proof, proof_flags))
+    }
+
+    /// Proof of the claimant, built from the levels of the tree
+    pub fn proof_for(&self, claimant: &Pubkey) -> Option<Vec<[u8; 32]>> {
+        self.proof_for_index(self.get_index(claimant)?)
+    }
+
+    /// Indexes the claimants, for constant time lookups of trees queried many times
+    pub fn build_claimant_index(&mut self) {
+        let claimant_index = self
+            .tree_nodes
+            .iter()
+            .enumerate()
+            .map(|(i, n)| (n.claimant, i))
+            .collect();
+        self.claimant_index = Some(claimant_index);
+    }
+
+    /// Index of the claimant in the nodes, none if the claimant is not eligible
+    pub fn get_index(&self, claimant: &Pubkey) -> Option<usize> {
+        match &self.claimant_index {
+            Some(claimant_index) => claimant_index.get(claimant).copied(),
+            None => self.tree_nodes.iter().position(|n| n.claimant == *claimant),
+        }
+    }
+
+    /// Node of the claimant, none if the claimant is not eligible
+    pub fn get_node(&self, claimant: &Pubkey) -> Option<&TreeNode> {
+        self.get_index(claimant).map(|i| &self.tree_nodes[i])
+    }
+
+    /// Indices of the claimants, in a single scan of the nodes without the claimant index
+    pub fn get_indices(&self, claimants: &[Pubkey]) -> Vec<Option<usize>> {
+        if self.claimant_index.is_some() {
+            return claimants.iter().map(|c| self.get_index(c)).collect();
+        }
+
+        let mut indices: HashMap<Pubkey, Option<usize>> =
+            claimants.iter().map(|c| (*c, None)).collect();
+        for (i, node) in self.tree_nodes.iter().enumerate() {
+            if let Some(index) = indices.get_mut(&node.claimant) {
+                *index = Some(i);
             }
         }
+        claimants.iter().map(|c| indices[c]).collect()
+    }
 
-        panic!("Claimant not found in tree");
+    /// Nodes of the claimants, see [AirdropMerkleTree::get_indices]
+    pub fn get_nodes(&self, claimants: &[Pubkey]) -> Vec<Option<&TreeNode>> {
+        self.get_indices(claimants)
+            .into_iter()
+            .map(|index| index.map(|i| &self.tree_nodes[i]))
+            .collect()
     }
 
     fn validate(&self) -> Result<()> {
@@ -216,33 +365,30 @@ impl AirdropMerkleTree {
         Ok(())
     }
 
-    /// verify that the leaves of the merkle tree match the nodes
+    /// verify that the leaves of the merkle tree match the nodes, against the levels the tree
+    /// was built with
     pub fn verify_proof(&self) -> Result<()> {
         let root = self.merkle_root;
 
-        // Recreate root given nodes
-        let hashed_nodes: Vec<[u8; 32]> = self
-            .tree_nodes
-            .iter()
-            .map(|n| n.hash().to_bytes())
-            .collect();
-        let mk = MerkleTree::new(&hashed_nodes[..], true);
-
-        assert_eq!(
-            mk.get_root()
-                .ok_or(MerkleValidationError("invalid merkle proof".to_string()))?
-                .to_bytes(),
-            root
-        );
-
-        // Verify each node against the root
-        for (i, _node) in hashed_nodes.iter().enumerate() {
-            let node = hashv(&[LEAF_PREFIX, &hashed_nodes[i]]);
-            let proof = get_proof(&mk, i);
+        if self.levels.last() != Some(&root) {
+            return Err(MerkleValidationError("invalid merkle proof".to_string()));
+        }
 
-            if !verify(proof, root, node.to_bytes()) {
-                return Err(MerkleValidationError("invalid merkle proof".to_string()));
-            }
+        // Verify each node against the root, with the proof built from the levels
+        let hashed_nodes = hash_tree_nodes(&self.tree_nodes, self.hash_type);
+        #[cfg(feature = "parallel")]
+        let indices = (0..hashed_nodes.len()).into_par_iter();
+        #[cfg(not(feature = "parallel"))]
+        let mut indices = 0..hashed_nodes.len();
+        let valid = indices.all(|i| {
+            let node = self.hash_type.hash_leaf(&hashed_nodes[i]).to_bytes();
+            self.levels[i] == node
+                && self
+                    .proof_for_index(i)
+                    .is_some_and(|proof| verify_with_hash_type(proof, root, node, self.hash_type))
+        });
+        if !valid {
+            return Err(MerkleValidationError("invalid merkle proof".to_string()));
         }
 
         Ok(())
@@ -257,11 +403,43 @@ impl AirdropMerkleTree {
     }
 }
 
+/// Hashes the claims of the nodes, with rayon if the parallel feature is enabled
+fn hash_tree_nodes(tree_nodes: &[TreeNode], hash_type: HashType) -> Vec<[u8; 32]> {
+    #[cfg(feature = "parallel")]
+    let nodes_iter = tree_nodes.par_iter();
+    #[cfg(not(feature = "parallel"))]
+    let nodes_iter = tree_nodes.iter();
+    nodes_iter
+        .map(|n| n.hash_with(&hash_type).to_bytes())
+        .collect()
+}
+
+/// Builds the levels of the tree with rayon
+#[cfg(feature = "parallel")]
+fn build_merkle_tree(hashed_nodes: &[[u8; 32]], hash_type: HashType) -> MerkleTree {
+    MerkleTree::new_with_hasher_par(hashed_nodes, true, &hash_type)
+}
+
+#[cfg(not(feature = "parallel"))]
+fn build_merkle_tree(hashed_nodes: &[[u8; 32]], hash_type: HashType) -> MerkleTree {
+    MerkleTree::new_with_hasher(hashed_nodes, true, &hash_type)
+}
+
+/// Hashes of all levels of the tree of the nodes
+fn build_levels(tree_nodes: &[TreeNode], hash_type: HashType) -> Vec<[u8; 32]> {
+    let hashed_nodes = hash_tree_nodes(tree_nodes, hash_type);
+    build_merkle_tree(&hashed_nodes, hash_type)
+        .get_nodes()
+        .iter()
+        .map(|hash| hash.to_bytes())
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use std::path::PathBuf;
 
-    use solana_program::{pubkey, pubkey::Pubkey};
+    use solana_program::{keccak, pubkey, pubkey::Pubkey};
     use solana_sdk::{
         signature::{EncodableKey, Keypair},
         signer::Signer,
@@ -292,7 +470,6 @@ mod tests {
                 claimant: new_test_key(),
                 amount: rand_balance(),
                 locked_amount: rand_balance(),
-                proof: None,
             });
         }
 
@@ -307,7 +484,6 @@ mod tests {
             claimant: Pubkey::default(),
             amount: 2,
             locked_amount: 0,
-            proof: None,
         }];
         let merkle_tree = AirdropMerkleTree::new(tree_nodes, 0).unwrap();
         assert!(merkle_tree.verify_proof().is_ok(), "verify failed");
@@ -321,19 +497,16 @@ mod tests {
                 claimant: pubkey!("FLYqJsmJ5AGMxMxK3Qy1rSen4ES2dqqo6h51W3C1tYS"),
                 amount: (100 * u64::pow(10, 9)),
                 locked_amount: 0,
-                proof: None,
             },
             TreeNode {
                 claimant: pubkey!("EDGARWktv3nDxRYjufjdbZmryqGXceaFPoPpbUzdpqED"),
                 amount: (100 * u64::pow(10, 9)),
                 locked_amount: 0,
-                proof: None,
             },
             TreeNode {
                 claimant: pubkey!("EDGARWktv3nDxRYjufjdbZmryqGXceaFPoPpbUzdpqEH"),
                 amount: (100 * u64::pow(10, 9)),
                 locked_amount: 1,
-                proof: None,
             },
         ];
 
@@ -363,25 +536,220 @@ mod tests {
                 claimant: duplicate_pubkey,
                 amount: 10,
                 locked_amount: 10,
-                proof: None,
             },
             TreeNode {
                 claimant: duplicate_pubkey,
                 amount: 1,
                 locked_amount: 10,
-                proof: None,
             },
             TreeNode {
                 claimant: Pubkey::new_unique(),
                 amount: 0,
                 locked_amount: 10,
-                proof: None,
             },
         ];
 
-        let tree = AirdropMerkleTree::new(tree_nodes, 0).unwrap();
+        let tree = AirdropMerkleTree::new(tree_nodes.clone(), 0).unwrap();
         assert_eq!(tree.tree_nodes.len(), 2);
         assert_eq!(tree.tree_nodes[0].amount, 11);
         assert_eq!(tree.tree_nodes[0].locked_amount, 20);
+
+        let builder = AirdropMerkleTreeBuilder {
+            merge_policy: MergePolicy::Last,
+            ..Default::default()
+        };
+        let build = builder.build(tree_nodes.clone()).unwrap();
+        assert_eq!(build.tree.tree_nodes.len(), 2);
+        assert_eq!(build.tree.tree_nodes[0].amount, 1);
+        assert_eq!(build.merged_claimants.len(), 1);
+        assert_eq!(build.merged_claimants[0].claimant, duplicate_pubkey);
+        assert_eq!(build.merged_claimants[0].num_entries, 2);
+
+        let builder = AirdropMerkleTreeBuilder {
+            merge_policy: MergePolicy::Error,
+            ..Default::default()
+        };
+        assert!(builder.build(tree_nodes).is_err());
+    }
+
+    #[test]
+    fn test_keccak_merkle_tree() {
+        let tree_nodes = (0..5)
+            .map(|i| TreeNode {
+                claimant: Pubkey::new_unique(),
+                amount: i * 10,
+                locked_amount: i,
+            })
+            .collect::<Vec<_>>();
+
+        let sha256_tree = AirdropMerkleTree::new(tree_nodes.clone(), 0).unwrap();
+        let keccak_tree =
+            AirdropMerkleTree::new_with_hash_type(tree_nodes, 0, HashType::Keccak256).unwrap();
+        assert_ne!(sha256_tree.merkle_root, keccak_tree.merkle_root);
+
+        for (i, node) in keccak_tree.tree_nodes.iter().enumerate() {
+            // leaf of an OpenZeppelin StandardMerkleTree of (bytes32, uint64, uint64)
+            let mut encoded = node.claimant.to_bytes().to_vec();
+            for amount in [node.amount, node.locked_amount] {
+                encoded.extend_from_slice(&[0u8; 24]);
+                encoded.extend_from_slice(&amount.to_be_bytes());
+            }
+            let leaf = keccak::hashv(&[&keccak::hash(&encoded).to_bytes()]).to_bytes();
+            assert_eq!(
+                leaf,
+                HashType::Keccak256.claim_leaf(
+                    &node.claimant.to_bytes(),
+                    node.amount,
+                    node.locked_amount
+                )
+            );
+            let proof = keccak_tree.proof_for_index(i).unwrap();
+            assert!(verify_with_hash_type(
+                proof.clone(),
+                keccak_tree.merkle_root,
+                leaf,
+                HashType::Keccak256
+            ));
+            assert!(!verify_with_hash_type(
+                proof,
+                keccak_tree.merkle_root,
+                leaf,
+                HashType::Sha256
+            ));
+        }
+
+        // the hash type is kept in the file, trees written without it are sha256
+        let mut value = serde_json::to_value(&keccak_tree).unwrap();
+        assert_eq!(value["hash_type"], "keccak256");
+        let read: AirdropMerkleTree = serde_json::from_value(value.clone()).unwrap();
+        assert_eq!(read.hash_type, HashType::Keccak256);
+        assert!(read.verify_proof().is_ok());
+
+        // read as sha256, the nodes don't hash to the keccak256 root
+        value.as_object_mut().unwrap().remove("hash_type");
+        assert!(serde_json::from_value::<AirdropMerkleTree>(value).is_err());
+
+        let sha256_tree = AirdropMerkleTree::new(keccak_tree.tree_nodes.clone(), 0).unwrap();
+        let mut value = serde_json::to_value(&sha256_tree).unwrap();
+        value.as_object_mut().unwrap().remove("hash_type");
+        let read: AirdropMerkleTree = serde_json::from_value(value).unwrap();
+        assert_eq!(read.hash_type, HashType::Sha256);
+    }
+
+    #[test]
+    fn test_proofs_from_levels() {
+        let tree_nodes = (0..7)
+            .map(|i| TreeNode {
+                claimant: Pubkey::new_unique(),
+                amount: i,
+                locked_amount: 0,
+            })
+            .collect::<Vec<_>>();
+        let tree = AirdropMerkleTree::new(tree_nodes, 0).unwrap();
+
+        let hashed_nodes = tree
+            .tree_nodes
+            .iter()
+            .map(|n| n.hash().to_bytes())
+            .collect::<Vec<_>>();
+        let mk = MerkleTree::new(&hashed_nodes[..], true);
+        for (i, node) in tree.tree_nodes.iter().enumerate() {
+            let proof = crate::utils::get_proof(&mk, i);
+            assert_eq!(tree.proof_for_index(i), Some(proof.clone()));
+            assert_eq!(tree.proof_for(&node.claimant), Some(proof));
+        }
+        assert_eq!(tree.proof_for_index(7), None);
+
+        let indices = [1, 2, 3, 6];
+        let (proof, proof_flags) = tree.multiproof_for_indices(&indices).unwrap();
+        let leaves = indices.iter().map(|i| tree.levels[*i]).collect();
+        assert!(jito_merkle_verify::verify_multiproof(
+            proof,
+            proof_flags,
+            tree.merkle_root,
+            leaves
+        ));
+        assert_eq!(tree.multiproof_for_indices(&[2, 1]), None);
+        assert_eq!(tree.proof_for(&Pubkey::new_unique()), None);
+
+        // proofs aren't written, files with a proof per node still load
+        let mut value = serde_json::to_value(&tree).unwrap();
+        assert!(value["tree_nodes"][0].get("proof").is_none());
+        for (i, node) in value["tree_nodes"]
+            .as_array_mut()
+            .unwrap()
+            .iter_mut()
+            .enumerate()
+        {
+            node["proof"] = serde_json::to_value(tree.proof_for_index(i)).unwrap();
+        }
+        let read: AirdropMerkleTree = serde_json::from_value(value.clone()).unwrap();
+        assert_eq!(read.tree_nodes, tree.tree_nodes);
+        assert_eq!(read.levels, tree.levels);
+        assert!(read.verify_proof().is_ok());
+
+        // a file whose nodes don't hash to its root is rejected
+        value["tree_nodes"][0]["amount"] = serde_json::to_value(100).unwrap();
+        assert!(serde_json::from_value::<AirdropMerkleTree>(value).is_err());
+    }
+
+    #[test]
+    fn test_claimant_lookups() {
+        let tree_nodes = (0..5)
+            .map(|i| TreeNode {
+                claimant: Pubkey::new_unique(),
+                amount: i,
+                locked_amount: 0,
+            })
+            .collect::<Vec<_>>();
+        let mut tree = AirdropMerkleTree::new(tree_nodes.clone(), 0).unwrap();
+        let not_eligible = Pubkey::new_unique();
+        let claimants = [
+            tree_nodes[3].claimant,
+            not_eligible,
+            tree_nodes[0].claimant,
+            tree_nodes[3].claimant,
+        ];
+
+        for indexed in [false, true] {
+            if indexed {
+                tree.build_claimant_index();
+            }
+            assert_eq!(tree.get_index(&tree_nodes[2].claimant), Some(2));
+            assert_eq!(tree.get_node(&tree_nodes[2].claimant), Some(&tree_nodes[2]));
+            assert_eq!(tree.get_node(&not_eligible), None);
+            assert_eq!(tree.proof_for(&not_eligible), None);
+            assert_eq!(
+                tree.get_indices(&claimants),
+                vec![Some(3), None, Some(0), Some(3)]
+            );
+            assert_eq!(
+                tree.get_nodes(&claimants),
+                vec![
+                    Some(&tree_nodes[3]),
+                    None,
+                    Some(&tree_nodes[0]),
+                    Some(&tree_nodes[3])
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn test_new_from_csv_decimal_mode() {
+        let path = PathBuf::from("./test_fixtures/test_csv_decimal.csv");
+        let builder = AirdropMerkleTreeBuilder::default();
+        let result = AirdropMerkleTree::new_from_csv(&path, 6, DecimalMode::Strict, &builder);
+        match result {
+            Err(MerkleTreeError::CsvValidationError(report)) => {
+                assert_eq!(report.errors.len(), 6)
+            }
+            _ => panic!("expected a csv validation error"),
+        }
+
+        let build =
+            AirdropMerkleTree::new_from_csv(&path, 6, DecimalMode::Floor, &builder).unwrap();
+        assert_eq!(build.tree.tree_nodes[0].amount, 1000123456);
+        assert_eq!(build.dust.num_floored_amounts, 6);
     }
 }
@@ -0,0 +1,454 @@
+//! Compact binary format of an [AirdropMerkleTree]. The file stores the leaves and the hashes of
+//! every level once, proofs are derived on demand, so that a memory-mapped file can serve proofs
+//! without being deserialized.
+//!
+//! Layout, all integers little endian:
+//! - header of [HEADER_LEN] bytes: magic, format version, hash type, airdrop version, number of
+//!   nodes, total unlocked amount, total locked amount and merkle root
+//! - one leaf of [LEAF_LEN] bytes per node: claimant, amount and locked amount
+//! - the hashes of all levels of the tree, from the leaves to the root, see
+//!   [MerkleTree::get_nodes]
+//! - the claimant index: the u32 index of every leaf, ordered by claimant, so a claimant is
+//!   found by binary search without reading the other leaves
+use std::{
+    cmp::Ordering,
+    fs::File,
+    io::{BufWriter, Write},
+    path::PathBuf,
+};
+
+use memmap2::Mmap;
+use solana_program::pubkey::Pubkey;
+
+use crate::{
+    airdrop_merkle_tree::{AirdropMerkleTree, Result},
+    error::MerkleTreeError::{self, BinaryTreeError},
+    hasher::HashType,
+    merkle_tree::MerkleTree,
+    tree_node::TreeNode,
+};
+
+pub const MAGIC: &[u8; 8] = b"MDBTREE\0";
+/// Version of the layout, bumped on any change. Version 2 appends the claimant index
+pub const FORMAT_VERSION: u32 = 2;
+pub const HEADER_LEN: usize = 80;
+pub const LEAF_LEN: usize = 48;
+const HASH_LEN: usize = 32;
+const INDEX_LEN: usize = 4;
+
+/// Header of a binary tree file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BinaryTreeHeader {
+    pub format_version: u32,
+    pub hash_type: HashType,
+    pub airdrop_version: u64,
+    pub max_num_nodes: u64,
+    pub total_unlocked_amount: u64,
+    pub total_locked_amount: u64,
+    pub merkle_root: [u8; 32],
+}
+
+impl BinaryTreeHeader {
+    fn to_bytes(self) -> [u8; HEADER_LEN] {
+        let mut bytes = [0u8; HEADER_LEN];
+        bytes[0..8].copy_from_slice(MAGIC);
+        bytes[8..12].copy_from_slice(&self.format_version.to_le_bytes());
+        bytes[12] = self.hash_type.into();
+        // 13..16 is padding
+        bytes[16..24].copy_from_slice(&self.airdrop_version.to_le_bytes());
+        bytes[24..32].copy_from_slice(&self.max_num_nodes.to_le_bytes());
+        bytes[32..40].copy_from_slice(&self.total_unlocked_amount.to_le_bytes());
+        bytes[40..48].copy_from_slice(&self.total_locked_amount.to_le_bytes());
+        bytes[48..80].copy_from_slice(&self.merkle_root);
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() < HEADER_LEN {
+            return Err(BinaryTreeError("file shorter than the header".to_string()));
+        }
+        if &bytes[0..8] != MAGIC {
+            return Err(BinaryTreeError("not a binary merkle tree".to_string()));
+        }
+        let format_version = u32::from_le_bytes(bytes[8..12].try_into().unwrap());
+        if format_version != FORMAT_VERSION {
+            return Err(BinaryTreeError(format!(
+                "unsupported format version {format_version}, expected {FORMAT_VERSION}"
+            )));
+        }
+        let hash_type = HashType::try_from(bytes[12])
+            .map_err(|hash_type| BinaryTreeError(format!("invalid hash type {hash_type}")))?;
+        Ok(BinaryTreeHeader {
+            format_version,
+            hash_type,
+            airdrop_version: read_u64(bytes, 16),
+            max_num_nodes: read_u64(bytes, 24),
+            total_unlocked_amount: read_u64(bytes, 32),
+            total_locked_amount: read_u64(bytes, 40),
+            merkle_root: bytes[48..80].try_into().unwrap(),
+        })
+    }
+}
+
+fn read_u64(bytes: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap())
+}
+
+/// Writes the tree in the binary format. The levels of the tree are written as they are, they
+/// must match the number of nodes and end with the merkle root, see [BinaryMerkleTree::verify]
+/// to check them against the nodes
+pub fn write_binary_tree<W: Write>(tree: &AirdropMerkleTree, writer: &mut W) -> Result<()> {
+    let num_nodes = tree.tree_nodes.len();
+    if num_nodes > u32::MAX as usize {
+        return Err(BinaryTreeError(format!(
+            "{num_nodes} nodes do not fit the u32 claimant index"
+        )));
+    }
+    if tree.levels.len() != MerkleTree::get_node_count(num_nodes) {
+        return Err(BinaryTreeError(format!(
+            "{} level hashes do not match {} nodes",
+            tree.levels.len(),
+            num_nodes
+        )));
+    }
+    if tree.levels.last() != Some(&tree.merkle_root) {
+        return Err(BinaryTreeError(
+            "levels do not end with the merkle root".to_string(),
+        ));
+    }
+
+    let mut claimant_index: Vec<u32> = (0..num_nodes as u32).collect();
+    claimant_index.sort_unstable_by_key(|i| tree.tree_nodes[*i as usize].claimant);
+
+    let header = BinaryTreeHeader {
+        format_version: FORMAT_VERSION,
+        hash_type: tree.hash_type,
+        airdrop_version: tree.airdrop_version,
+        max_num_nodes: num_nodes as u64,
+        total_unlocked_amount: tree.total_unlocked_amount,
+        total_locked_amount: tree.total_locked_amount,
+        merkle_root: tree.merkle_root,
+    };
+    writer.write_all(&header.to_bytes())?;
+    for node in tree.tree_nodes.iter() {
+        writer.write_all(&node.claimant.to_bytes())?;
+        writer.write_all(&node.amount.to_le_bytes())?;
+        writer.write_all(&node.locked_amount.to_le_bytes())?;
+    }
+    for hash in tree.levels.iter() {
+        writer.write_all(hash)?;
+    }
+    for index in claimant_index {
+        writer.write_all(&index.to_le_bytes())?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Writes the tree in the binary format to a file path
+pub fn write_binary_tree_to_file(tree: &AirdropMerkleTree, path: &PathBuf) -> Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    write_binary_tree(tree, &mut writer)
+}
+
+/// Merkle tree in the binary format, read in place from a memory-mapped file or any buffer
+#[derive(Debug)]
+pub struct BinaryMerkleTree<B = Mmap> {
+    bytes: B,
+    header: BinaryTreeHeader,
+}
+
+impl BinaryMerkleTree<Mmap> {
+    /// Maps a binary tree file into memory, only the header is read
+    pub fn open(path: &PathBuf) -> Result<Self> {
+        let file = File::open(path)?;
+        // Safety: the file must not be modified while it is mapped, tree files are written once
+        let mmap = unsafe { Mmap::map(&file)? };
+        Self::from_bytes(mmap)
+    }
+}
+
+impl<B: AsRef<[u8]>> BinaryMerkleTree<B> {
+    /// Checks the header and the length of the buffer
+    pub fn from_bytes(bytes: B) -> Result<Self> {
+        let header = BinaryTreeHeader::from_bytes(bytes.as_ref())?;
+        // The Merkle tree can be at most height 32, implying a max node count of 2^32 - 1
+        if header.max_num_nodes > 2u64.pow(32) - 1 {
+            return Err(BinaryTreeError(format!(
+                "Max num nodes {} is greater than 2^32 - 1",
+                header.max_num_nodes
+            )));
+        }
+        let num_nodes = header.max_num_nodes as usize;
+        let expected_len = HEADER_LEN
+            + num_nodes * LEAF_LEN
+            + MerkleTree::get_node_count(num_nodes) * HASH_LEN
+            + num_nodes * INDEX_LEN;
+        if bytes.as_ref().len() != expected_len {
+            return Err(BinaryTreeError(format!(
+                "file length {} does not match {} nodes, expected {}",
+                bytes.as_ref().len(),
+                num_nodes,
+                expected_len
+            )));
+        }
+        Ok(BinaryMerkleTree { bytes, header })
+    }
+
+    pub fn header(&self) -> &BinaryTreeHeader {
+        &self.header
+    }
+
+    pub fn num_nodes(&self) -> usize {
+        self.header.max_num_nodes as usize
+    }
+
+    fn leaf_bytes(&self, index: usize) -> &[u8] {
+        let start = HEADER_LEN + index * LEAF_LEN;
+        &self.bytes.as_ref()[start..start + LEAF_LEN]
+    }
+
+    fn hash_bytes(&self, index: usize) -> [u8; 32] {
+        let start = HEADER_LEN + self.num_nodes() * LEAF_LEN + index * HASH_LEN;
+        self.bytes.as_ref()[start..start + HASH_LEN]
+            .try_into()
+            .unwrap()
+    }
+
+    /// Index of the leaf of the i-th claimant in claimant order
+    fn claimant_index(&self, i: usize) -> usize {
+        let start = HEADER_LEN
+            + self.num_nodes() * LEAF_LEN
+            + MerkleTree::get_node_count(self.num_nodes()) * HASH_LEN
+            + i * INDEX_LEN;
+        u32::from_le_bytes(
+            self.bytes.as_ref()[start..start + INDEX_LEN]
+                .try_into()
+                .unwrap(),
+        ) as usize
+    }
+
+    fn claimant_at(&self, index: usize) -> &[u8] {
+        &self.leaf_bytes(index)[0..32]
+    }
+
+    /// Node at the index of the tree, see [BinaryMerkleTree::proof_for_index] for its proof
+    pub fn get_node_at(&self, index: usize) -> Option<TreeNode> {
+        if index >= self.num_nodes() {
+            return None;
+        }
+        let leaf = self.leaf_bytes(index);
+        Some(TreeNode {
+            claimant: Pubkey::new_from_array(leaf[0..32].try_into().unwrap()),
+            amount: read_u64(leaf, 32),
+            locked_amount: read_u64(leaf, 40),
+        })
+    }
+
+    /// Index of the claimant in the tree, binary searched in the claimant index
+    pub fn find_index(&self, claimant: &Pubkey) -> Option<usize> {
+        let (mut low, mut high) = (0, self.num_nodes());
+        while low < high {
+            let mid = low + (high - low) / 2;
+            let index = self.claimant_index(mid);
+            match self.claimant_at(index).cmp(claimant.as_ref()) {
+                Ordering::Less => low = mid + 1,
+                Ordering::Greater => high = mid,
+                Ordering::Equal => return Some(index),
+            }
+        }
+        None
+    }
+
+    pub fn get_node(&self, claimant: &Pubkey) -> Option<TreeNode> {
+        self.get_node_at(self.find_index(claimant)?)
+    }
+
+    /// Proof of the node at the index, read from the stored levels
+    pub fn proof_for_index(&self, index: usize) -> Option<Vec<[u8; 32]>> {
+        let sibling_indices = MerkleTree::get_sibling_indices(self.num_nodes(), index)?;
+        Some(
+            sibling_indices
+                .into_iter()
+                .map(|i| self.hash_bytes(i))
+                .collect(),
+        )
+    }
+
+    /// Rebuilds the levels from the leaves and checks them against the stored ones, then checks
+    /// that the claimant index lists every leaf in claimant order
+    pub fn verify(&self) -> Result<()> {
+        let hashed_nodes = (0..self.num_nodes())
+            .map(|i| {
+                let node = self.get_node_at(i).unwrap();
+                node.hash_with(&self.header.hash_type)
+            })
+            .collect::<Vec<_>>();
+        let mk = MerkleTree::new_with_hasher(&hashed_nodes[..], true, &self.header.hash_type);
+        let root = mk.get_root().ok_or(MerkleTreeError::MerkleRootError)?;
+        if root.to_bytes() != self.header.merkle_root {
+            return Err(BinaryTreeError(
+                "merkle root is invalid given nodes".to_string(),
+            ));
+        }
+        for (i, hash) in mk.get_nodes().iter().enumerate() {
+            if hash.to_bytes() != self.hash_bytes(i) {
+                return Err(BinaryTreeError(format!("stored hash {i} is invalid")));
+            }
+        }
+        // strictly increasing claimants of in range indices, so each leaf is listed once
+        for i in 0..self.num_nodes() {
+            let index = self.claimant_index(i);
+            if index >= self.num_nodes()
+                || (i > 0
+                    && self.claimant_at(self.claimant_index(i - 1)) >= self.claimant_at(index))
+            {
+                return Err(BinaryTreeError(format!(
+                    "claimant index {i} is out of order"
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Deserializes every node and the levels
+    pub fn to_airdrop_merkle_tree(&self) -> AirdropMerkleTree {
+        let tree_nodes = (0..self.num_nodes())
+            .map(|i| self.get_node_at(i).unwrap())
+            .collect();
+        let levels = (0..MerkleTree::get_node_count(self.num_nodes()))
+            .map(|i| self.hash_bytes(i))
+            .collect();
+        AirdropMerkleTree {
+            merkle_root: self.header.merkle_root,
+            airdrop_version: self.header.airdrop_version,
+            hash_type: self.header.hash_type,
+            max_num_nodes: self.header.max_num_nodes,
+            total_unlocked_amount: self.header.total_unlocked_amount,
+            total_locked_amount: self.header.total_locked_amount,
+            tree_nodes,
+            levels,
+            claimant_index: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_test_tree(num_nodes: u64, hash_type: HashType) -> AirdropMerkleTree {
+        let tree_nodes = (0..num_nodes)
+            .map(|i| TreeNode {
+                claimant: Pubkey::new_unique(),
+                amount: i * 100,
+                locked_amount: i,
+            })
+            .collect();
+        AirdropMerkleTree::new_with_hash_type(tree_nodes, 3, hash_type).unwrap()
+    }
+
+    #[test]
+    fn test_binary_tree_round_trip() {
+        for (num_nodes, hash_type) in [(1, HashType::Sha256), (7, HashType::Keccak256)] {
+            let tree = new_test_tree(num_nodes, hash_type);
+            let mut bytes = Vec::new();
+            write_binary_tree(&tree, &mut bytes).unwrap();
+
+            let binary_tree = BinaryMerkleTree::from_bytes(bytes).unwrap();
+            binary_tree.verify().unwrap();
+            assert_eq!(binary_tree.header().hash_type, hash_type);
+            assert_eq!(binary_tree.header().airdrop_version, 3);
+            for (i, node) in tree.tree_nodes.iter().enumerate() {
+                assert_eq!(binary_tree.get_node(&node.claimant).as_ref(), Some(node));
+                assert_eq!(binary_tree.proof_for_index(i), tree.proof_for_index(i));
+            }
+            assert_eq!(binary_tree.get_node(&Pubkey::new_unique()), None);
+
+            let read = binary_tree.to_airdrop_merkle_tree();
+            assert_eq!(
+                serde_json::to_value(&read).unwrap(),
+                serde_json::to_value(&tree).unwrap()
+            );
+            assert_eq!(read.levels, tree.levels);
+        }
+    }
+
+    #[test]
+    fn test_binary_tree_file() {
+        let tree = new_test_tree(10, HashType::Sha256);
+        let path = PathBuf::from("merkle_tree_test.bin");
+        write_binary_tree_to_file(&tree, &path).unwrap();
+
+        let binary_tree = BinaryMerkleTree::open(&path).unwrap();
+        assert_eq!(binary_tree.num_nodes(), 10);
+        assert_eq!(
+            binary_tree.get_node_at(4).as_ref(),
+            Some(&tree.tree_nodes[4])
+        );
+        assert_eq!(binary_tree.get_node_at(10), None);
+    }
+
+    #[test]
+    fn test_binary_tree_invalid() {
+        let tree = new_test_tree(5, HashType::Sha256);
+        let mut bytes = Vec::new();
+        write_binary_tree(&tree, &mut bytes).unwrap();
+
+        assert!(BinaryMerkleTree::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(BinaryMerkleTree::from_bytes(&bytes[..HEADER_LEN - 1]).is_err());
+
+        let mut unknown_version = bytes.clone();
+        unknown_version[8] = 1;
+        assert!(BinaryMerkleTree::from_bytes(unknown_version).is_err());
+
+        // a tampered amount is caught by verify only
+        let mut tampered = bytes.clone();
+        tampered[HEADER_LEN + 32] ^= 1;
+        let binary_tree = BinaryMerkleTree::from_bytes(tampered).unwrap();
+        assert!(binary_tree.verify().is_err());
+
+        // so is a claimant index out of order
+        let mut tampered = bytes.clone();
+        let len = tampered.len();
+        tampered.swap(len - 4, len - 8);
+        let binary_tree = BinaryMerkleTree::from_bytes(tampered).unwrap();
+        assert!(binary_tree.verify().is_err());
+
+        // the levels are written as they are, they must only fit the nodes and the root
+        let mut tampered_tree = tree.clone();
+        tampered_tree.tree_nodes[0].amount += 1;
+        let mut tampered = Vec::new();
+        write_binary_tree(&tampered_tree, &mut tampered).unwrap();
+        assert!(BinaryMerkleTree::from_bytes(tampered)
+            .unwrap()
+            .verify()
+            .is_err());
+
+        let mut tampered_tree = tree.clone();
+        tampered_tree.levels.pop();
+        assert!(write_binary_tree(&tampered_tree, &mut Vec::new()).is_err());
+
+        let mut tampered_tree = tree.clone();
+        tampered_tree.merkle_root = [0; 32];
+        assert!(write_binary_tree(&tampered_tree, &mut Vec::new()).is_err());
+    }
+
+    #[test]
+    fn test_binary_tree_find_index() {
+        let tree = new_test_tree(100, HashType::Sha256);
+        let mut bytes = Vec::new();
+        write_binary_tree(&tree, &mut bytes).unwrap();
+
+        let binary_tree = BinaryMerkleTree::from_bytes(bytes).unwrap();
+        binary_tree.verify().unwrap();
+        for (i, node) in tree.tree_nodes.iter().enumerate() {
+            assert_eq!(binary_tree.find_index(&node.claimant), Some(i));
+        }
+        assert_eq!(binary_tree.find_index(&Pubkey::default()), None);
+        assert_eq!(
+            binary_tree.find_index(&Pubkey::new_from_array([255; 32])),
+            None
+        );
+    }
+}
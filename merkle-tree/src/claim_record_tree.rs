@@ -0,0 +1,395 @@
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{BufReader, Write},
+    path::PathBuf,
+    result,
+};
+
+use jito_merkle_verify::verify_with_path;
+use serde::{Deserialize, Serialize};
+use solana_program::{hash::hashv, pubkey::Pubkey};
+
+use crate::{error::MerkleTreeError, hasher::HashType, tree_node::TreeNode};
+
+// Same prefixes as the claim record tree of the program
+const INTERMEDIATE_PREFIX: &[u8] = &[1];
+const RECORD_PREFIX: &[u8] = &[2];
+
+/// Max depth of the canopy of the program, the largest one fitting the 10MB account size limit
+pub const MAX_CANOPY_DEPTH: u32 = 17;
+/// Nodes of the record proof left in a claim transaction by the canopy, a claim of a tree of
+/// depth 20 with a priority fee and an airdrop proof of 19 nodes then fits in a transaction
+pub const MAX_CLAIM_PROOF_LEN: u32 = 3;
+
+/// Claim of a compressed distributor, stored as a leaf of its claim record tree
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ClaimRecord {
+    /// Pubkey of the claimant
+    pub claimant: Pubkey,
+    /// Unlocked amount
+    pub unlocked_amount: u64,
+    /// Locked amount
+    pub locked_amount: u64,
+    /// Locked amount withdrawn
+    pub locked_amount_withdrawn: u64,
+    /// Bonus amount
+    pub bonus_amount: u64,
+}
+
+impl ClaimRecord {
+    pub fn hash(&self) -> [u8; 32] {
+        hashv(&[
+            RECORD_PREFIX,
+            &self.claimant.to_bytes(),
+            &self.unlocked_amount.to_le_bytes(),
+            &self.locked_amount.to_le_bytes(),
+            &self.locked_amount_withdrawn.to_le_bytes(),
+            &self.bonus_amount.to_le_bytes(),
+        ])
+        .to_bytes()
+    }
+}
+
+/// Claim records of a claim record tree, as replayed from its events by the local indexer
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ClaimRecordIndex {
+    /// Claim record tree account
+    pub claim_record_tree: Pubkey,
+    /// Depth of the claim record tree
+    pub max_depth: u32,
+    /// Depth of the canopy of the claim record tree
+    pub canopy_depth: u32,
+    /// Sequence number of the last replayed update
+    pub sequence_number: u64,
+    /// Last transaction signature replayed, the next run starts after it
+    pub last_signature: Option<String>,
+    /// Latest record of each claimed leaf, by leaf index
+    pub records: BTreeMap<u32, ClaimRecord>,
+}
+
+pub type Result<T> = result::Result<T, MerkleTreeError>;
+
+impl ClaimRecordIndex {
+    pub fn new(claim_record_tree: Pubkey, max_depth: u32, canopy_depth: u32) -> Self {
+        Self {
+            claim_record_tree,
+            max_depth,
+            canopy_depth,
+            ..Default::default()
+        }
+    }
+
+    /// Load a claim record index from a filepath
+    pub fn new_from_file(path: &PathBuf) -> Result<Self> {
+        let file = File::open(path)?;
+        let reader = BufReader::new(file);
+        let index: ClaimRecordIndex = serde_json::from_reader(reader)?;
+
+        Ok(index)
+    }
+
+    /// Write a claim record index to a filepath
+    pub fn write_to_file(&self, path: &PathBuf) {
+        let serialized = serde_json::to_string_pretty(&self).unwrap();
+        let mut file = File::create(path).unwrap();
+        file.write_all(serialized.as_bytes()).unwrap();
+    }
+
+    /// Applies an update of the tree, updates already replayed are ignored
+    pub fn apply(&mut self, sequence_number: u64, index: u32, record: ClaimRecord) -> bool {
+        if sequence_number <= self.sequence_number {
+            return false;
+        }
+        self.sequence_number = sequence_number;
+        self.records.insert(index, record);
+        true
+    }
+
+    pub fn get_record(&self, index: u32) -> Option<&ClaimRecord> {
+        self.records.get(&index)
+    }
+}
+
+/// Returns the depth of the claim record tree of an airdrop merkle tree of num_leaves leaves, one
+/// level more than the airdrop merkle tree as record indexes carry a leading 1
+pub fn get_record_tree_depth(num_leaves: u64) -> u32 {
+    let mut depth = 1;
+    while (1u64 << (depth - 1)) < num_leaves {
+        depth += 1;
+    }
+    depth
+}
+
+/// Returns the canopy depth leaving [MAX_CLAIM_PROOF_LEN] nodes to the record proofs
+pub fn get_canopy_depth(max_depth: u32) -> u32 {
+    max_depth
+        .saturating_sub(MAX_CLAIM_PROOF_LEN)
+        .min(MAX_CANOPY_DEPTH)
+}
+
+/// Returns the index of the record of a claimant in the claim record tree, the path of its leaf
+/// in the airdrop merkle tree, or none if the proof is not valid
+pub fn get_record_index(
+    node: &TreeNode,
+    proof: Vec<[u8; 32]>,
+    root: [u8; 32],
+    hash_type: HashType,
+) -> Option<u32> {
+    let path = verify_with_path(proof, root, get_airdrop_leaf(node, hash_type), hash_type)?;
+    u32::try_from(path).ok()
+}
+
+/// Returns the leaf of a claimant in the airdrop merkle tree, hashed with the hash type of the
+/// tree. The record tree itself is always sha256
+pub fn get_airdrop_leaf(node: &TreeNode, hash_type: HashType) -> [u8; 32] {
+    hash_type.claim_leaf(&node.claimant.to_bytes(), node.amount, node.locked_amount)
+}
+
+fn hash_intermediate(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    hashv(&[INTERMEDIATE_PREFIX, left, right]).to_bytes()
+}
+
+/// Off-chain copy of a claim record tree, padded with zero leaves up to 2^max_depth leaves
+#[derive(Debug, Clone)]
+pub struct ClaimRecordMerkleTree {
+    /// Non empty nodes of each level, from the leaves up to the root
+    levels: Vec<Vec<[u8; 32]>>,
+    /// Root of an empty subtree of each level
+    empty_nodes: Vec<[u8; 32]>,
+    /// Number of levels below the root stored in the canopy of the program
+    canopy_depth: u32,
+}
+
+impl ClaimRecordMerkleTree {
+    pub fn new(leaves: Vec<[u8; 32]>, max_depth: u32, canopy_depth: u32) -> Self {
+        assert!(leaves.len() as u64 <= 1u64 << max_depth);
+
+        let mut empty_nodes = vec![[0u8; 32]];
+        let mut levels = vec![leaves];
+        for level in 0..max_depth as usize {
+            let empty_node = empty_nodes[level];
+            let nodes = levels[level]
+                .chunks(2)
+                .map(|pair| hash_intermediate(&pair[0], pair.get(1).unwrap_or(&empty_node)))
+                .collect();
+            empty_nodes.push(hash_intermediate(&empty_node, &empty_node));
+            levels.push(nodes);
+        }
+
+        Self {
+            levels,
+            empty_nodes,
+            canopy_depth,
+        }
+    }
+
+    /// Rebuilds the tree from the indexed records, the other leaves are empty
+    pub fn new_from_index(index: &ClaimRecordIndex) -> Self {
+        let num_leaves = index
+            .records
+            .keys()
+            .next_back()
+            .map_or(0, |last| *last as usize + 1);
+        let mut leaves = vec![[0u8; 32]; num_leaves];
+        for (i, record) in index.records.iter() {
+            leaves[*i as usize] = record.hash();
+        }
+        Self::new(leaves, index.max_depth, index.canopy_depth)
+    }
+
+    pub fn max_depth(&self) -> u32 {
+        (self.levels.len() - 1) as u32
+    }
+
+    pub fn get_root(&self) -> [u8; 32] {
+        let root_level = &self.levels[self.levels.len() - 1];
+        root_level
+            .first()
+            .copied()
+            .unwrap_or(self.empty_nodes[self.empty_nodes.len() - 1])
+    }
+
+    pub fn get_leaf(&self, index: usize) -> [u8; 32] {
+        self.get_node(0, index)
+    }
+
+    /// Returns the siblings from the leaf up to, but excluding, the root
+    pub fn get_proof(&self, index: usize) -> Vec<[u8; 32]> {
+        (0..self.max_depth() as usize)
+            .map(|level| self.get_node(level, (index >> level) ^ 1))
+            .collect()
+    }
+
+    /// Returns the proof passed to a claim, the siblings up to the canopy of the program which
+    /// completes it on-chain
+    pub fn get_claim_proof(&self, index: usize) -> Vec<[u8; 32]> {
+        let mut proof = self.get_proof(index);
+        proof.truncate((self.max_depth() - self.canopy_depth) as usize);
+        proof
+    }
+
+    fn get_node(&self, level: usize, index: usize) -> [u8; 32] {
+        self.levels[level]
+            .get(index)
+            .copied()
+            .unwrap_or(self.empty_nodes[level])
+    }
+}
+
+/// Verifies the proof of the leaf at index against the root of a claim record tree
+pub fn verify_record_proof(leaf: [u8; 32], proof: &[[u8; 32]], index: u32, root: [u8; 32]) -> bool {
+    let computed_root = proof
+        .iter()
+        .enumerate()
+        .fold(leaf, |node, (level, sibling)| {
+            if (index >> level) & 1 == 0 {
+                hash_intermediate(&node, sibling)
+            } else {
+                hash_intermediate(sibling, &node)
+            }
+        });
+    computed_root == root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::airdrop_merkle_tree::AirdropMerkleTree;
+
+    fn new_test_tree_nodes(num_nodes: u64) -> Vec<TreeNode> {
+        (0..num_nodes)
+            .map(|i| TreeNode {
+                claimant: Pubkey::new_unique(),
+                amount: 100 + i,
+                locked_amount: 200 + i,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_get_record_tree_depth() {
+        assert_eq!(get_record_tree_depth(1), 1);
+        assert_eq!(get_record_tree_depth(2), 2);
+        assert_eq!(get_record_tree_depth(3), 3);
+        assert_eq!(get_record_tree_depth(4), 3);
+        assert_eq!(get_record_tree_depth(5), 4);
+        assert_eq!(get_record_tree_depth(1 << 19), 20);
+        assert_eq!(get_canopy_depth(2), 0);
+        assert_eq!(get_canopy_depth(10), 7);
+        assert_eq!(get_canopy_depth(20), 17);
+        assert_eq!(get_canopy_depth(24), 17);
+    }
+
+    #[test]
+    fn test_record_indexes() {
+        for hash_type in [HashType::Sha256, HashType::Keccak256] {
+            for num_nodes in [1, 2, 5, 8, 13] {
+                let airdrop_merkle_tree = AirdropMerkleTree::new_with_hash_type(
+                    new_test_tree_nodes(num_nodes),
+                    0,
+                    hash_type,
+                )
+                .unwrap();
+                let max_depth = get_record_tree_depth(airdrop_merkle_tree.max_num_nodes);
+
+                let mut record_indexes: Vec<u32> = airdrop_merkle_tree
+                    .tree_nodes
+                    .iter()
+                    .enumerate()
+                    .map(|(i, node)| {
+                        get_record_index(
+                            node,
+                            airdrop_merkle_tree.proof_for_index(i).unwrap(),
+                            airdrop_merkle_tree.merkle_root,
+                            hash_type,
+                        )
+                        .unwrap()
+                    })
+                    .collect();
+                assert!(record_indexes
+                    .iter()
+                    .all(|i| (*i as u64) < 1u64 << max_depth));
+                record_indexes.sort_unstable();
+                record_indexes.dedup();
+                assert_eq!(record_indexes.len() as u64, num_nodes);
+            }
+        }
+
+        let airdrop_merkle_tree = AirdropMerkleTree::new(new_test_tree_nodes(5), 0).unwrap();
+        let node = &airdrop_merkle_tree.tree_nodes[0];
+        let mut proof = airdrop_merkle_tree.proof_for_index(0).unwrap();
+        proof[0] = [0; 32];
+        assert!(get_record_index(
+            node,
+            proof,
+            airdrop_merkle_tree.merkle_root,
+            HashType::Sha256
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn test_record_proofs_after_claims() {
+        let airdrop_merkle_tree = AirdropMerkleTree::new(new_test_tree_nodes(5), 0).unwrap();
+        let max_depth = get_record_tree_depth(airdrop_merkle_tree.max_num_nodes);
+        let canopy_depth = get_canopy_depth(max_depth);
+        let mut index = ClaimRecordIndex::new(Pubkey::new_unique(), max_depth, canopy_depth);
+
+        let tree = ClaimRecordMerkleTree::new_from_index(&index);
+        assert_eq!(
+            tree.get_root(),
+            ClaimRecordMerkleTree::new(vec![], max_depth, 0).get_root()
+        );
+
+        let node = &airdrop_merkle_tree.tree_nodes[3];
+        let record_index = get_record_index(
+            node,
+            airdrop_merkle_tree.proof_for_index(3).unwrap(),
+            airdrop_merkle_tree.merkle_root,
+            HashType::Sha256,
+        )
+        .unwrap();
+        let record = ClaimRecord {
+            claimant: node.claimant,
+            unlocked_amount: node.amount,
+            locked_amount: node.locked_amount,
+            locked_amount_withdrawn: 10,
+            bonus_amount: 0,
+        };
+        // the record replaces the empty leaf with the same siblings
+        let proof = tree.get_proof(record_index as usize);
+        assert!(verify_record_proof(
+            [0; 32],
+            &proof,
+            record_index,
+            tree.get_root()
+        ));
+        assert!(index.apply(1, record_index, record.clone()));
+        assert!(!index.apply(1, record_index, record.clone()));
+
+        let tree = ClaimRecordMerkleTree::new_from_index(&index);
+        assert!(verify_record_proof(
+            record.hash(),
+            &proof,
+            record_index,
+            tree.get_root()
+        ));
+        assert!(!verify_record_proof(
+            [0; 32],
+            &tree.get_proof(record_index as usize),
+            record_index,
+            tree.get_root()
+        ));
+        assert!(verify_record_proof(
+            [0; 32],
+            &tree.get_proof(record_index as usize ^ 1),
+            record_index ^ 1,
+            tree.get_root()
+        ));
+        assert_eq!(
+            tree.get_claim_proof(record_index as usize).len() as u32,
+            max_depth - canopy_depth
+        );
+    }
+}
@@ -24,7 +24,7 @@ impl CsvEntry {
 
         let mut entries = Vec::new();
         for result in rdr.deserialize() {
-            let record: CsvEntry = result.unwrap();
+            let record: CsvEntry = result?;
             entries.push(record);
         }
 
@@ -0,0 +1,284 @@
+use std::{collections::HashMap, fs::File, io::Write, path::PathBuf, str::FromStr};
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use solana_program::pubkey::Pubkey;
+
+use crate::{
+    csv_entry::{CsvEntry, Result},
+    merge_policy::MergePolicy,
+    tree_node::{convert_ui_amount, DecimalMode},
+};
+
+/// Field of a csv row an error refers to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CsvField {
+    /// The whole row, e.g. a missing column
+    Row,
+    Pubkey,
+    Amount,
+    LockedAmount,
+}
+
+/// Error of a single csv row
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CsvRowError {
+    /// Line of the row in the file, the header is line 1
+    pub line: u64,
+    pub field: CsvField,
+    pub reason: String,
+}
+
+/// Dust lost when amounts are floored to the decimals of the mint
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DustReport {
+    /// Number of amounts with a remainder beyond the decimals of the mint
+    pub num_floored_amounts: u64,
+    /// Total remainder floored away, in ui amount
+    pub total_dust: Decimal,
+}
+
+impl DustReport {
+    fn add(&mut self, dust: Decimal) {
+        if !dust.is_zero() {
+            self.num_floored_amounts += 1;
+            self.total_dust += dust;
+        }
+    }
+}
+
+/// Outcome of the validation of a csv file, every invalid row is reported
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CsvValidationReport {
+    /// Number of rows, excluding the header
+    pub num_rows: u64,
+    /// Number of rows without any error
+    pub num_valid_rows: u64,
+    pub errors: Vec<CsvRowError>,
+    /// Dust of the valid rows, always empty in strict mode
+    pub dust: DustReport,
+}
+
+impl CsvValidationReport {
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    fn push(&mut self, line: u64, field: CsvField, reason: impl ToString) {
+        self.errors.push(CsvRowError {
+            line,
+            field,
+            reason: reason.to_string(),
+        });
+    }
+
+    /// Prints a summary followed by one line per error
+    pub fn print(&self) {
+        println!(
+            "{} of {} rows are valid, {} errors",
+            self.num_valid_rows,
+            self.num_rows,
+            self.errors.len()
+        );
+        if self.dust.num_floored_amounts > 0 {
+            println!(
+                "{} amounts floored, total dust {}",
+                self.dust.num_floored_amounts, self.dust.total_dust
+            );
+        }
+        for error in self.errors.iter() {
+            println!("line {} {:?}: {}", error.line, error.field, error.reason);
+        }
+    }
+
+    /// Writes the report as json if the path ends with .json, else the errors as csv
+    pub fn write_to_file(&self, path: &PathBuf) -> Result<()> {
+        if path
+            .extension()
+            .is_some_and(|extension| extension == "json")
+        {
+            let serialized = serde_json::to_string_pretty(&self)?;
+            let mut file = File::create(path)?;
+            file.write_all(serialized.as_bytes())?;
+        } else {
+            let mut wtr = csv::Writer::from_path(path)?;
+            for error in self.errors.iter() {
+                wtr.serialize(error)?;
+            }
+            wtr.flush()?;
+        }
+        Ok(())
+    }
+}
+
+/// Loads a csv file without panicking on bad rows. Returns the valid entries and a report of
+/// every row error: unparsable rows, invalid base58 pubkeys, negative or overflowing amounts,
+/// amounts more precise than decimals in strict mode, and duplicate claimants with the error
+/// merge policy
+pub fn validate_csv_file(
+    path: &PathBuf,
+    decimals: u32,
+    decimal_mode: DecimalMode,
+    merge_policy: MergePolicy,
+) -> Result<(Vec<CsvEntry>, CsvValidationReport)> {
+    let file = File::open(path)?;
+    let mut rdr = csv::Reader::from_reader(file);
+
+    let mut entries = Vec::new();
+    let mut report = CsvValidationReport::default();
+    // line of the first row of each claimant
+    let mut claimant_lines: HashMap<Pubkey, u64> = HashMap::new();
+    let mut total_unlocked_amount: u64 = 0;
+    let mut total_locked_amount: u64 = 0;
+
+    for result in rdr.records() {
+        report.num_rows += 1;
+        let num_errors = report.errors.len();
+
+        let record = match result {
+            Ok(record) => record,
+            Err(e) => {
+                let line = e.position().map_or(report.num_rows + 1, |p| p.line());
+                report.push(line, CsvField::Row, e);
+                continue;
+            }
+        };
+        let line = record.position().map_or(report.num_rows + 1, |p| p.line());
+        let entry: CsvEntry = match record.deserialize(None) {
+            Ok(entry) => entry,
+            Err(e) => {
+                report.push(line, CsvField::Row, e);
+                continue;
+            }
+        };
+
+        match Pubkey::from_str(entry.pubkey.trim()) {
+            // duplicates are merged by the tree builder unless the policy rejects them
+            Ok(claimant) => match claimant_lines.get(&claimant) {
+                Some(first_line) if merge_policy == MergePolicy::Error => report.push(
+                    line,
+                    CsvField::Pubkey,
+                    format!("duplicate claimant, first seen on line {first_line}"),
+                ),
+                Some(_) => {}
+                None => {
+                    claimant_lines.insert(claimant, line);
+                }
+            },
+            Err(_) => report.push(line, CsvField::Pubkey, "invalid base58 pubkey"),
+        }
+
+        let amount = convert_ui_amount(&entry.amount, decimals, decimal_mode)
+            .map_err(|e| report.push(line, CsvField::Amount, e));
+        let locked_amount = convert_ui_amount(&entry.locked_amount, decimals, decimal_mode)
+            .map_err(|e| report.push(line, CsvField::LockedAmount, e));
+
+        // the totals of the tree must fit in the distributor
+        if let Ok((amount, _dust)) = amount {
+            match total_unlocked_amount.checked_add(amount) {
+                Some(total) => total_unlocked_amount = total,
+                None => report.push(line, CsvField::Amount, "total amount overflows u64"),
+            }
+        }
+        if let Ok((locked_amount, _dust)) = locked_amount {
+            match total_locked_amount.checked_add(locked_amount) {
+                Some(total) => total_locked_amount = total,
+                None => report.push(
+                    line,
+                    CsvField::LockedAmount,
+                    "total locked amount overflows u64",
+                ),
+            }
+        }
+
+        if report.errors.len() == num_errors {
+            if let (Ok((_, dust)), Ok((_, locked_dust))) = (amount, locked_amount) {
+                report.dust.add(dust);
+                report.dust.add(locked_dust);
+            }
+            report.num_valid_rows += 1;
+            entries.push(entry);
+        }
+    }
+
+    Ok((entries, report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_csv_file() {
+        let path = PathBuf::from("./test_fixtures/test_csv.csv");
+        let (entries, report) =
+            validate_csv_file(&path, 6, DecimalMode::Strict, MergePolicy::Error).unwrap();
+        assert!(report.is_valid());
+        assert_eq!(report.num_rows, 3);
+        assert_eq!(entries.len(), 3);
+
+        let path = PathBuf::from("./test_fixtures/test_csv_invalid.csv");
+        let (entries, report) =
+            validate_csv_file(&path, 2, DecimalMode::Strict, MergePolicy::Error).unwrap();
+        assert!(!report.is_valid());
+        assert_eq!(report.num_rows, 9);
+        assert_eq!(report.num_valid_rows, 2);
+        assert_eq!(entries.len(), 2);
+
+        let errors = report
+            .errors
+            .iter()
+            .map(|e| (e.line, e.field))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            errors,
+            vec![
+                (3, CsvField::Pubkey),
+                (4, CsvField::Amount),
+                (5, CsvField::LockedAmount),
+                (6, CsvField::Pubkey),
+                (7, CsvField::Amount),
+                (8, CsvField::Amount),
+                (9, CsvField::Row),
+            ]
+        );
+        assert_eq!(report.errors[0].reason, "invalid base58 pubkey");
+        assert_eq!(report.errors[1].reason, "negative amount");
+        assert_eq!(report.errors[2].reason, "more than 2 decimals");
+        assert_eq!(
+            report.errors[3].reason,
+            "duplicate claimant, first seen on line 2"
+        );
+        assert_eq!(report.errors[4].reason, "overflows u64");
+        assert_eq!(report.errors[5].reason, "total amount overflows u64");
+
+        // the duplicate is left to the tree builder
+        let (entries, report) =
+            validate_csv_file(&path, 2, DecimalMode::Strict, MergePolicy::Sum).unwrap();
+        assert_eq!(report.errors.len(), 6);
+        assert_eq!(report.num_valid_rows, 3);
+        assert_eq!(entries.len(), 3);
+    }
+
+    #[test]
+    fn test_validate_csv_file_dust() {
+        let path = PathBuf::from("./test_fixtures/test_csv_decimal.csv");
+        let (_entries, report) =
+            validate_csv_file(&path, 6, DecimalMode::Strict, MergePolicy::Error).unwrap();
+        assert_eq!(report.num_valid_rows, 0);
+        assert_eq!(report.errors.len(), 6);
+        assert_eq!(report.errors[0].reason, "more than 6 decimals");
+        assert_eq!(report.dust, DustReport::default());
+
+        let (entries, report) =
+            validate_csv_file(&path, 6, DecimalMode::Floor, MergePolicy::Error).unwrap();
+        assert!(report.is_valid());
+        assert_eq!(entries.len(), 3);
+        assert_eq!(report.dust.num_floored_amounts, 6);
+        assert_eq!(
+            report.dust.total_dust,
+            Decimal::from_str("0.00000468").unwrap()
+        );
+    }
+}
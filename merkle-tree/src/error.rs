@@ -1,5 +1,7 @@
 use thiserror::Error;
 
+use crate::csv_validation::CsvValidationReport;
+
 #[derive(Error, Debug)]
 pub enum MerkleTreeError {
     #[error("Merkle Tree Validation Error: {0}")]
@@ -10,4 +12,12 @@ pub enum MerkleTreeError {
     IoError(#[from] std::io::Error),
     #[error("Serde Error: {0}")]
     SerdeError(#[from] serde_json::Error),
+    #[error("csv Error: {0}")]
+    CsvError(#[from] csv::Error),
+    #[error("Csv Validation Error: {} invalid rows", .0.num_rows - .0.num_valid_rows)]
+    CsvValidationError(Box<CsvValidationReport>),
+    #[error("Binary Tree Error: {0}")]
+    BinaryTreeError(String),
+    #[error("Manifest Error: {0}")]
+    ManifestError(String),
 }
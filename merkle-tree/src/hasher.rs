@@ -0,0 +1,181 @@
+use jito_merkle_verify::abi_encode_u64;
+pub use jito_merkle_verify::HashType;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use solana_program::{hash::Hash, pubkey::Pubkey};
+
+// We need to discern between leaf and intermediate nodes to prevent trivial second
+// pre-image attacks.
+// https://flawed.net.nz/2018/02/21/attacking-merkle-trees-with-a-second-preimage-attack
+const LEAF_PREFIX: &[u8] = &[0];
+const INTERMEDIATE_PREFIX: &[u8] = &[1];
+
+/// Hash function used to build the leaves and intermediate nodes of a merkle tree, must match
+/// [HashType::claim_leaf] and [HashType::hash_pair] of the on-chain verification
+pub trait Hasher {
+    /// Hashes the concatenation of vals
+    fn hashv(&self, vals: &[&[u8]]) -> Hash;
+
+    /// Hashes a claim, the data of its leaf
+    fn hash_claim(&self, claimant: &Pubkey, amount: u64, locked_amount: u64) -> Hash {
+        self.hashv(&[
+            &claimant.to_bytes(),
+            &amount.to_le_bytes(),
+            &locked_amount.to_le_bytes(),
+        ])
+    }
+
+    /// Hashes the data of a leaf
+    fn hash_leaf(&self, data: &[u8]) -> Hash {
+        self.hashv(&[LEAF_PREFIX, data])
+    }
+
+    /// Hashes two sibling nodes
+    fn hash_intermediate(&self, left: &[u8], right: &[u8]) -> Hash {
+        self.hashv(&[INTERMEDIATE_PREFIX, left, right])
+    }
+}
+
+/// SHA-256, used by the trees of Solana distributors
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Sha256Hasher;
+
+impl Hasher for Sha256Hasher {
+    fn hashv(&self, vals: &[&[u8]]) -> Hash {
+        solana_program::hash::hashv(vals)
+    }
+}
+
+/// keccak256, for trees also published on EVM chains. Claims are abi-encoded and nodes are not
+/// prefixed, as in an OpenZeppelin StandardMerkleTree
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Keccak256Hasher;
+
+impl Hasher for Keccak256Hasher {
+    fn hashv(&self, vals: &[&[u8]]) -> Hash {
+        Hash::new_from_array(solana_program::keccak::hashv(vals).to_bytes())
+    }
+
+    fn hash_claim(&self, claimant: &Pubkey, amount: u64, locked_amount: u64) -> Hash {
+        self.hashv(&[
+            &claimant.to_bytes(),
+            &abi_encode_u64(amount),
+            &abi_encode_u64(locked_amount),
+        ])
+    }
+
+    fn hash_leaf(&self, data: &[u8]) -> Hash {
+        self.hashv(&[data])
+    }
+
+    fn hash_intermediate(&self, left: &[u8], right: &[u8]) -> Hash {
+        self.hashv(&[left, right])
+    }
+}
+
+/// Dispatches on the hash type of a distributor
+impl Hasher for HashType {
+    fn hashv(&self, vals: &[&[u8]]) -> Hash {
+        match self {
+            HashType::Sha256 => Sha256Hasher.hashv(vals),
+            HashType::Keccak256 => Keccak256Hasher.hashv(vals),
+        }
+    }
+
+    fn hash_claim(&self, claimant: &Pubkey, amount: u64, locked_amount: u64) -> Hash {
+        match self {
+            HashType::Sha256 => Sha256Hasher.hash_claim(claimant, amount, locked_amount),
+            HashType::Keccak256 => Keccak256Hasher.hash_claim(claimant, amount, locked_amount),
+        }
+    }
+
+    fn hash_leaf(&self, data: &[u8]) -> Hash {
+        match self {
+            HashType::Sha256 => Sha256Hasher.hash_leaf(data),
+            HashType::Keccak256 => Keccak256Hasher.hash_leaf(data),
+        }
+    }
+
+    fn hash_intermediate(&self, left: &[u8], right: &[u8]) -> Hash {
+        match self {
+            HashType::Sha256 => Sha256Hasher.hash_intermediate(left, right),
+            HashType::Keccak256 => Keccak256Hasher.hash_intermediate(left, right),
+        }
+    }
+}
+
+/// Name of the hash type in tree files and on the command line
+pub fn hash_type_name(hash_type: HashType) -> &'static str {
+    match hash_type {
+        HashType::Sha256 => "sha256",
+        HashType::Keccak256 => "keccak256",
+    }
+}
+
+/// Parses the name of a hash type, see [hash_type_name]
+pub fn parse_hash_type(name: &str) -> Result<HashType, String> {
+    match name {
+        "sha256" => Ok(HashType::Sha256),
+        "keccak256" => Ok(HashType::Keccak256),
+        _ => Err(format!(
+            "unknown hash type {name}, expected sha256 or keccak256"
+        )),
+    }
+}
+
+/// (De)serializes a [HashType] by name, trees written before hash types were introduced are sha256
+pub mod serde_hash_type {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(
+        hash_type: &HashType,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        hash_type_name(*hash_type).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<HashType, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        parse_hash_type(&name).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash_type_dispatch() {
+        let vals: &[&[u8]] = &[&[0u8], b"claimant"];
+        assert_eq!(HashType::Sha256.hashv(vals), Sha256Hasher.hashv(vals));
+        assert_eq!(HashType::Keccak256.hashv(vals), Keccak256Hasher.hashv(vals));
+        assert_ne!(Sha256Hasher.hashv(vals), Keccak256Hasher.hashv(vals));
+
+        // the leaves and nodes match the on-chain verification
+        let claimant = Pubkey::new_unique();
+        for hash_type in [HashType::Sha256, HashType::Keccak256] {
+            let claim = hash_type.hash_claim(&claimant, 3, 5);
+            assert_eq!(
+                hash_type.hash_leaf(claim.as_ref()).to_bytes(),
+                hash_type.claim_leaf(&claimant.to_bytes(), 3, 5)
+            );
+            let (a, b) = ([1u8; 32], [2u8; 32]);
+            assert_eq!(
+                hash_type.hash_intermediate(&a, &b).to_bytes(),
+                hash_type.hash_pair(&a, &b)
+            );
+        }
+
+        // keccak256 of the empty string
+        assert_eq!(
+            hex::encode(Keccak256Hasher.hashv(&[]).to_bytes()),
+            "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"
+        );
+
+        for hash_type in [HashType::Sha256, HashType::Keccak256] {
+            assert_eq!(parse_hash_type(hash_type_name(hash_type)), Ok(hash_type));
+            assert_eq!(HashType::try_from(u8::from(hash_type)), Ok(hash_type));
+        }
+        assert!(parse_hash_type("blake3").is_err());
+        assert!(HashType::try_from(2).is_err());
+    }
+}
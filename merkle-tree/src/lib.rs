@@ -1,6 +1,13 @@
 pub mod airdrop_merkle_tree;
+pub mod binary_tree;
+pub mod claim_record_tree;
 pub mod csv_entry;
+pub mod csv_validation;
 pub mod error;
+pub mod hasher;
+pub mod manifest;
+pub mod merge_policy;
 pub mod merkle_tree;
+pub mod tree_diff;
 pub mod tree_node;
 pub mod utils;
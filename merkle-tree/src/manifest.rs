@@ -0,0 +1,290 @@
+//! Provenance manifest of a generated tree, tying the published tree to the csv it was built
+//! from and to the root uploaded on-chain. The manifest is signed by the admin of the
+//! distributor, so that users and auditors can check who vouched for a tree.
+use std::{
+    fs,
+    fs::File,
+    io::{BufReader, Write},
+    path::PathBuf,
+    str::FromStr,
+};
+
+use serde::{Deserialize, Serialize};
+use solana_program::{hash::hash, pubkey::Pubkey};
+use solana_sdk::signature::{Keypair, Signature, Signer};
+
+use crate::{
+    airdrop_merkle_tree::{AirdropMerkleTree, Result},
+    error::MerkleTreeError::ManifestError,
+    hasher::{serde_hash_type, HashType},
+    merge_policy::MergePolicy,
+    tree_node::DecimalMode,
+    utils::{get_total_locked_amount, get_total_unlocked_amount, serde_pubkey},
+};
+
+/// Version of the tree builder, recorded in the manifests
+pub const BUILDER_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Inputs and outputs of the build of a tree
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TreeManifest {
+    /// SHA-256 of the csv file, in hex as printed by sha256sum
+    pub csv_hash: String,
+    pub decimals: u32,
+    pub decimal_mode: DecimalMode,
+    pub merge_policy: MergePolicy,
+    pub builder_version: String,
+    pub airdrop_version: u64,
+    #[serde(with = "serde_hash_type")]
+    pub hash_type: HashType,
+    pub merkle_root: [u8; 32],
+    pub num_nodes: u64,
+    pub total_unlocked_amount: u64,
+    pub total_locked_amount: u64,
+}
+
+/// Manifest with the ed25519 signature of the admin
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedTreeManifest {
+    pub manifest: TreeManifest,
+    /// Admin of the distributor of the tree
+    #[serde(with = "serde_pubkey")]
+    pub signer: Pubkey,
+    /// Signature of the json of the manifest, in base58
+    pub signature: String,
+}
+
+/// Fields of an on-chain MerkleDistributor account checked against a manifest
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DistributorParams {
+    pub admin: Pubkey,
+    pub version: u64,
+    pub root: [u8; 32],
+    /// Total claim of the tree plus the total bonus
+    pub max_total_claim: u64,
+    pub total_bonus: u64,
+    pub max_num_nodes: u64,
+    pub hash_type: u8,
+}
+
+fn hash_file(path: &PathBuf) -> Result<String> {
+    let bytes = fs::read(path)?;
+    Ok(hash(&bytes)
+        .to_bytes()
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect())
+}
+
+fn check<T: PartialEq + std::fmt::Debug>(name: &str, manifest: T, actual: T) -> Result<()> {
+    if manifest != actual {
+        return Err(ManifestError(format!(
+            "{name} is {actual:?}, the manifest has {manifest:?}"
+        )));
+    }
+    Ok(())
+}
+
+impl TreeManifest {
+    /// Manifest of a tree built from the csv with the given options
+    pub fn new(
+        tree: &AirdropMerkleTree,
+        csv_path: &PathBuf,
+        decimals: u32,
+        decimal_mode: DecimalMode,
+        merge_policy: MergePolicy,
+    ) -> Result<Self> {
+        Ok(TreeManifest {
+            csv_hash: hash_file(csv_path)?,
+            decimals,
+            decimal_mode,
+            merge_policy,
+            builder_version: BUILDER_VERSION.to_string(),
+            airdrop_version: tree.airdrop_version,
+            hash_type: tree.hash_type,
+            merkle_root: tree.merkle_root,
+            num_nodes: tree.tree_nodes.len() as u64,
+            total_unlocked_amount: tree.total_unlocked_amount,
+            total_locked_amount: tree.total_locked_amount,
+        })
+    }
+
+    pub fn sign(self, keypair: &Keypair) -> Result<SignedTreeManifest> {
+        let signature = keypair.sign_message(&serde_json::to_vec(&self)?);
+        Ok(SignedTreeManifest {
+            manifest: self,
+            signer: keypair.pubkey(),
+            signature: signature.to_string(),
+        })
+    }
+}
+
+impl SignedTreeManifest {
+    pub fn new_from_file(path: &PathBuf) -> Result<Self> {
+        let reader = BufReader::new(File::open(path)?);
+        Ok(serde_json::from_reader(reader)?)
+    }
+
+    pub fn write_to_file(&self, path: &PathBuf) -> Result<()> {
+        let serialized = serde_json::to_string_pretty(&self)?;
+        let mut file = File::create(path)?;
+        file.write_all(serialized.as_bytes())?;
+        Ok(())
+    }
+
+    /// Checks that the signer signed the manifest
+    pub fn verify_signature(&self) -> Result<()> {
+        let signature = Signature::from_str(&self.signature)
+            .map_err(|e| ManifestError(format!("invalid signature: {e}")))?;
+        if !signature.verify(self.signer.as_ref(), &serde_json::to_vec(&self.manifest)?) {
+            return Err(ManifestError(format!(
+                "manifest is not signed by {}",
+                self.signer
+            )));
+        }
+        Ok(())
+    }
+
+    /// Checks the signature, then that the manifest describes the tree, with the root and
+    /// totals computed from the nodes rather than read from the file, and, if given, that the
+    /// distributor was created from the tree by the signer
+    pub fn verify(
+        &self,
+        tree: &AirdropMerkleTree,
+        distributor: Option<&DistributorParams>,
+    ) -> Result<()> {
+        self.verify_signature()?;
+
+        let manifest = &self.manifest;
+        let root = *tree
+            .levels
+            .last()
+            .ok_or_else(|| ManifestError("empty tree".to_string()))?;
+        check("tree root", manifest.merkle_root, root)?;
+        check(
+            "tree airdrop version",
+            manifest.airdrop_version,
+            tree.airdrop_version,
+        )?;
+        check("tree hash type", manifest.hash_type, tree.hash_type)?;
+        check(
+            "tree node count",
+            manifest.num_nodes,
+            tree.tree_nodes.len() as u64,
+        )?;
+        check(
+            "tree unlocked amount",
+            manifest.total_unlocked_amount,
+            get_total_unlocked_amount(&tree.tree_nodes),
+        )?;
+        check(
+            "tree locked amount",
+            manifest.total_locked_amount,
+            get_total_locked_amount(&tree.tree_nodes),
+        )?;
+
+        if let Some(distributor) = distributor {
+            check("distributor admin", self.signer, distributor.admin)?;
+            check(
+                "distributor version",
+                manifest.airdrop_version,
+                distributor.version,
+            )?;
+            check("distributor root", manifest.merkle_root, distributor.root)?;
+            check(
+                "distributor hash type",
+                u8::from(manifest.hash_type),
+                distributor.hash_type,
+            )?;
+            check(
+                "distributor max num nodes",
+                manifest.num_nodes,
+                distributor.max_num_nodes,
+            )?;
+            let total_claim = manifest.total_unlocked_amount as u128
+                + manifest.total_locked_amount as u128
+                + distributor.total_bonus as u128;
+            check(
+                "distributor max total claim",
+                total_claim,
+                distributor.max_total_claim as u128,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Checks that the tree was built from the csv
+    pub fn verify_csv(&self, csv_path: &PathBuf) -> Result<()> {
+        check(
+            "csv hash",
+            self.manifest.csv_hash.clone(),
+            hash_file(csv_path)?,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::airdrop_merkle_tree::AirdropMerkleTreeBuilder;
+
+    #[test]
+    fn test_manifest() {
+        let csv_path = PathBuf::from("./test_fixtures/test_csv.csv");
+        let builder = AirdropMerkleTreeBuilder::default();
+        let tree = AirdropMerkleTree::new_from_csv(&csv_path, 0, DecimalMode::Strict, &builder)
+            .unwrap()
+            .tree;
+        let admin = Keypair::new();
+        let signed = TreeManifest::new(&tree, &csv_path, 0, DecimalMode::Strict, MergePolicy::Sum)
+            .unwrap()
+            .sign(&admin)
+            .unwrap();
+
+        let distributor = DistributorParams {
+            admin: admin.pubkey(),
+            version: tree.airdrop_version,
+            root: tree.merkle_root,
+            max_total_claim: tree.get_max_total_claim() + 100,
+            total_bonus: 100,
+            max_num_nodes: tree.max_num_nodes,
+            hash_type: tree.hash_type.into(),
+        };
+        signed.verify(&tree, Some(&distributor)).unwrap();
+        signed.verify_csv(&csv_path).unwrap();
+        assert!(signed
+            .verify_csv(&PathBuf::from("./test_fixtures/test_csv_invalid.csv"))
+            .is_err());
+
+        // survives a round trip through json
+        let read: SignedTreeManifest =
+            serde_json::from_str(&serde_json::to_string(&signed).unwrap()).unwrap();
+        assert_eq!(read, signed);
+        read.verify(&tree, None).unwrap();
+
+        let mut tampered = signed.clone();
+        tampered.manifest.total_unlocked_amount += 1;
+        assert!(tampered.verify_signature().is_err());
+        let mut other_signer = signed.clone();
+        other_signer.signer = Keypair::new().pubkey();
+        assert!(other_signer.verify_signature().is_err());
+
+        let mut other_tree = tree.clone();
+        other_tree.tree_nodes[0].amount += 1;
+        other_tree.levels = AirdropMerkleTree::new(other_tree.tree_nodes.clone(), 0)
+            .unwrap()
+            .levels;
+        assert!(signed.verify(&other_tree, None).is_err());
+
+        let other_admin = DistributorParams {
+            admin: Keypair::new().pubkey(),
+            ..distributor
+        };
+        assert!(signed.verify(&tree, Some(&other_admin)).is_err());
+        let no_bonus = DistributorParams {
+            total_bonus: 0,
+            ..distributor
+        };
+        assert!(signed.verify(&tree, Some(&no_bonus)).is_err());
+    }
+}
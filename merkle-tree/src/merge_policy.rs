@@ -0,0 +1,188 @@
+use std::{result, str::FromStr};
+
+use indexmap::{map::Entry, IndexMap};
+use serde::{Deserialize, Serialize};
+use solana_program::pubkey::Pubkey;
+
+use crate::{
+    error::MerkleTreeError::{self, MerkleValidationError},
+    tree_node::TreeNode,
+};
+
+/// How the entries of a claimant appearing several times in a list are merged
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MergePolicy {
+    /// Sums the unlocked and locked amounts
+    #[default]
+    Sum,
+    /// Keeps the entry with the largest total amount, the first one on ties
+    Max,
+    /// Keeps the first entry
+    First,
+    /// Keeps the last entry
+    Last,
+    /// Fails, a duplicate is a bug in the list
+    Error,
+}
+
+impl FromStr for MergePolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> result::Result<Self, Self::Err> {
+        match s {
+            "sum" => Ok(MergePolicy::Sum),
+            "max" => Ok(MergePolicy::Max),
+            "first" => Ok(MergePolicy::First),
+            "last" => Ok(MergePolicy::Last),
+            "error" => Ok(MergePolicy::Error),
+            _ => Err(format!(
+                "unknown merge policy {s}, expected sum, max, first, last or error"
+            )),
+        }
+    }
+}
+
+/// Claimant whose entries were merged into a single node
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MergedClaimant {
+    pub claimant: Pubkey,
+    /// Number of entries of the claimant in the list
+    pub num_entries: u64,
+    /// Unlocked amount after the merge
+    pub amount: u64,
+    /// Locked amount after the merge
+    pub locked_amount: u64,
+}
+
+/// Merges the nodes of the same claimant according to the policy, while retaining the order of
+/// first appearance. Returns the unique nodes and the claimants that were merged
+pub fn merge_tree_nodes(
+    tree_nodes: Vec<TreeNode>,
+    merge_policy: MergePolicy,
+) -> result::Result<(Vec<TreeNode>, Vec<MergedClaimant>), MerkleTreeError> {
+    // node and number of entries of each claimant
+    let mut tree_nodes_map: IndexMap<Pubkey, (TreeNode, u64)> = IndexMap::new();
+    for tree_node in tree_nodes {
+        let mut entry = match tree_nodes_map.entry(tree_node.claimant) {
+            Entry::Occupied(entry) => entry,
+            Entry::Vacant(entry) => {
+                entry.insert((tree_node, 1));
+                continue;
+            }
+        };
+        let (node, num_entries) = entry.get_mut();
+        *num_entries += 1;
+        match merge_policy {
+            MergePolicy::Sum => {
+                node.amount = node.amount.checked_add(tree_node.amount).ok_or_else(|| {
+                    MerkleValidationError(format!("amount of claimant {} overflows", node.claimant))
+                })?;
+                node.locked_amount = node
+                    .locked_amount
+                    .checked_add(tree_node.locked_amount)
+                    .ok_or_else(|| {
+                        MerkleValidationError(format!(
+                            "locked amount of claimant {} overflows",
+                            node.claimant
+                        ))
+                    })?;
+            }
+            MergePolicy::Max => {
+                let total = node.amount as u128 + node.locked_amount as u128;
+                let new_total = tree_node.amount as u128 + tree_node.locked_amount as u128;
+                if new_total > total {
+                    *node = tree_node;
+                }
+            }
+            MergePolicy::First => {}
+            MergePolicy::Last => *node = tree_node,
+            MergePolicy::Error => {
+                return Err(MerkleValidationError(format!(
+                    "duplicate claimant {}",
+                    node.claimant
+                )));
+            }
+        }
+    }
+
+    let merged_claimants = tree_nodes_map
+        .values()
+        .filter(|(_node, num_entries)| *num_entries > 1)
+        .map(|(node, num_entries)| MergedClaimant {
+            claimant: node.claimant,
+            num_entries: *num_entries,
+            amount: node.amount,
+            locked_amount: node.locked_amount,
+        })
+        .collect();
+    let tree_nodes = tree_nodes_map
+        .into_values()
+        .map(|(node, _num_entries)| node)
+        .collect();
+    Ok((tree_nodes, merged_claimants))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_tree_node(claimant: Pubkey, amount: u64, locked_amount: u64) -> TreeNode {
+        TreeNode {
+            claimant,
+            amount,
+            locked_amount,
+        }
+    }
+
+    #[test]
+    fn test_merge_tree_nodes() {
+        let duplicate = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+        let tree_nodes = vec![
+            new_tree_node(duplicate, 10, 10),
+            new_tree_node(other, 5, 0),
+            new_tree_node(duplicate, 30, 0),
+            new_tree_node(duplicate, 1, 2),
+        ];
+
+        let expected = [
+            (MergePolicy::Sum, (41, 12)),
+            (MergePolicy::Max, (30, 0)),
+            (MergePolicy::First, (10, 10)),
+            (MergePolicy::Last, (1, 2)),
+        ];
+        for (merge_policy, (amount, locked_amount)) in expected {
+            let (nodes, merged) = merge_tree_nodes(tree_nodes.clone(), merge_policy).unwrap();
+            assert_eq!(nodes.len(), 2);
+            assert_eq!(nodes[0].claimant, duplicate);
+            assert_eq!(nodes[1].claimant, other);
+            assert_eq!(
+                (nodes[0].amount, nodes[0].locked_amount),
+                (amount, locked_amount)
+            );
+            assert_eq!(
+                merged,
+                vec![MergedClaimant {
+                    claimant: duplicate,
+                    num_entries: 3,
+                    amount,
+                    locked_amount,
+                }]
+            );
+        }
+
+        assert!(merge_tree_nodes(tree_nodes.clone(), MergePolicy::Error).is_err());
+
+        let (_nodes, merged) =
+            merge_tree_nodes(tree_nodes[..2].to_vec(), MergePolicy::Error).unwrap();
+        assert!(merged.is_empty());
+
+        let overflow = vec![
+            new_tree_node(other, u64::MAX, 0),
+            new_tree_node(other, 1, 0),
+        ];
+        assert!(merge_tree_nodes(overflow, MergePolicy::Sum).is_err());
+        assert_eq!("last".parse(), Ok(MergePolicy::Last));
+    }
+}
@@ -1,21 +1,19 @@
 // https://github.com/jito-foundation/jito-solana/blob/v1.16.19-jito/merkle-tree/src/merkle_tree.rs
-use solana_program::hash::{hashv, Hash};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use solana_program::hash::Hash;
 
-// We need to discern between leaf and intermediate nodes to prevent trivial second
-// pre-image attacks.
-// https://flawed.net.nz/2018/02/21/attacking-merkle-trees-with-a-second-preimage-attack
-const LEAF_PREFIX: &[u8] = &[0];
-const INTERMEDIATE_PREFIX: &[u8] = &[1];
+use crate::hasher::{Hasher, Sha256Hasher};
 
 macro_rules! hash_leaf {
-    {$d:ident} => {
-        hashv(&[LEAF_PREFIX, $d])
+    {$h:ident, $d:ident} => {
+        $h.hash_leaf($d.as_ref())
     }
 }
 
 macro_rules! hash_intermediate {
-    {$l:ident, $r:ident} => {
-        hashv(&[INTERMEDIATE_PREFIX, $l.as_ref(), $r.as_ref()])
+    {$h:ident, $l:ident, $r:ident} => {
+        $h.hash_intermediate($l.as_ref(), $r.as_ref())
     }
 }
 
@@ -56,10 +54,15 @@ impl<'a> Proof<'a> {
     }
 
     pub fn verify(&self, candidate: Hash) -> bool {
+        self.verify_with_hasher(candidate, &Sha256Hasher)
+    }
+
+    /// Same as [Proof::verify], for a tree built with the given hasher
+    pub fn verify_with_hasher<H: Hasher>(&self, candidate: Hash, hasher: &H) -> bool {
         let result = self.0.iter().try_fold(candidate, |candidate, pe| {
             let lsib = pe.1.unwrap_or(&candidate);
             let rsib = pe.2.unwrap_or(&candidate);
-            let hash = hash_intermediate!(lsib, rsib);
+            let hash = hash_intermediate!(hasher, lsib, rsib);
 
             if hash == *pe.0 {
                 Some(hash)
@@ -75,6 +78,16 @@ impl<'a> Proof<'a> {
     }
 }
 
+/// Sibling hashes proving several leaves at once, see [jito_merkle_verify::verify_multiproof]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct MultiProof {
+    /// Siblings that can't be computed from the proven leaves, from the leaves to the root
+    pub proof: Vec<Hash>,
+    /// One flag per hash computed on the way to the root, true if both operands are proven leaves
+    /// or computed hashes, false if the second one is the next hash of the proof
+    pub proof_flags: Vec<bool>,
+}
+
 impl MerkleTree {
     #[inline]
     fn next_level_len(level_len: usize) -> usize {
@@ -108,7 +121,26 @@ impl MerkleTree {
         }
     }
 
+    fn hash_siblings<H: Hasher>(hasher: &H, lsib: &Hash, rsib: &Hash, sorted_hashes: bool) -> Hash {
+        // tip-distribution verification uses sorted hashing
+        if sorted_hashes && lsib > rsib {
+            hash_intermediate!(hasher, rsib, lsib)
+        } else {
+            // hashing for solana internals
+            hash_intermediate!(hasher, lsib, rsib)
+        }
+    }
+
     pub fn new<T: AsRef<[u8]>>(items: &[T], sorted_hashes: bool) -> Self {
+        Self::new_with_hasher(items, sorted_hashes, &Sha256Hasher)
+    }
+
+    /// Builds the tree with the given hasher instead of SHA-256
+    pub fn new_with_hasher<T: AsRef<[u8]>, H: Hasher>(
+        items: &[T],
+        sorted_hashes: bool,
+        hasher: &H,
+    ) -> Self {
         let cap = MerkleTree::calculate_vec_capacity(items.len());
         let mut mt = MerkleTree {
             leaf_count: items.len(),
@@ -117,7 +149,7 @@ impl MerkleTree {
 
         for item in items {
             let item = item.as_ref();
-            let hash = hash_leaf!(item);
+            let hash = hash_leaf!(hasher, item);
             mt.nodes.push(hash);
         }
 
@@ -136,20 +168,8 @@ impl MerkleTree {
                     &mt.nodes[prev_level_start + prev_level_idx]
                 };
 
-                // tip-distribution verification uses sorted hashing
-                if sorted_hashes {
-                    if lsib <= rsib {
-                        let hash = hash_intermediate!(lsib, rsib);
-                        mt.nodes.push(hash);
-                    } else {
-                        let hash = hash_intermediate!(rsib, lsib);
-                        mt.nodes.push(hash);
-                    }
-                } else {
-                    // hashing for solana internals
-                    let hash = hash_intermediate!(lsib, rsib);
-                    mt.nodes.push(hash);
-                }
+                let hash = MerkleTree::hash_siblings(hasher, lsib, rsib, sorted_hashes);
+                mt.nodes.push(hash);
             }
             prev_level_start = level_start;
             prev_level_len = level_len;
@@ -160,10 +180,157 @@ impl MerkleTree {
         mt
     }
 
+    /// Same as [MerkleTree::new_with_hasher], hashing the leaves and each level with rayon
+    #[cfg(feature = "parallel")]
+    pub fn new_with_hasher_par<T: AsRef<[u8]> + Sync, H: Hasher + Sync>(
+        items: &[T],
+        sorted_hashes: bool,
+        hasher: &H,
+    ) -> Self {
+        let cap = MerkleTree::calculate_vec_capacity(items.len());
+        let mut mt = MerkleTree {
+            leaf_count: items.len(),
+            nodes: Vec::with_capacity(cap),
+        };
+
+        mt.nodes.par_extend(items.par_iter().map(|item| {
+            let item = item.as_ref();
+            hash_leaf!(hasher, item)
+        }));
+
+        let mut level_len = MerkleTree::next_level_len(items.len());
+        let mut prev_level_len = items.len();
+        let mut prev_level_start = 0;
+        while level_len > 0 {
+            let prev_level = &mt.nodes[prev_level_start..(prev_level_start + prev_level_len)];
+            let level = (0..level_len)
+                .into_par_iter()
+                .map(|i| {
+                    let lsib = &prev_level[2 * i];
+                    // Duplicate last entry if the level length is odd
+                    let rsib = prev_level.get(2 * i + 1).unwrap_or(lsib);
+                    MerkleTree::hash_siblings(hasher, lsib, rsib, sorted_hashes)
+                })
+                .collect::<Vec<_>>();
+            mt.nodes.extend(level);
+
+            prev_level_start += prev_level_len;
+            prev_level_len = level_len;
+            level_len = MerkleTree::next_level_len(level_len);
+        }
+
+        mt
+    }
+
     pub fn get_root(&self) -> Option<&Hash> {
         self.nodes.iter().last()
     }
 
+    pub fn get_leaf_count(&self) -> usize {
+        self.leaf_count
+    }
+
+    /// Hashes of all levels, from the leaves to the root
+    pub fn get_nodes(&self) -> &[Hash] {
+        &self.nodes
+    }
+
+    /// Number of hashes of a tree with leaf_count leaves, all levels included
+    pub fn get_node_count(leaf_count: usize) -> usize {
+        let mut node_count = 0;
+        let mut level_len = leaf_count;
+        while level_len > 0 {
+            node_count += level_len;
+            level_len = MerkleTree::next_level_len(level_len);
+        }
+        node_count
+    }
+
+    /// Positions in [MerkleTree::get_nodes] of the siblings on the path of a leaf to the root,
+    /// so that proofs can be derived from the stored levels of a tree
+    pub fn get_sibling_indices(leaf_count: usize, index: usize) -> Option<Vec<usize>> {
+        if index >= leaf_count {
+            return None;
+        }
+
+        let mut sibling_indices = Vec::new();
+        let mut level_len = leaf_count;
+        let mut level_start = 0;
+        let mut node_index = index;
+        while level_len > 1 {
+            let sibling_index = node_index ^ 1;
+            if sibling_index < level_len {
+                sibling_indices.push(level_start + sibling_index);
+            } else {
+                // the last node of an odd level is its own sibling
+                sibling_indices.push(level_start + node_index);
+            }
+            node_index /= 2;
+
+            level_start += level_len;
+            level_len = MerkleTree::next_level_len(level_len);
+        }
+        Some(sibling_indices)
+    }
+
+    /// Positions in [MerkleTree::get_nodes] of the hashes of the multiproof of the leaves at
+    /// indices, which must be strictly increasing, and the flags of the multiproof
+    pub fn get_multiproof_indices(
+        leaf_count: usize,
+        indices: &[usize],
+    ) -> Option<(Vec<usize>, Vec<bool>)> {
+        if indices.is_empty()
+            || indices.windows(2).any(|pair| pair[0] >= pair[1])
+            || indices[indices.len() - 1] >= leaf_count
+        {
+            return None;
+        }
+
+        let mut proof_indices = Vec::new();
+        let mut proof_flags = Vec::new();
+        let mut node_indices = indices.to_vec();
+        let mut level_len = leaf_count;
+        let mut level_start = 0;
+        while level_len > 1 {
+            let mut parent_indices = Vec::with_capacity(node_indices.len());
+            let mut i = 0;
+            while i < node_indices.len() {
+                let node_index = node_indices[i];
+                let sibling_index = node_index ^ 1;
+                if node_indices.get(i + 1) == Some(&sibling_index) {
+                    // both children are known, no proof hash needed
+                    proof_flags.push(true);
+                    i += 2;
+                } else {
+                    proof_flags.push(false);
+                    if sibling_index < level_len {
+                        proof_indices.push(level_start + sibling_index);
+                    } else {
+                        // the last node of an odd level is its own sibling
+                        proof_indices.push(level_start + node_index);
+                    }
+                    i += 1;
+                }
+                parent_indices.push(node_index / 2);
+            }
+            node_indices = parent_indices;
+
+            level_start += level_len;
+            level_len = MerkleTree::next_level_len(level_len);
+        }
+        Some((proof_indices, proof_flags))
+    }
+
+    /// Multiproof of the leaves at indices, which must be strictly increasing
+    pub fn find_multiproof(&self, indices: &[usize]) -> Option<MultiProof> {
+        let (proof_indices, proof_flags) =
+            MerkleTree::get_multiproof_indices(self.leaf_count, indices)?;
+        Some(MultiProof {
+            proof: proof_indices.into_iter().map(|i| self.nodes[i]).collect(),
+            proof_flags,
+        })
+    }
+
     pub fn find_path(&self, index: usize) -> Option<Proof> {
         if index >= self.leaf_count {
             return None;
@@ -205,6 +372,7 @@ impl MerkleTree {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::hasher::Keccak256Hasher;
 
     const TEST: &[&[u8]] = &[
         b"my", b"very", b"eager", b"mother", b"just", b"served", b"us", b"nine", b"pizzas",
@@ -222,7 +390,7 @@ mod tests {
     fn test_tree_from_one() {
         let input = b"test";
         let mt = MerkleTree::new(&[input], false);
-        let expected = hash_leaf!(input);
+        let expected = hash_leaf!(Sha256Hasher, input);
         assert_eq!(mt.get_root(), Some(&expected));
     }
 
@@ -256,7 +424,7 @@ mod tests {
     fn test_path_verify_good() {
         let mt = MerkleTree::new(TEST, false);
         for (i, s) in TEST.iter().enumerate() {
-            let hash = hash_leaf!(s);
+            let hash = hash_leaf!(Sha256Hasher, s);
             let path = mt.find_path(i).unwrap();
             assert!(path.verify(hash));
         }
@@ -266,12 +434,106 @@ mod tests {
     fn test_path_verify_bad() {
         let mt = MerkleTree::new(TEST, false);
         for (i, s) in BAD.iter().enumerate() {
-            let hash = hash_leaf!(s);
+            let hash = hash_leaf!(Sha256Hasher, s);
             let path = mt.find_path(i).unwrap();
             assert!(!path.verify(hash));
         }
     }
 
+    #[test]
+    fn test_path_verify_keccak() {
+        let hasher = Keccak256Hasher;
+        let mt = MerkleTree::new_with_hasher(TEST, false, &hasher);
+        assert_ne!(mt.get_root(), MerkleTree::new(TEST, false).get_root());
+        for (i, s) in TEST.iter().enumerate() {
+            let hash = hash_leaf!(hasher, s);
+            let path = mt.find_path(i).unwrap();
+            assert!(path.verify_with_hasher(hash, &hasher));
+            assert!(!path.verify(hash));
+        }
+    }
+
+    #[test]
+    fn test_sibling_indices() {
+        for len in 1..TEST.len() {
+            let mt = MerkleTree::new(&TEST[..len], true);
+            assert_eq!(mt.get_nodes().len(), MerkleTree::get_node_count(len));
+            for i in 0..len {
+                let siblings = MerkleTree::get_sibling_indices(len, i)
+                    .unwrap()
+                    .into_iter()
+                    .map(|index| mt.get_nodes()[index].to_bytes())
+                    .collect::<Vec<_>>();
+                assert_eq!(siblings, crate::utils::get_proof(&mt, i));
+            }
+            assert_eq!(MerkleTree::get_sibling_indices(len, len), None);
+        }
+    }
+
+    #[test]
+    fn test_multiproof() {
+        use jito_merkle_verify::verify_multiproof;
+
+        let to_bytes = |hashes: &[Hash]| {
+            hashes
+                .iter()
+                .map(|hash| hash.to_bytes())
+                .collect::<Vec<_>>()
+        };
+        for len in 1..TEST.len() {
+            let mt = MerkleTree::new(&TEST[..len], true);
+            let root = mt.get_root().unwrap().to_bytes();
+            let leaves = to_bytes(&mt.get_nodes()[..len]);
+            // every subset of the leaves
+            for subset in 1..(1u32 << len) {
+                let indices = (0..len)
+                    .filter(|i| subset & (1 << i) != 0)
+                    .collect::<Vec<_>>();
+                let multiproof = mt.find_multiproof(&indices).unwrap();
+                let proven = indices.iter().map(|i| leaves[*i]).collect::<Vec<_>>();
+                assert!(verify_multiproof(
+                    to_bytes(&multiproof.proof),
+                    multiproof.proof_flags.clone(),
+                    root,
+                    proven.clone(),
+                ));
+                if indices.len() == 1 {
+                    assert_eq!(
+                        to_bytes(&multiproof.proof),
+                        crate::utils::get_proof(&mt, indices[0])
+                    );
+                }
+
+                let mut bad_leaves = proven;
+                bad_leaves[0] = [0; 32];
+                assert!(!verify_multiproof(
+                    to_bytes(&multiproof.proof),
+                    multiproof.proof_flags,
+                    root,
+                    bad_leaves,
+                ));
+            }
+            assert_eq!(mt.find_multiproof(&[]), None);
+            assert_eq!(mt.find_multiproof(&[len]), None);
+        }
+        let mt = MerkleTree::new(TEST, true);
+        assert_eq!(mt.find_multiproof(&[3, 1]), None);
+        assert_eq!(mt.find_multiproof(&[1, 1]), None);
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_tree_par() {
+        for sorted_hashes in [false, true] {
+            for len in 0..TEST.len() {
+                assert_eq!(
+                    MerkleTree::new_with_hasher_par(&TEST[..len], sorted_hashes, &Sha256Hasher),
+                    MerkleTree::new(&TEST[..len], sorted_hashes)
+                );
+            }
+        }
+    }
+
     #[test]
     fn test_proof_entry_instantiation_lsib_set() {
         ProofEntry::new(&Hash::default(), Some(&Hash::default()), None);
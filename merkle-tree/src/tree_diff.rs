@@ -0,0 +1,260 @@
+use std::{fs, fs::File, io::Write, path::PathBuf};
+
+use indexmap::{map::Entry, IndexMap};
+use serde::{Deserialize, Serialize};
+use solana_program::pubkey::Pubkey;
+
+use crate::{
+    airdrop_merkle_tree::{AirdropMerkleTree, Result},
+    error::MerkleTreeError::MerkleValidationError,
+    utils::serde_pubkey,
+};
+
+/// Claim of a claimant in one of the compared trees
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Claim {
+    /// Version of the tree of the claimant
+    pub airdrop_version: u64,
+    pub amount: u64,
+    pub locked_amount: u64,
+}
+
+/// Claims of a claimant before and after, none if the claimant is missing from that side
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClaimantDiff {
+    #[serde(with = "serde_pubkey")]
+    pub claimant: Pubkey,
+    pub old: Option<Claim>,
+    pub new: Option<Claim>,
+}
+
+/// Differences between two trees, or two sets of trees, by claimant
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TreeDiff {
+    /// Claimants only in the new trees
+    pub added: Vec<ClaimantDiff>,
+    /// Claimants only in the old trees
+    pub removed: Vec<ClaimantDiff>,
+    /// Claimants whose amounts changed, or who moved to a tree of another version
+    pub changed: Vec<ClaimantDiff>,
+    pub num_unchanged: u64,
+    /// New total unlocked amount minus the old one
+    pub unlocked_amount_delta: i128,
+    /// New total locked amount minus the old one
+    pub locked_amount_delta: i128,
+}
+
+impl TreeDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    /// Change of the total amount, unlocked and locked
+    pub fn total_delta(&self) -> i128 {
+        self.unlocked_amount_delta + self.locked_amount_delta
+    }
+
+    /// Prints a summary followed by one line per added, removed or changed claimant
+    pub fn print(&self) {
+        println!(
+            "{} added, {} removed, {} changed, {} unchanged, total delta {} (unlocked {}, locked {})",
+            self.added.len(),
+            self.removed.len(),
+            self.changed.len(),
+            self.num_unchanged,
+            self.total_delta(),
+            self.unlocked_amount_delta,
+            self.locked_amount_delta
+        );
+        for diff in self.added.iter() {
+            println!("added {} {:?}", diff.claimant, diff.new);
+        }
+        for diff in self.removed.iter() {
+            println!("removed {} {:?}", diff.claimant, diff.old);
+        }
+        for diff in self.changed.iter() {
+            println!("changed {} {:?} -> {:?}", diff.claimant, diff.old, diff.new);
+        }
+    }
+
+    /// Writes the diff as json
+    pub fn write_to_file(&self, path: &PathBuf) -> Result<()> {
+        let serialized = serde_json::to_string_pretty(&self)?;
+        let mut file = File::create(path)?;
+        file.write_all(serialized.as_bytes())?;
+        Ok(())
+    }
+}
+
+/// Claims of the trees by claimant, in the order of the trees
+fn get_claims(trees: &[AirdropMerkleTree]) -> Result<IndexMap<Pubkey, Claim>> {
+    let mut claims = IndexMap::new();
+    for tree in trees {
+        for node in tree.tree_nodes.iter() {
+            let claim = Claim {
+                airdrop_version: tree.airdrop_version,
+                amount: node.amount,
+                locked_amount: node.locked_amount,
+            };
+            match claims.entry(node.claimant) {
+                Entry::Vacant(entry) => {
+                    entry.insert(claim);
+                }
+                Entry::Occupied(entry) => {
+                    return Err(MerkleValidationError(format!(
+                        "claimant {} is in the trees of versions {} and {}",
+                        node.claimant,
+                        entry.get().airdrop_version,
+                        tree.airdrop_version
+                    )));
+                }
+            }
+        }
+    }
+    Ok(claims)
+}
+
+fn get_totals<'a>(claims: impl Iterator<Item = &'a Claim>) -> (i128, i128) {
+    claims.fold((0, 0), |(unlocked, locked), claim| {
+        (
+            unlocked + claim.amount as i128,
+            locked + claim.locked_amount as i128,
+        )
+    })
+}
+
+/// Compares two sets of trees by claimant. A claimant must be in a single tree of each set
+pub fn diff_tree_sets(
+    old_trees: &[AirdropMerkleTree],
+    new_trees: &[AirdropMerkleTree],
+) -> Result<TreeDiff> {
+    let old_claims = get_claims(old_trees)?;
+    let new_claims = get_claims(new_trees)?;
+
+    let mut diff = TreeDiff::default();
+    for (claimant, old) in old_claims.iter() {
+        match new_claims.get(claimant) {
+            Some(new) if new == old => diff.num_unchanged += 1,
+            new => {
+                let claimant_diff = ClaimantDiff {
+                    claimant: *claimant,
+                    old: Some(*old),
+                    new: new.copied(),
+                };
+                if new.is_some() {
+                    diff.changed.push(claimant_diff);
+                } else {
+                    diff.removed.push(claimant_diff);
+                }
+            }
+        }
+    }
+    for (claimant, new) in new_claims.iter() {
+        if !old_claims.contains_key(claimant) {
+            diff.added.push(ClaimantDiff {
+                claimant: *claimant,
+                old: None,
+                new: Some(*new),
+            });
+        }
+    }
+
+    let (old_unlocked_amount, old_locked_amount) = get_totals(old_claims.values());
+    let (new_unlocked_amount, new_locked_amount) = get_totals(new_claims.values());
+    diff.unlocked_amount_delta = new_unlocked_amount - old_unlocked_amount;
+    diff.locked_amount_delta = new_locked_amount - old_locked_amount;
+    Ok(diff)
+}
+
+/// Compares two trees by claimant
+pub fn diff_trees(old_tree: &AirdropMerkleTree, new_tree: &AirdropMerkleTree) -> TreeDiff {
+    // claimants are unique within a tree
+    diff_tree_sets(
+        std::slice::from_ref(old_tree),
+        std::slice::from_ref(new_tree),
+    )
+    .unwrap()
+}
+
+/// Loads every tree of a directory, sorted by file name
+pub fn read_trees_from_dir(path: &PathBuf) -> Result<Vec<AirdropMerkleTree>> {
+    let mut paths = fs::read_dir(path)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<std::io::Result<Vec<_>>>()?;
+    paths.sort();
+    paths.iter().map(AirdropMerkleTree::new_from_file).collect()
+}
+
+/// Compares the trees of two directories by claimant, see [diff_tree_sets]
+pub fn diff_tree_dirs(old_path: &PathBuf, new_path: &PathBuf) -> Result<TreeDiff> {
+    diff_tree_sets(
+        &read_trees_from_dir(old_path)?,
+        &read_trees_from_dir(new_path)?,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tree_node::TreeNode;
+
+    fn build_tree(airdrop_version: u64, claims: &[(Pubkey, u64, u64)]) -> AirdropMerkleTree {
+        let tree_nodes = claims
+            .iter()
+            .map(|(claimant, amount, locked_amount)| TreeNode {
+                claimant: *claimant,
+                amount: *amount,
+                locked_amount: *locked_amount,
+            })
+            .collect();
+        AirdropMerkleTree::new(tree_nodes, airdrop_version).unwrap()
+    }
+
+    #[test]
+    fn test_diff_trees() {
+        let [unchanged, changed, removed, added, moved] = [(); 5].map(|_| Pubkey::new_unique());
+        let old_tree = build_tree(0, &[(unchanged, 10, 0), (changed, 10, 5), (removed, 7, 0)]);
+        let new_tree = build_tree(0, &[(added, 1, 1), (changed, 20, 0), (unchanged, 10, 0)]);
+
+        let diff = diff_trees(&old_tree, &new_tree);
+        assert_eq!(diff.num_unchanged, 1);
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.added[0].claimant, added);
+        assert_eq!(diff.added[0].old, None);
+        assert_eq!(diff.removed.len(), 1);
+        assert_eq!(diff.removed[0].claimant, removed);
+        assert_eq!(diff.removed[0].new, None);
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(
+            diff.changed[0].new,
+            Some(Claim {
+                airdrop_version: 0,
+                amount: 20,
+                locked_amount: 0
+            })
+        );
+        assert_eq!(diff.unlocked_amount_delta, 4);
+        assert_eq!(diff.locked_amount_delta, -4);
+        assert_eq!(diff.total_delta(), 0);
+        assert!(diff_trees(&old_tree, &old_tree).is_empty());
+
+        // the claimants are compared across the trees of each set
+        let old_trees = [old_tree.clone(), build_tree(1, &[(moved, 3, 0)])];
+        let new_trees = [build_tree(0, &[(moved, 3, 0)]), old_tree];
+        let diff = diff_tree_sets(&old_trees, &new_trees).unwrap();
+        assert_eq!(diff.num_unchanged, 3);
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.changed[0].claimant, moved);
+        assert_eq!(diff.total_delta(), 0);
+
+        let duplicate = [
+            build_tree(0, &[(moved, 1, 0)]),
+            build_tree(1, &[(moved, 1, 0)]),
+        ];
+        assert!(diff_tree_sets(&duplicate, &new_trees).is_err());
+
+        let value = serde_json::to_value(&diff).unwrap();
+        assert_eq!(value["changed"][0]["claimant"], moved.to_string());
+        assert_eq!(serde_json::from_value::<TreeDiff>(value).unwrap(), diff);
+    }
+}
@@ -1,10 +1,14 @@
-use std::str::FromStr;
+use std::{result, str::FromStr};
 
-use crate::csv_entry::CsvEntry;
+use crate::{
+    csv_entry::CsvEntry,
+    hasher::{Hasher, Sha256Hasher},
+};
 use rust_decimal::{prelude::FromPrimitive, Decimal};
 use serde::{Deserialize, Serialize};
-use solana_program::{hash::hashv, pubkey::Pubkey};
+use solana_program::pubkey::Pubkey;
 use solana_sdk::hash::Hash;
+use thiserror::Error;
 
 /// Represents the claim information for an account.
 #[derive(Debug, Clone, Eq, Hash, PartialEq, Serialize, Deserialize)]
@@ -15,17 +19,16 @@ pub struct TreeNode {
     pub amount: u64,
     /// Locked amount
     pub locked_amount: u64,
-    /// Claimant's proof of inclusion in the Merkle Tree
-    pub proof: Option<Vec<[u8; 32]>>,
 }
 
 impl TreeNode {
     pub fn hash(&self) -> Hash {
-        hashv(&[
-            &self.claimant.to_bytes(),
-            &self.amount.to_le_bytes(),
-            &self.locked_amount.to_le_bytes(),
-        ])
+        self.hash_with(&Sha256Hasher)
+    }
+
+    /// Hash of the claim with the hasher of the tree, the data of its leaf
+    pub fn hash_with<H: Hasher>(&self, hasher: &H) -> Hash {
+        hasher.hash_claim(&self.claimant, self.amount, self.locked_amount)
     }
     /// Return total amount for this claimant
     pub fn total_amount(&self) -> u64 {
@@ -42,6 +45,82 @@ impl TreeNode {
     }
 }
 
+/// Reason a ui amount can't be converted to a token amount
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum AmountError {
+    #[error("invalid decimal {0}")]
+    InvalidDecimal(String),
+    #[error("negative amount")]
+    Negative,
+    #[error("more than {0} decimals")]
+    TooPrecise(u32),
+    #[error("overflows u64")]
+    Overflow,
+}
+
+/// How a ui amount with more decimals than the mint is converted to a token amount
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DecimalMode {
+    /// Fails on any remainder beyond the decimals of the mint
+    #[default]
+    Strict,
+    /// Floors the amount, the remainder is lost as dust
+    Floor,
+}
+
+impl FromStr for DecimalMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> result::Result<Self, Self::Err> {
+        match s {
+            "strict" => Ok(DecimalMode::Strict),
+            "floor" => Ok(DecimalMode::Floor),
+            _ => Err(format!(
+                "unknown decimal mode {s}, expected strict or floor"
+            )),
+        }
+    }
+}
+
+/// Converts a ui amount to a token amount (with decimals), fails instead of panicking.
+/// Returns the token amount and the dust floored away in ui amount, always zero in strict mode
+pub fn convert_ui_amount(
+    amount: &str,
+    decimals: u32,
+    mode: DecimalMode,
+) -> result::Result<(u64, Decimal), AmountError> {
+    let amount = Decimal::from_str(amount.trim())
+        .map_err(|_| AmountError::InvalidDecimal(amount.to_string()))?;
+    if amount.is_sign_negative() && !amount.is_zero() {
+        return Err(AmountError::Negative);
+    }
+    let multiplier = 10u64
+        .checked_pow(decimals)
+        .and_then(Decimal::from_u64)
+        .ok_or(AmountError::Overflow)?;
+    let amount = amount
+        .checked_mul(multiplier)
+        .ok_or(AmountError::Overflow)?;
+    let remainder = amount.fract();
+    if !remainder.is_zero() && mode == DecimalMode::Strict {
+        return Err(AmountError::TooPrecise(decimals));
+    }
+    let token_amount = amount
+        .floor()
+        .try_into()
+        .map_err(|_| AmountError::Overflow)?;
+    Ok((token_amount, remainder / multiplier))
+}
+
+/// Converts a ui amount to a token amount (with decimals), fails on any precision loss
+pub fn try_ui_amount_to_token_amount(
+    amount: &str,
+    decimals: u32,
+) -> result::Result<u64, AmountError> {
+    convert_ui_amount(amount, decimals, DecimalMode::Strict).map(|(amount, _dust)| amount)
+}
+
 /// Converts a ui amount to a token amount (with decimals)
 pub fn ui_amount_to_token_amount(amount: &str, decimals: u32) -> u64 {
     let amount = Decimal::from_str(amount).unwrap();
@@ -58,7 +137,6 @@ impl TreeNode {
             claimant: Pubkey::from_str(entry.pubkey.as_str()).unwrap(),
             amount: ui_amount_to_token_amount(entry.amount.as_str(), decimals),
             locked_amount: ui_amount_to_token_amount(entry.locked_amount.as_str(), decimals),
-            proof: None,
         };
         node
     }
@@ -80,6 +158,55 @@ mod tests {
         assert_eq!(ui_amount_to_token_amount(amount, 2), 0);
     }
 
+    #[test]
+    fn test_try_ui_amount_to_token_amount() {
+        assert_eq!(try_ui_amount_to_token_amount("3.123", 3), Ok(3123));
+        assert_eq!(try_ui_amount_to_token_amount("1.50", 1), Ok(15));
+        assert_eq!(try_ui_amount_to_token_amount("-0", 2), Ok(0));
+        assert_eq!(
+            try_ui_amount_to_token_amount("3.1234", 3),
+            Err(AmountError::TooPrecise(3))
+        );
+        assert_eq!(
+            try_ui_amount_to_token_amount("-1", 2),
+            Err(AmountError::Negative)
+        );
+        assert_eq!(
+            try_ui_amount_to_token_amount("1O0", 2),
+            Err(AmountError::InvalidDecimal("1O0".to_string()))
+        );
+        assert_eq!(
+            try_ui_amount_to_token_amount("18446744073709551615", 0),
+            Ok(u64::MAX)
+        );
+        assert_eq!(
+            try_ui_amount_to_token_amount("18446744073709551616", 0),
+            Err(AmountError::Overflow)
+        );
+        assert_eq!(
+            try_ui_amount_to_token_amount("18446744073709551615", 9),
+            Err(AmountError::Overflow)
+        );
+    }
+
+    #[test]
+    fn test_convert_ui_amount_floor() {
+        assert_eq!(
+            convert_ui_amount("3.1234", 3, DecimalMode::Floor),
+            Ok((3123, Decimal::from_str("0.0004").unwrap()))
+        );
+        assert_eq!(
+            convert_ui_amount("3.123", 3, DecimalMode::Floor),
+            Ok((3123, Decimal::ZERO))
+        );
+        assert_eq!(
+            convert_ui_amount("-3.1234", 3, DecimalMode::Floor),
+            Err(AmountError::Negative)
+        );
+        assert_eq!("floor".parse(), Ok(DecimalMode::Floor));
+        assert!("round".parse::<DecimalMode>().is_err());
+    }
+
     #[test]
     fn test_csv_decimals_parsing() {
         let path = PathBuf::from("./test_fixtures/test_csv_decimal.csv");
@@ -64,11 +64,68 @@ pub fn get_claim_status_pda(
     )
 }
 
+pub fn get_pending_action_pda(
+    program_id: &Pubkey,
+    distributor: &Pubkey,
+    action_id: u64,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            b"PendingAction".as_ref(),
+            distributor.to_bytes().as_ref(),
+            action_id.to_le_bytes().as_ref(),
+        ],
+        program_id,
+    )
+}
+
+pub fn get_denylist_pda(program_id: &Pubkey, distributor: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"Denylist".as_ref(), distributor.to_bytes().as_ref()],
+        program_id,
+    )
+}
+
+pub fn get_bonus_vault_pda(program_id: &Pubkey, distributor: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"BonusVault".as_ref(), distributor.to_bytes().as_ref()],
+        program_id,
+    )
+}
+
+pub fn get_registry_pda(program_id: &Pubkey, base: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            b"Registry".as_ref(),
+            base.to_bytes().as_ref(),
+            mint.to_bytes().as_ref(),
+        ],
+        program_id,
+    )
+}
+
 #[derive(Debug)]
 pub struct MerkleValidationError {
     pub msg: String,
 }
 
+/// (De)serializes a pubkey in base58, to keep reports readable
+pub mod serde_pubkey {
+    use std::str::FromStr;
+
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use solana_program::pubkey::Pubkey;
+
+    pub fn serialize<S: Serializer>(pubkey: &Pubkey, serializer: S) -> Result<S::Ok, S::Error> {
+        pubkey.to_string().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Pubkey, D::Error> {
+        let pubkey = String::deserialize(deserializer)?;
+        Pubkey::from_str(&pubkey).map_err(serde::de::Error::custom)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -78,7 +135,6 @@ mod tests {
             claimant,
             amount,
             locked_amount,
-            proof: None,
         }
     }
 
@@ -59,4 +59,76 @@ pub enum ErrorCode {
     InvalidLocker,
     #[msg("Escrow is not max lock")]
     EscrowIsNotMaxLock,
+    #[msg("Invalid lock duration")]
+    InvalidLockDuration,
+    #[msg("Invalid escrow owner")]
+    InvalidEscrowOwner,
+    #[msg("Admin action must be queued when timelock is enabled")]
+    TimelockEnabled,
+    #[msg("Invalid action type")]
+    InvalidActionType,
+    #[msg("Timelock delay is not over")]
+    TimelockNotExpired,
+    #[msg("Invalid claim fee")]
+    InvalidClaimFee,
+    #[msg("Invalid fee receiver")]
+    InvalidFeeReceiver,
+    #[msg("Claim cap of the current window is reached, retry in the next window")]
+    ClaimCapExceeded,
+    #[msg("Invalid claim cap")]
+    InvalidClaimCap,
+    #[msg("Claimant is denylisted")]
+    ClaimantDenylisted,
+    #[msg("Claimant is already denylisted")]
+    AlreadyDenylisted,
+    #[msg("Claimant is not denylisted")]
+    NotDenylisted,
+    #[msg("Invalid denylist")]
+    InvalidDenylist,
+    #[msg("Vesting is already revoked")]
+    VestingAlreadyRevoked,
+    #[msg("No unvested tokens to revoke")]
+    NothingToRevoke,
+    #[msg("Invalid distributor layout")]
+    InvalidDistributorLayout,
+    #[msg("Distributor is already migrated to the current layout")]
+    DistributorAlreadyMigrated,
+    #[msg("Invalid vesting type")]
+    InvalidVestingType,
+    #[msg("Invalid bonus vault")]
+    InvalidBonusVault,
+    #[msg("Bonus vault can only be created before the first claim")]
+    BonusVaultAfterClaim,
+    #[msg("Bonus already clawed back")]
+    BonusAlreadyClawedBack,
+    #[msg("Invalid registry")]
+    InvalidRegistry,
+    #[msg("Same relayer")]
+    SameRelayer,
+    #[msg("Invalid relayer")]
+    InvalidRelayer,
+    #[msg("Invalid claim record tree")]
+    InvalidClaimRecordTree,
+    #[msg("Claim record tree can only be created before the first claim")]
+    ClaimRecordTreeAfterClaim,
+    #[msg("Invalid claim record tree depth")]
+    InvalidRecordTreeDepth,
+    #[msg("Root of the record proof is not in the changelog, refresh the proof")]
+    RecordRootNotFound,
+    #[msg("Record leaf changed since the proof was built")]
+    RecordLeafChanged,
+    #[msg("Claims of a compressed distributor must use the compressed instructions")]
+    CompressedClaimRequired,
+    #[msg("Invalid hash type")]
+    InvalidHashType,
+    #[msg("Batch claim needs a claim status and a token account per leaf")]
+    InvalidBatchClaimAccounts,
+    #[msg("Invalid claim status")]
+    InvalidClaimStatus,
+    #[msg("Pending action was queued by a previous admin")]
+    StaleAction,
+    #[msg("Rent payer account does not match the claim status")]
+    InvalidRentPayer,
+    #[msg("Invalid canopy depth")]
+    InvalidCanopyDepth,
 }
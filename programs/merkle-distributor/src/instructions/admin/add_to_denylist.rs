@@ -0,0 +1,86 @@
+use anchor_lang::{
+    accounts::signer::Signer, context::Context, prelude::*, system_program, Accounts, Key, Result,
+};
+
+use crate::{
+    error::ErrorCode,
+    math::safe_math::SafeMath,
+    state::{
+        denylist::Denylist,
+        denylist_event::DenylistAddedEvent,
+        merkle_distributor::{DistributorLoader, MerkleDistributor},
+    },
+};
+
+/// [merkle_distributor::add_to_denylist] accounts.
+#[derive(Accounts)]
+pub struct AddToDenylist<'info> {
+    /// The [MerkleDistributor].
+    #[account(
+        constraint = distributor.load_current().is_ok() @ ErrorCode::InvalidDistributorLayout,
+    )]
+    pub distributor: AccountLoader<'info, MerkleDistributor>,
+
+    /// Denylist PDA
+    #[account(mut, has_one = distributor)]
+    pub denylist: AccountLoader<'info, Denylist>,
+
+    /// Admin signer, pays the rent of the new entry
+    #[account(mut, address = distributor.load_current()?.admin @ ErrorCode::Unauthorized)]
+    pub admin: Signer<'info>,
+
+    /// The [System] program.
+    pub system_program: Program<'info, System>,
+}
+
+/// Adds a claimant to the denylist, blocking its new claims and locked claims.
+/// Its unclaimed allocation stays in the vault and can be clawed back.
+/// CHECK:
+///     1. The claimant is not already denylisted
+#[allow(clippy::result_large_err)]
+pub fn handle_add_to_denylist(ctx: Context<AddToDenylist>, claimant: Pubkey) -> Result<()> {
+    let denylist_info = ctx.accounts.denylist.to_account_info();
+    let len = ctx.accounts.denylist.load()?.len;
+
+    let index = {
+        let data = denylist_info.try_borrow_data()?;
+        match Denylist::entries(&data, len)?.binary_search(&claimant) {
+            Ok(_) => return Err(ErrorCode::AlreadyDenylisted.into()),
+            Err(index) => index,
+        }
+    };
+
+    let new_len = len.safe_add(1)?;
+    let new_space = Denylist::space(new_len)?;
+    let rent_due = Rent::get()?
+        .minimum_balance(new_space)
+        .saturating_sub(denylist_info.lamports());
+    if rent_due > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.admin.to_account_info(),
+                    to: denylist_info.clone(),
+                },
+            ),
+            rent_due,
+        )?;
+    }
+    denylist_info.realloc(new_space, false)?;
+
+    {
+        let mut data = denylist_info.try_borrow_mut_data()?;
+        let entries = Denylist::entries_mut(&mut data, new_len)?;
+        entries.copy_within(index..entries.len() - 1, index + 1);
+        entries[index] = claimant;
+    }
+    ctx.accounts.denylist.load_mut()?.len = new_len;
+
+    emit!(DenylistAddedEvent {
+        distributor: ctx.accounts.distributor.key(),
+        claimant,
+    });
+
+    Ok(())
+}
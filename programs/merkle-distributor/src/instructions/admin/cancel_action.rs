@@ -0,0 +1,51 @@
+use anchor_lang::{accounts::signer::Signer, context::Context, prelude::*, Accounts, Key, Result};
+
+use crate::{
+    error::ErrorCode,
+    state::{
+        action_event::ActionCancelledEvent,
+        merkle_distributor::{DistributorLoader, MerkleDistributor},
+        pending_action::PendingAction,
+    },
+};
+
+/// [merkle_distributor::cancel_action] accounts.
+#[derive(Accounts)]
+pub struct CancelAction<'info> {
+    /// The [MerkleDistributor].
+    #[account(
+        constraint = distributor.load_current().is_ok() @ ErrorCode::InvalidDistributorLayout,
+    )]
+    pub distributor: AccountLoader<'info, MerkleDistributor>,
+
+    /// Pending action PDA
+    #[account(
+        mut,
+        has_one = distributor,
+        has_one = payer,
+        close = payer,
+    )]
+    pub pending_action: AccountLoader<'info, PendingAction>,
+
+    /// CHECK: payer of the pending action, receives the rent back
+    #[account(mut)]
+    pub payer: UncheckedAccount<'info>,
+
+    /// Admin signer
+    #[account(address = distributor.load_current()?.admin @ ErrorCode::Unauthorized)]
+    pub admin: Signer<'info>,
+}
+
+/// Cancels a queued admin action before it is executed
+#[allow(clippy::result_large_err)]
+pub fn handle_cancel_action(ctx: Context<CancelAction>) -> Result<()> {
+    let pending_action = ctx.accounts.pending_action.load()?;
+
+    emit!(ActionCancelledEvent {
+        distributor: ctx.accounts.distributor.key(),
+        pending_action: ctx.accounts.pending_action.key(),
+        action_type: pending_action.action_type,
+    });
+
+    Ok(())
+}
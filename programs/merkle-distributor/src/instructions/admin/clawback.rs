@@ -6,26 +6,32 @@ use anchor_spl::{
     token::{Token, TokenAccount},
 };
 
-use crate::{error::ErrorCode, state::merkle_distributor::MerkleDistributor};
+use crate::{
+    error::ErrorCode,
+    state::merkle_distributor::{DistributorLoader, MerkleDistributor},
+};
 
 /// [merkle_distributor::clawback] accounts.
 #[derive(Accounts)]
 pub struct Clawback<'info> {
     /// The [MerkleDistributor].
-    #[account(mut, has_one = clawback_receiver)]
+    #[account(
+        mut,
+        constraint = distributor.load_current().is_ok() @ ErrorCode::InvalidDistributorLayout,
+    )]
     pub distributor: AccountLoader<'info, MerkleDistributor>,
 
     /// Distributor ATA containing the tokens to distribute.
     #[account(
         mut,
-        associated_token::mint = distributor.load()?.mint,
+        associated_token::mint = distributor.load_current()?.mint,
         associated_token::authority = distributor.key(),
-        address = distributor.load()?.token_vault
+        address = distributor.load_current()?.token_vault
     )]
     pub from: Account<'info, TokenAccount>,
 
     /// The Clawback token account.
-    #[account(mut)]
+    #[account(mut, address = distributor.load_current()?.clawback_receiver)]
     pub clawback_receiver: Account<'info, TokenAccount>,
 
     /// SPL [Token] program.
@@ -38,9 +44,10 @@ pub struct Clawback<'info> {
 /// 3. Marking the distributor as clawed back
 /// CHECK:
 ///     1. The distributor has not already been clawed back
+///     2. The vesting has ended
 #[allow(clippy::result_large_err)]
 pub fn handle_clawback(ctx: Context<Clawback>) -> Result<()> {
-    let mut distributor = ctx.accounts.distributor.load_mut()?;
+    let mut distributor = ctx.accounts.distributor.load_current_mut()?;
 
     require!(
         !distributor.clawed_back(),
@@ -53,6 +60,12 @@ pub fn handle_clawback(ctx: Context<Clawback>) -> Result<()> {
         return Err(ErrorCode::ClawbackBeforeStart.into());
     }
 
+    // clawback_start_ts is only estimated to be after the end of a slot vesting
+    require!(
+        distributor.get_vesting_point()? >= distributor.end_ts,
+        ErrorCode::ClawbackDuringVesting
+    );
+
     distributor.set_clawed_back();
 
     let signer = distributor.signer();
@@ -0,0 +1,81 @@
+// Instruction to clawback the unclaimed bonus once it has expired
+
+use anchor_lang::{context::Context, prelude::*, Accounts, Key, Result};
+use anchor_spl::{
+    token,
+    token::{Token, TokenAccount},
+};
+
+use crate::{
+    error::ErrorCode,
+    state::merkle_distributor::{DistributorLoader, MerkleDistributor},
+};
+
+/// [merkle_distributor::clawback_bonus] accounts.
+#[derive(Accounts)]
+pub struct ClawbackBonus<'info> {
+    /// The [MerkleDistributor].
+    #[account(
+        mut,
+        constraint = distributor.load_current().is_ok() @ ErrorCode::InvalidDistributorLayout,
+    )]
+    pub distributor: AccountLoader<'info, MerkleDistributor>,
+
+    /// Bonus vault of the distributor.
+    #[account(
+        mut,
+        address = distributor.load_current()?.bonus_vault @ ErrorCode::InvalidBonusVault,
+    )]
+    pub bonus_vault: Account<'info, TokenAccount>,
+
+    /// The Clawback token account.
+    #[account(mut, address = distributor.load_current()?.clawback_receiver)]
+    pub clawback_receiver: Account<'info, TokenAccount>,
+
+    /// SPL [Token] program.
+    pub token_program: Program<'info, Token>,
+}
+
+/// Claws back the unclaimed bonus by:
+/// 1. Checking that the lockup has expired
+/// 2. Transferring remaining funds from the bonus vault to the clawback receiver
+/// 3. Marking the bonus as clawed back, later claims get no bonus
+/// Not an admin action, anyone can call it once the clawback start is reached.
+/// CHECK:
+///     1. The bonus has not already been clawed back
+#[allow(clippy::result_large_err)]
+pub fn handle_clawback_bonus(ctx: Context<ClawbackBonus>) -> Result<()> {
+    let mut distributor = ctx.accounts.distributor.load_current_mut()?;
+
+    require!(
+        !distributor.bonus_clawed_back(),
+        ErrorCode::BonusAlreadyClawedBack
+    );
+
+    let curr_ts = Clock::get()?.unix_timestamp;
+
+    if curr_ts < distributor.clawback_start_ts {
+        return Err(ErrorCode::ClawbackBeforeStart.into());
+    }
+
+    distributor.bonus_clawed_back = 1;
+
+    let signer = distributor.signer();
+    drop(distributor);
+    let seeds = signer.seeds();
+
+    token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            token::Transfer {
+                from: ctx.accounts.bonus_vault.to_account_info(),
+                to: ctx.accounts.clawback_receiver.to_account_info(),
+                authority: ctx.accounts.distributor.to_account_info(),
+            },
+        )
+        .with_signer(&[&seeds[..]]),
+        ctx.accounts.bonus_vault.amount,
+    )?;
+
+    Ok(())
+}
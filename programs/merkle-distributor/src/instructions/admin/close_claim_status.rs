@@ -1,6 +1,12 @@
 use anchor_lang::{account, context::Context, prelude::*, Accounts, Key, ToAccountInfo};
 
-use crate::{error::ErrorCode, state::claim_status::ClaimStatus};
+use crate::{
+    error::ErrorCode,
+    state::{
+        claim_status::ClaimStatus,
+        merkle_distributor::{DistributorLoader, MerkleDistributor},
+    },
+};
 
 // Accounts for [merkle_distributor::close_claim_status].
 #[derive(Accounts)]
@@ -8,20 +14,43 @@ pub struct CloseClaimStatus<'info> {
     #[account(
         mut,
         has_one = claimant,
-        has_one = admin,
+        has_one = distributor,
         constraint = claim_status.load()?.closable() @ ErrorCode::CannotCloseClaimStatus,
-        close = claimant,
     )]
     pub claim_status: AccountLoader<'info, ClaimStatus>,
 
+    /// The [MerkleDistributor] of the claim status.
+    #[account(
+        constraint = distributor.load_current().is_ok() @ ErrorCode::InvalidDistributorLayout,
+    )]
+    pub distributor: AccountLoader<'info, MerkleDistributor>,
+
     /// CHECK: claimant
     #[account(mut)]
     pub claimant: UncheckedAccount<'info>,
 
+    /// CHECK: who paid the claim status rent, required when it is not the claimant
+    #[account(mut)]
+    pub rent_payer: Option<UncheckedAccount<'info>>,
+    #[account(address = distributor.load_current()?.admin)]
     pub admin: Signer<'info>,
 }
 
+/// Closes the claim status, refunding the rent to whoever paid it.
 #[allow(clippy::result_large_err)]
-pub fn handle_close_status(_ctx: Context<CloseClaimStatus>) -> Result<()> {
-    Ok(())
+pub fn handle_close_status(ctx: Context<CloseClaimStatus>) -> Result<()> {
+    let rent_receiver = ctx.accounts.claim_status.load()?.rent_receiver();
+    let rent_receiver = if rent_receiver == ctx.accounts.claimant.key() {
+        ctx.accounts.claimant.to_account_info()
+    } else {
+        let rent_payer = ctx
+            .accounts
+            .rent_payer
+            .as_ref()
+            .ok_or(ErrorCode::InvalidRentPayer)?;
+        require_keys_eq!(rent_payer.key(), rent_receiver, ErrorCode::InvalidRentPayer);
+        rent_payer.to_account_info()
+    };
+
+    ctx.accounts.claim_status.close(rent_receiver)
 }
@@ -1,7 +1,10 @@
 use anchor_lang::{account, context::Context, prelude::*, Accounts, Key, ToAccountInfo};
 use anchor_spl::token::{self, Token, TokenAccount};
 
-use crate::{error::ErrorCode, state::merkle_distributor::MerkleDistributor};
+use crate::{
+    error::ErrorCode,
+    state::merkle_distributor::{DistributorLoader, MerkleDistributor},
+};
 
 // Accounts for [merkle_distributor::close_distributor].
 #[derive(Accounts)]
@@ -9,20 +12,18 @@ pub struct CloseDistributor<'info> {
     /// [MerkleDistributor].
     #[account(
         mut,
-        has_one = admin,
-        has_one = token_vault,
-        constraint = distributor.load()?.closable() @ ErrorCode::CannotCloseDistributor,
-        close = admin
+        constraint = distributor.load_current()?.closable() @ ErrorCode::CannotCloseDistributor,
+        close = admin,
     )]
     pub distributor: AccountLoader<'info, MerkleDistributor>,
 
     /// Clawback receiver token account
-    #[account(mut)]
+    #[account(mut, address = distributor.load_current()?.token_vault)]
     pub token_vault: Account<'info, TokenAccount>,
 
     /// Admin wallet, responsible for creating the distributor and paying for the transaction.
     /// Also has the authority to set the clawback receiver and change itself.
-    #[account(mut)]
+    #[account(mut, address = distributor.load_current()?.admin)]
     pub admin: Signer<'info>,
 
     /// account receive token back
@@ -35,7 +36,7 @@ pub struct CloseDistributor<'info> {
 
 #[allow(clippy::result_large_err)]
 pub fn handle_close_distributor(ctx: Context<CloseDistributor>) -> Result<()> {
-    let distributor = ctx.accounts.distributor.load()?;
+    let distributor = ctx.accounts.distributor.load_current()?;
     let signer = distributor.signer();
     drop(distributor);
     let seeds = signer.seeds();
@@ -0,0 +1,107 @@
+use anchor_lang::{context::Context, prelude::*, Accounts, Key, Result};
+use anchor_spl::token::TokenAccount;
+
+use crate::{
+    error::ErrorCode,
+    state::{
+        action_event::ActionExecutedEvent,
+        merkle_distributor::{DistributorLoader, MerkleDistributor},
+        pending_action::{ActionType, PendingAction},
+    },
+};
+
+/// [merkle_distributor::execute_action] accounts.
+#[derive(Accounts)]
+pub struct ExecuteAction<'info> {
+    /// The [MerkleDistributor].
+    #[account(
+        mut,
+        constraint = distributor.load_current().is_ok() @ ErrorCode::InvalidDistributorLayout,
+    )]
+    pub distributor: AccountLoader<'info, MerkleDistributor>,
+
+    /// Pending action PDA
+    #[account(
+        mut,
+        has_one = distributor,
+        has_one = payer,
+        close = payer,
+    )]
+    pub pending_action: AccountLoader<'info, PendingAction>,
+
+    /// CHECK: payer of the pending action, receives the rent back
+    #[account(mut)]
+    pub payer: UncheckedAccount<'info>,
+
+    /// New clawback account, only required for set clawback receiver action
+    #[account(token::mint = distributor.load_current()?.mint)]
+    pub new_clawback_account: Option<Account<'info, TokenAccount>>,
+}
+
+/// Executes a queued admin action, can be called by anyone
+/// CHECK:
+///     1. The timelock delay is over
+///     2. The action was queued by the current admin
+///     3. The new value still passes the checks of the direct admin instruction
+#[allow(clippy::result_large_err)]
+pub fn handle_execute_action(ctx: Context<ExecuteAction>) -> Result<()> {
+    let pending_action = ctx.accounts.pending_action.load()?;
+    let mut distributor = ctx.accounts.distributor.load_current_mut()?;
+
+    let curr_ts = Clock::get()?.unix_timestamp;
+    require!(
+        pending_action.is_executable(curr_ts),
+        ErrorCode::TimelockNotExpired
+    );
+    require!(
+        pending_action.is_queued_by_admin(&distributor),
+        ErrorCode::StaleAction
+    );
+    pending_action.validate(&distributor, &ctx.accounts.new_clawback_account)?;
+
+    let action_type = ActionType::try_from(pending_action.action_type)
+        .map_err(|_| ErrorCode::InvalidActionType)?;
+    match action_type {
+        ActionType::SetActivationPoint => {
+            distributor.activation_point = pending_action.new_value;
+        }
+        ActionType::SetClawbackReceiver => {
+            distributor.clawback_receiver = pending_action.new_pubkey;
+        }
+        ActionType::SetOperator => {
+            distributor.operator = pending_action.new_pubkey;
+        }
+        ActionType::SetAdmin => {
+            distributor.set_admin(pending_action.new_pubkey);
+        }
+        ActionType::SetRelayer => {
+            distributor.relayer = pending_action.new_pubkey;
+        }
+        ActionType::SetTimelockDelay => {
+            distributor.timelock_delay =
+                u32::try_from(pending_action.new_value).map_err(|_| ErrorCode::TypeCastedError)?;
+        }
+        ActionType::SetClaimCap => {
+            distributor.set_claim_cap(
+                pending_action.claim_cap_type,
+                pending_action.new_value,
+                pending_action.claim_window_length,
+            )?;
+        }
+    }
+
+    // Note: might get truncated, do not rely on
+    msg!(
+        "executed action {} type {:?}",
+        pending_action.action_id,
+        action_type
+    );
+
+    emit!(ActionExecutedEvent {
+        distributor: ctx.accounts.distributor.key(),
+        pending_action: ctx.accounts.pending_action.key(),
+        action_type: pending_action.action_type,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,85 @@
+use anchor_lang::{
+    accounts::signer::Signer, context::Context, prelude::*, system_program, Accounts,
+    Discriminator, Key, Result,
+};
+
+use crate::{
+    error::ErrorCode,
+    state::merkle_distributor::{MerkleDistributor, LAYOUT_V0_SPACE},
+};
+
+/// Offset of the admin in the account data, same in every layout version
+/// (discriminator, root, mint, base, token_vault, clawback_receiver)
+const ADMIN_OFFSET: usize = 8 + 32 * 5;
+
+/// [merkle_distributor::migrate_distributor] accounts.
+#[derive(Accounts)]
+pub struct MigrateDistributor<'info> {
+    /// CHECK: The [MerkleDistributor], loaded in the handler as it may still have an older layout
+    #[account(mut, owner = crate::ID)]
+    pub distributor: UncheckedAccount<'info>,
+
+    /// Admin signer, pays the rent of the extra space
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// The [System] program.
+    pub system_program: Program<'info, System>,
+}
+
+/// Migrates a distributor to the current layout version by:
+/// 1. Reallocating the account to the current layout size
+/// 2. Filling in the default values of the new fields
+/// CHECK:
+///     1. The account is a distributor with a known layout
+///     2. The signer is the admin of the distributor
+///     3. The distributor is not already at the current layout version
+#[allow(clippy::result_large_err)]
+pub fn handle_migrate_distributor(ctx: Context<MigrateDistributor>) -> Result<()> {
+    let distributor_info = ctx.accounts.distributor.to_account_info();
+
+    {
+        let data = distributor_info.try_borrow_data()?;
+        require!(
+            data.len() >= 8 + LAYOUT_V0_SPACE && data[..8] == MerkleDistributor::DISCRIMINATOR,
+            ErrorCode::InvalidDistributorLayout
+        );
+        require!(
+            data[ADMIN_OFFSET..ADMIN_OFFSET + 32] == ctx.accounts.admin.key().to_bytes(),
+            ErrorCode::Unauthorized
+        );
+    }
+
+    let new_space = 8 + MerkleDistributor::INIT_SPACE;
+    if distributor_info.data_len() < new_space {
+        let rent_due = Rent::get()?
+            .minimum_balance(new_space)
+            .saturating_sub(distributor_info.lamports());
+        if rent_due > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.admin.to_account_info(),
+                        to: distributor_info.clone(),
+                    },
+                ),
+                rent_due,
+            )?;
+        }
+        distributor_info.realloc(new_space, true)?;
+    }
+
+    let distributor_loader = AccountLoader::<MerkleDistributor>::try_from(&distributor_info)?;
+    let mut distributor = distributor_loader.load_mut()?;
+    distributor.migrate()?;
+
+    // Note: might get truncated, do not rely on
+    msg!(
+        "migrated distributor {} to layout version {}",
+        distributor_info.key(),
+        distributor.layout_version
+    );
+
+    Ok(())
+}
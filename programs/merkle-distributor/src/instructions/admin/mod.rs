@@ -15,3 +15,29 @@ pub use set_admin::*;
 pub use set_clawback_receiver::*;
 pub mod set_operator;
 pub use set_operator::*;
+pub mod cancel_action;
+pub mod execute_action;
+pub mod queue_action;
+pub use cancel_action::*;
+pub use execute_action::*;
+pub use queue_action::*;
+pub mod set_claim_cap;
+pub use set_claim_cap::*;
+pub mod add_to_denylist;
+pub mod new_denylist;
+pub mod remove_from_denylist;
+pub use add_to_denylist::*;
+pub use new_denylist::*;
+pub use remove_from_denylist::*;
+pub mod revoke_vesting;
+pub use revoke_vesting::*;
+pub mod migrate_distributor;
+pub use migrate_distributor::*;
+pub mod clawback_bonus;
+pub mod new_bonus_vault;
+pub use clawback_bonus::*;
+pub use new_bonus_vault::*;
+pub mod set_relayer;
+pub use set_relayer::*;
+pub mod new_claim_record_tree;
+pub use new_claim_record_tree::*;
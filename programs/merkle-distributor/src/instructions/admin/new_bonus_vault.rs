@@ -0,0 +1,66 @@
+use anchor_lang::{accounts::signer::Signer, context::Context, prelude::*, Accounts, Key, Result};
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+use crate::{
+    error::ErrorCode,
+    state::merkle_distributor::{DistributorLoader, MerkleDistributor},
+};
+
+/// [merkle_distributor::new_bonus_vault] accounts.
+#[derive(Accounts)]
+pub struct NewBonusVault<'info> {
+    /// The [MerkleDistributor].
+    #[account(
+        mut,
+        constraint = distributor.load_current().is_ok() @ ErrorCode::InvalidDistributorLayout,
+    )]
+    pub distributor: AccountLoader<'info, MerkleDistributor>,
+
+    /// Bonus vault PDA, owned by the distributor
+    #[account(
+        init,
+        seeds = [
+            b"BonusVault".as_ref(),
+            distributor.key().to_bytes().as_ref(),
+        ],
+        bump,
+        token::mint = mint,
+        token::authority = distributor,
+        payer = admin,
+    )]
+    pub bonus_vault: Account<'info, TokenAccount>,
+
+    /// The mint to distribute.
+    #[account(address = distributor.load_current()?.mint)]
+    pub mint: Account<'info, Mint>,
+
+    /// Admin signer
+    #[account(mut, address = distributor.load_current()?.admin @ ErrorCode::Unauthorized)]
+    pub admin: Signer<'info>,
+
+    /// The [System] program.
+    pub system_program: Program<'info, System>,
+
+    /// SPL [Token] program.
+    pub token_program: Program<'info, Token>,
+}
+
+/// Creates a vault holding the bonus apart from the token vault, funded and clawed back independently
+/// CHECK:
+///     1. No claim has been made yet, so every bonus is paid from the bonus vault
+#[allow(clippy::result_large_err)]
+pub fn handle_new_bonus_vault(ctx: Context<NewBonusVault>) -> Result<()> {
+    let mut distributor = ctx.accounts.distributor.load_current_mut()?;
+
+    require!(
+        distributor.num_nodes_claimed == 0,
+        ErrorCode::BonusVaultAfterClaim
+    );
+
+    distributor.bonus_vault = ctx.accounts.bonus_vault.key();
+
+    // Note: might get truncated, do not rely on
+    msg!("created bonus vault {}", distributor.bonus_vault);
+
+    Ok(())
+}
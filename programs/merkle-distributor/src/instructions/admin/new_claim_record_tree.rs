@@ -0,0 +1,84 @@
+use anchor_lang::{accounts::signer::Signer, context::Context, prelude::*, Accounts, Key, Result};
+
+use crate::{
+    error::ErrorCode,
+    state::{
+        claim_record_tree::{ClaimRecordTree, MAX_CANOPY_DEPTH, MAX_RECORD_TREE_DEPTH},
+        merkle_distributor::{DistributorLoader, MerkleDistributor},
+    },
+};
+
+/// [merkle_distributor::new_claim_record_tree] accounts.
+#[derive(Accounts)]
+pub struct NewClaimRecordTree<'info> {
+    /// The [MerkleDistributor].
+    #[account(
+        mut,
+        constraint = distributor.load_current().is_ok() @ ErrorCode::InvalidDistributorLayout,
+    )]
+    pub distributor: AccountLoader<'info, MerkleDistributor>,
+
+    /// Claim record tree, created beforehand with [ClaimRecordTree::space] bytes of its canopy
+    /// depth owned by the program
+    #[account(zero)]
+    pub claim_record_tree: AccountLoader<'info, ClaimRecordTree>,
+
+    /// Admin signer
+    #[account(address = distributor.load_current()?.admin @ ErrorCode::Unauthorized)]
+    pub admin: Signer<'info>,
+}
+
+/// Makes the distributor compressed, claims are then recorded as leaves of the claim record tree
+/// instead of claim status accounts.
+/// The tree starts empty, the record of a claimant is written at the path of its leaf in the
+/// airdrop merkle tree, which takes one more level than the airdrop merkle tree.
+/// CHECK:
+///     1. The distributor is not compressed yet
+///     2. No claim has been made yet, so every claim is recorded in the tree
+///     3. The tree of max_depth fits the record indexes of max_num_nodes leaves
+///     4. The canopy fits in the tree and in the account
+#[allow(clippy::result_large_err)]
+pub fn handle_new_claim_record_tree(
+    ctx: Context<NewClaimRecordTree>,
+    max_depth: u32,
+    canopy_depth: u32,
+) -> Result<()> {
+    let mut distributor = ctx.accounts.distributor.load_current_mut()?;
+
+    require!(
+        !distributor.is_compressed(),
+        ErrorCode::InvalidClaimRecordTree
+    );
+    require!(
+        distributor.num_nodes_claimed == 0,
+        ErrorCode::ClaimRecordTreeAfterClaim
+    );
+    require!(
+        max_depth > 0
+            && max_depth as usize <= MAX_RECORD_TREE_DEPTH
+            && distributor.max_num_nodes <= 1u64 << (max_depth - 1),
+        ErrorCode::InvalidRecordTreeDepth
+    );
+    require!(
+        canopy_depth <= max_depth
+            && canopy_depth as usize <= MAX_CANOPY_DEPTH
+            && ctx.accounts.claim_record_tree.as_ref().data_len()
+                == ClaimRecordTree::space(canopy_depth),
+        ErrorCode::InvalidCanopyDepth
+    );
+
+    let mut claim_record_tree = ctx.accounts.claim_record_tree.load_init()?;
+    claim_record_tree.initialize(ctx.accounts.distributor.key(), max_depth, canopy_depth);
+
+    distributor.claim_record_tree = ctx.accounts.claim_record_tree.key();
+
+    // Note: might get truncated, do not rely on
+    msg!(
+        "created claim record tree {} with depth {} and canopy depth {}",
+        distributor.claim_record_tree,
+        max_depth,
+        canopy_depth
+    );
+
+    Ok(())
+}
@@ -0,0 +1,56 @@
+use anchor_lang::{accounts::signer::Signer, context::Context, prelude::*, Accounts, Key, Result};
+
+use crate::{
+    error::ErrorCode,
+    state::{
+        denylist::Denylist,
+        merkle_distributor::{DistributorLoader, MerkleDistributor},
+    },
+};
+
+/// [merkle_distributor::new_denylist] accounts.
+#[derive(Accounts)]
+pub struct NewDenylist<'info> {
+    /// The [MerkleDistributor].
+    #[account(
+        mut,
+        constraint = distributor.load_current().is_ok() @ ErrorCode::InvalidDistributorLayout,
+    )]
+    pub distributor: AccountLoader<'info, MerkleDistributor>,
+
+    /// Denylist PDA
+    #[account(
+        init,
+        seeds = [
+            b"Denylist".as_ref(),
+            distributor.key().to_bytes().as_ref(),
+        ],
+        bump,
+        space = 8 + Denylist::INIT_SPACE,
+        payer = admin,
+    )]
+    pub denylist: AccountLoader<'info, Denylist>,
+
+    /// Admin signer
+    #[account(mut, address = distributor.load_current()?.admin @ ErrorCode::Unauthorized)]
+    pub admin: Signer<'info>,
+
+    /// The [System] program.
+    pub system_program: Program<'info, System>,
+}
+
+/// Creates an empty denylist, claims must provide it from now on.
+#[allow(clippy::result_large_err)]
+pub fn handle_new_denylist(ctx: Context<NewDenylist>) -> Result<()> {
+    let mut distributor = ctx.accounts.distributor.load_current_mut()?;
+    let mut denylist = ctx.accounts.denylist.load_init()?;
+
+    denylist.distributor = ctx.accounts.distributor.key();
+    denylist.len = 0;
+    distributor.has_denylist = 1;
+
+    // Note: might get truncated, do not rely on
+    msg!("created denylist {}", ctx.accounts.denylist.key());
+
+    Ok(())
+}
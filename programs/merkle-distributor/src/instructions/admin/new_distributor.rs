@@ -1,11 +1,21 @@
 use crate::error::ErrorCode::ArithmeticError;
-use crate::state::merkle_distributor::{ActivationType, ClaimType};
+use crate::math::safe_math::SafeMath;
+use crate::state::merkle_distributor::{
+    ActivationType, ClaimFeeType, ClaimType, VestingType, CURRENT_LAYOUT_VERSION, MAX_BPS,
+};
 use crate::{
     error::ErrorCode,
+    instructions::append_to_registry,
     state::merkle_distributor::{AirdropBonus, MerkleDistributor},
+    state::registry::RegistryEntry,
+};
+use anchor_lang::{
+    account, context::Context, prelude::*, solana_program::clock::DEFAULT_MS_PER_SLOT, Accounts,
+    Key, ToAccountInfo,
 };
-use anchor_lang::{account, context::Context, prelude::*, Accounts, Key, ToAccountInfo};
 use anchor_spl::token::{Mint, Token, TokenAccount};
+use jito_merkle_verify::HashType;
+use locked_voter::Locker;
 
 #[cfg(feature = "localnet")]
 const SECONDS_PER_DAY: i64 = 0;
@@ -30,6 +40,13 @@ pub struct NewDistributorParams {
     pub claim_type: u8,
     pub operator: Pubkey,
     pub locker: Pubkey,
+    pub min_lock_duration: u32,
+    pub timelock_delay: u32,
+    pub claim_fee_type: u8,
+    pub claim_fee: u64,
+    pub fee_receiver: Pubkey,
+    pub vesting_type: u8,
+    pub hash_type: u8,
 }
 
 impl NewDistributorParams {
@@ -52,29 +69,51 @@ impl NewDistributorParams {
         ActivationType::try_from(self.activation_type)
             .map_err(|_| ErrorCode::InvalidActivationType)?;
 
-        let curr_ts = Clock::get()?.unix_timestamp;
+        let vesting_type =
+            VestingType::try_from(self.vesting_type).map_err(|_| ErrorCode::InvalidVestingType)?;
+
+        HashType::try_from(self.hash_type).map_err(|_| ErrorCode::InvalidHashType)?;
+
+        let clock = Clock::get()?;
+        let curr_point = match vesting_type {
+            VestingType::Timestamp => clock.unix_timestamp,
+            VestingType::Slot => clock.slot as i64,
+        };
 
         require!(
             self.start_vesting_ts < self.end_vesting_ts,
             ErrorCode::StartTimestampAfterEnd
         );
 
+        // New distributor parameters must all be set in the future
         require!(
-            self.clawback_start_ts > self.end_vesting_ts,
-            ErrorCode::ClawbackDuringVesting
+            self.start_vesting_ts > curr_point,
+            ErrorCode::TimestampsNotInFuture
         );
 
-        // New distributor parameters must all be set in the future
+        // clawback_start_ts is always a timestamp, estimate when a slot vesting ends
+        let end_vesting_ts = match vesting_type {
+            VestingType::Timestamp => self.end_vesting_ts,
+            VestingType::Slot => {
+                let remaining_ms = self
+                    .end_vesting_ts
+                    .safe_sub(curr_point)?
+                    .safe_mul(DEFAULT_MS_PER_SLOT as i64)?;
+                clock
+                    .unix_timestamp
+                    .safe_add(remaining_ms.safe_div(1000)?)?
+            }
+        };
+
         require!(
-            self.start_vesting_ts > curr_ts,
-            ErrorCode::TimestampsNotInFuture
+            self.clawback_start_ts > end_vesting_ts,
+            ErrorCode::ClawbackDuringVesting
         );
 
         // Ensure clawback_start_ts is at least one day after end_vesting_ts
         require!(
             self.clawback_start_ts
-                >= self
-                    .end_vesting_ts
+                >= end_vesting_ts
                     .checked_add(SECONDS_PER_DAY)
                     .ok_or(ErrorCode::ArithmeticError)?,
             ErrorCode::InsufficientClawbackDelay
@@ -90,9 +129,11 @@ impl NewDistributorParams {
                     self.operator == Pubkey::default(),
                     ErrorCode::InvalidOperator
                 );
+                require!(self.min_lock_duration == 0, ErrorCode::InvalidLockDuration);
             }
             ClaimType::Permissioned => {
                 require!(self.locker == Pubkey::default(), ErrorCode::InvalidLocker);
+                require!(self.min_lock_duration == 0, ErrorCode::InvalidLockDuration);
             }
             ClaimType::PermissionlessWithStaking => {
                 require!(self.locker != Pubkey::default(), ErrorCode::InvalidLocker);
@@ -105,6 +146,48 @@ impl NewDistributorParams {
                 require!(self.locker != Pubkey::default(), ErrorCode::InvalidLocker);
             }
         }
+
+        // validate claim fee
+        let claim_fee_type =
+            ClaimFeeType::try_from(self.claim_fee_type).map_err(|_| ErrorCode::InvalidClaimFee)?;
+        match claim_fee_type {
+            ClaimFeeType::None => {
+                require!(self.claim_fee == 0, ErrorCode::InvalidClaimFee);
+                require!(
+                    self.fee_receiver == Pubkey::default(),
+                    ErrorCode::InvalidFeeReceiver
+                );
+            }
+            ClaimFeeType::Lamports | ClaimFeeType::Bps => {
+                require!(self.claim_fee > 0, ErrorCode::InvalidClaimFee);
+                require!(
+                    claim_fee_type != ClaimFeeType::Bps || self.claim_fee <= MAX_BPS,
+                    ErrorCode::InvalidClaimFee
+                );
+                require!(
+                    self.fee_receiver != Pubkey::default(),
+                    ErrorCode::InvalidFeeReceiver
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Escrow locks are extended to min_lock_duration, which must be a stake duration the
+    /// locker accepts
+    pub fn validate_min_lock_duration(&self, locker: &Option<Box<Account<Locker>>>) -> Result<()> {
+        if self.min_lock_duration == 0 {
+            return Ok(());
+        }
+        let locker = locker.as_ref().ok_or(ErrorCode::InvalidLocker)?;
+        require!(locker.key() == self.locker, ErrorCode::InvalidLocker);
+
+        let min_lock_duration = u64::from(self.min_lock_duration);
+        require!(
+            min_lock_duration >= locker.params.min_stake_duration
+                && min_lock_duration <= locker.params.max_stake_duration,
+            ErrorCode::InvalidLockDuration
+        );
         Ok(())
     }
 }
@@ -127,6 +210,18 @@ pub struct NewDistributor<'info> {
     )]
     pub distributor: AccountLoader<'info, MerkleDistributor>,
 
+    /// CHECK: Registry of the distributors of the base and mint, created with the first one
+    #[account(
+        mut,
+        seeds = [
+            b"Registry".as_ref(),
+            base.key().to_bytes().as_ref(),
+            mint.key().to_bytes().as_ref(),
+        ],
+        bump,
+    )]
+    pub registry: UncheckedAccount<'info>,
+
     /// Base key of the distributor.
     pub base: Signer<'info>,
 
@@ -155,9 +250,12 @@ pub struct NewDistributor<'info> {
 
     /// The [Token] program.
     pub token_program: Program<'info, Token>,
+
+    /// Locker of the escrows, required when min_lock_duration is set
+    pub locker: Option<Box<Account<'info, Locker>>>,
 }
 
-/// Creates a new [MerkleDistributor].
+/// Creates a new [MerkleDistributor] and appends it to the registry of its base and mint.
 /// After creating this [MerkleDistributor],
 /// the token_vault should be seeded with max_total_claim tokens.
 /// CHECK:
@@ -165,6 +263,9 @@ pub struct NewDistributor<'info> {
 ///     2. The clawback timestamp is after the end timestamp
 ///     3. The start, end, and clawback_start timestamps are all in the future
 ///     4. The clawback start is at least one day after end timestamp
+///     5. The claim fee is within bounds and has a fee receiver
+///     6. The hash type is sha256 or keccak256
+///     7. The min lock duration is within the stake durations of the locker
 #[allow(clippy::too_many_arguments)]
 #[allow(clippy::result_large_err)]
 pub fn handle_new_distributor(
@@ -172,10 +273,12 @@ pub fn handle_new_distributor(
     params: &NewDistributorParams,
 ) -> Result<()> {
     params.validate()?;
+    params.validate_min_lock_duration(&ctx.accounts.locker)?;
 
     let mut distributor = ctx.accounts.distributor.load_init()?;
 
     distributor.bump = *ctx.bumps.get("distributor").unwrap();
+    distributor.layout_version = CURRENT_LAYOUT_VERSION;
     distributor.version = params.version;
     distributor.root = params.root;
     distributor.mint = ctx.accounts.mint.key();
@@ -200,10 +303,39 @@ pub fn handle_new_distributor(
     distributor.activation_type = params.activation_type;
     distributor.operator = params.operator;
     distributor.locker = params.locker;
+    distributor.min_lock_duration = params.min_lock_duration;
+    distributor.timelock_delay = params.timelock_delay;
+    distributor.claim_fee_type = params.claim_fee_type;
+    distributor.claim_fee = params.claim_fee;
+    distributor.fee_receiver = params.fee_receiver;
+    distributor.vesting_type = params.vesting_type;
+    distributor.hash_type = params.hash_type;
+
+    let base = ctx.accounts.base.key();
+    let mint = ctx.accounts.mint.key();
+    let registry_bump = *ctx.bumps.get("registry").unwrap();
+    append_to_registry(
+        ctx.accounts.registry.to_account_info(),
+        ctx.accounts.admin.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        &[&[
+            b"Registry".as_ref(),
+            base.as_ref(),
+            mint.as_ref(),
+            &[registry_bump],
+        ]],
+        base,
+        mint,
+        RegistryEntry {
+            version: params.version,
+            distributor: ctx.accounts.distributor.key(),
+            root: params.root,
+        },
+    )?;
 
     // Note: might get truncated, do not rely on
     msg! {
-        "New distributor created with version = {}, mint={}, vault={} max_total_claim={}, max_nodes: {}, start_ts: {}, end_ts: {}, clawback_start: {}, clawback_receiver: {} activation_point {} activation_type {} total_bonus {}, bonus_vesting_duration {}, claim_type {}",
+        "New distributor created with version = {}, mint={}, vault={} max_total_claim={}, max_nodes: {}, start_ts: {}, end_ts: {}, clawback_start: {}, clawback_receiver: {} activation_point {} activation_type {} total_bonus {}, bonus_vesting_duration {}, claim_type {}, min_lock_duration {}, timelock_delay {}, claim_fee_type {}, claim_fee {}, fee_receiver {}, vesting_type {}, hash_type {}",
             distributor.version,
             distributor.mint,
             ctx.accounts.token_vault.key(),
@@ -218,6 +350,13 @@ pub fn handle_new_distributor(
             distributor.airdrop_bonus.total_bonus,
             distributor.airdrop_bonus.vesting_duration,
             distributor.claim_type,
+            distributor.min_lock_duration,
+            distributor.timelock_delay,
+            distributor.claim_fee_type,
+            distributor.claim_fee,
+            distributor.fee_receiver,
+            distributor.vesting_type,
+            distributor.hash_type,
     };
 
     Ok(())
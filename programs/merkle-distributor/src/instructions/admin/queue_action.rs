@@ -0,0 +1,97 @@
+use anchor_lang::{
+    accounts::signer::Signer, context::Context, prelude::*, system_program::System, Accounts, Key,
+    Result,
+};
+use anchor_spl::token::TokenAccount;
+
+use crate::{
+    error::ErrorCode,
+    math::safe_math::SafeMath,
+    state::{
+        action_event::ActionQueuedEvent,
+        merkle_distributor::{DistributorLoader, MerkleDistributor},
+        pending_action::PendingAction,
+    },
+};
+
+/// [merkle_distributor::queue_action] accounts.
+#[derive(Accounts)]
+pub struct QueueAction<'info> {
+    /// The [MerkleDistributor].
+    #[account(
+        mut,
+        constraint = distributor.load_current().is_ok() @ ErrorCode::InvalidDistributorLayout,
+    )]
+    pub distributor: AccountLoader<'info, MerkleDistributor>,
+
+    /// Pending action PDA
+    #[account(
+        init,
+        seeds = [
+            b"PendingAction".as_ref(),
+            distributor.key().to_bytes().as_ref(),
+            distributor.load_current()?.num_queued_actions.to_le_bytes().as_ref()
+        ],
+        bump,
+        space = 8 + PendingAction::INIT_SPACE,
+        payer = admin,
+    )]
+    pub pending_action: AccountLoader<'info, PendingAction>,
+
+    /// New clawback account, only required for set clawback receiver action
+    #[account(token::mint = distributor.load_current()?.mint)]
+    pub new_clawback_account: Option<Account<'info, TokenAccount>>,
+
+    /// Admin signer
+    #[account(mut, address = distributor.load_current()?.admin @ ErrorCode::Unauthorized)]
+    pub admin: Signer<'info>,
+
+    /// The [System] program.
+    pub system_program: Program<'info, System>,
+}
+
+/// Queues an admin action, which can be executed by anyone after the timelock delay
+/// CHECK:
+///     1. The action type is valid
+///     2. The new value passes the same checks as the direct admin instruction
+#[allow(clippy::result_large_err)]
+pub fn handle_queue_action(
+    ctx: Context<QueueAction>,
+    action_type: u8,
+    new_pubkey: Pubkey,
+    new_value: u64,
+    claim_cap_type: u8,
+    claim_window_length: u32,
+) -> Result<()> {
+    let mut distributor = ctx.accounts.distributor.load_current_mut()?;
+
+    let curr_ts = Clock::get()?.unix_timestamp;
+    let executable_ts = curr_ts.safe_add(i64::from(distributor.timelock_delay))?;
+
+    let mut pending_action = ctx.accounts.pending_action.load_init()?;
+    pending_action.distributor = ctx.accounts.distributor.key();
+    pending_action.payer = ctx.accounts.admin.key();
+    pending_action.new_pubkey = new_pubkey;
+    pending_action.action_id = distributor.num_queued_actions;
+    pending_action.new_value = new_value;
+    pending_action.queued_ts = curr_ts;
+    pending_action.executable_ts = executable_ts;
+    pending_action.action_type = action_type;
+    pending_action.claim_cap_type = claim_cap_type;
+    pending_action.claim_window_length = claim_window_length;
+
+    pending_action.validate(&distributor, &ctx.accounts.new_clawback_account)?;
+
+    distributor.num_queued_actions = distributor.num_queued_actions.safe_add(1)?;
+
+    emit!(ActionQueuedEvent {
+        distributor: ctx.accounts.distributor.key(),
+        pending_action: ctx.accounts.pending_action.key(),
+        action_type,
+        new_pubkey,
+        new_value,
+        executable_ts,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,71 @@
+use anchor_lang::{accounts::signer::Signer, context::Context, prelude::*, Accounts, Key, Result};
+
+use crate::{
+    error::ErrorCode,
+    math::safe_math::SafeMath,
+    state::{
+        denylist::Denylist,
+        denylist_event::DenylistRemovedEvent,
+        merkle_distributor::{DistributorLoader, MerkleDistributor},
+    },
+};
+
+/// [merkle_distributor::remove_from_denylist] accounts.
+#[derive(Accounts)]
+pub struct RemoveFromDenylist<'info> {
+    /// The [MerkleDistributor].
+    #[account(
+        constraint = distributor.load_current().is_ok() @ ErrorCode::InvalidDistributorLayout,
+    )]
+    pub distributor: AccountLoader<'info, MerkleDistributor>,
+
+    /// Denylist PDA
+    #[account(mut, has_one = distributor)]
+    pub denylist: AccountLoader<'info, Denylist>,
+
+    /// Admin signer, receives the rent of the removed entry
+    #[account(mut, address = distributor.load_current()?.admin @ ErrorCode::Unauthorized)]
+    pub admin: Signer<'info>,
+}
+
+/// Removes a claimant from the denylist, allowing it to claim again.
+/// CHECK:
+///     1. The claimant is denylisted
+#[allow(clippy::result_large_err)]
+pub fn handle_remove_from_denylist(
+    ctx: Context<RemoveFromDenylist>,
+    claimant: Pubkey,
+) -> Result<()> {
+    let denylist_info = ctx.accounts.denylist.to_account_info();
+    let len = ctx.accounts.denylist.load()?.len;
+
+    {
+        let mut data = denylist_info.try_borrow_mut_data()?;
+        let entries = Denylist::entries_mut(&mut data, len)?;
+        let index = entries
+            .binary_search(&claimant)
+            .map_err(|_| ErrorCode::NotDenylisted)?;
+        entries.copy_within(index + 1.., index);
+    }
+
+    let new_len = len.safe_sub(1)?;
+    ctx.accounts.denylist.load_mut()?.len = new_len;
+
+    let new_space = Denylist::space(new_len)?;
+    denylist_info.realloc(new_space, false)?;
+
+    let refund = denylist_info
+        .lamports()
+        .saturating_sub(Rent::get()?.minimum_balance(new_space));
+    if refund > 0 {
+        **denylist_info.try_borrow_mut_lamports()? -= refund;
+        **ctx.accounts.admin.try_borrow_mut_lamports()? += refund;
+    }
+
+    emit!(DenylistRemovedEvent {
+        distributor: ctx.accounts.distributor.key(),
+        claimant,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,116 @@
+use anchor_lang::{accounts::signer::Signer, context::Context, prelude::*, Accounts, Key, Result};
+use anchor_spl::{
+    token,
+    token::{Token, TokenAccount},
+};
+
+use crate::{
+    error::ErrorCode,
+    math::safe_math::SafeMath,
+    state::{
+        claim_status::ClaimStatus,
+        merkle_distributor::{DistributorLoader, MerkleDistributor},
+        revoke_event::VestingRevokedEvent,
+    },
+};
+
+/// [merkle_distributor::revoke_vesting] accounts.
+#[derive(Accounts)]
+pub struct RevokeVesting<'info> {
+    /// The [MerkleDistributor].
+    #[account(
+        mut,
+        constraint = distributor.load_current().is_ok() @ ErrorCode::InvalidDistributorLayout,
+    )]
+    pub distributor: AccountLoader<'info, MerkleDistributor>,
+
+    /// Claim status PDA of the claimant
+    #[account(mut, has_one = distributor)]
+    pub claim_status: AccountLoader<'info, ClaimStatus>,
+
+    /// Distributor ATA containing the tokens to distribute.
+    #[account(
+        mut,
+        associated_token::mint = distributor.load_current()?.mint,
+        associated_token::authority = distributor.key(),
+        address = distributor.load_current()?.token_vault
+    )]
+    pub from: Account<'info, TokenAccount>,
+
+    /// The Clawback token account.
+    #[account(mut, address = distributor.load_current()?.clawback_receiver)]
+    pub clawback_receiver: Account<'info, TokenAccount>,
+
+    /// Admin signer
+    #[account(address = distributor.load_current()?.admin @ ErrorCode::Unauthorized)]
+    pub admin: Signer<'info>,
+
+    /// SPL [Token] program.
+    pub token_program: Program<'info, Token>,
+}
+
+/// Revokes the vesting of a claimant by:
+/// 1. Freezing claim_status.locked_amount at the amount vested so far
+/// 2. Transferring the unvested remainder to the clawback receiver
+/// 3. Reducing max_total_claim by the unvested remainder
+/// CHECK:
+///     1. The distributor has not been clawed back
+///     2. The vesting has not already been revoked
+///     3. The unvested remainder is greater than 0
+#[allow(clippy::result_large_err)]
+pub fn handle_revoke_vesting(ctx: Context<RevokeVesting>) -> Result<()> {
+    let mut distributor = ctx.accounts.distributor.load_current_mut()?;
+    let mut claim_status = ctx.accounts.claim_status.load_mut()?;
+
+    require!(!distributor.clawed_back(), ErrorCode::ClaimExpired);
+    require!(!claim_status.revoked(), ErrorCode::VestingAlreadyRevoked);
+
+    let curr_point = distributor.get_vesting_point()?;
+    let vested_amount =
+        claim_status.unlocked_amount(curr_point, distributor.start_ts, distributor.end_ts)?;
+    let revoked_amount = claim_status.locked_amount.safe_sub(vested_amount)?;
+
+    require!(revoked_amount > 0, ErrorCode::NothingToRevoke);
+
+    claim_status.locked_amount = vested_amount;
+    claim_status.revoked = 1;
+
+    distributor.max_total_claim = distributor.max_total_claim.safe_sub(revoked_amount)?;
+
+    // Note: might get truncated, do not rely on
+    msg!(
+        "revoked vesting of {} with vested {} and revoked {}",
+        claim_status.claimant,
+        vested_amount,
+        revoked_amount,
+    );
+
+    let claimant = claim_status.claimant;
+    drop(claim_status);
+
+    let signer = distributor.signer();
+    drop(distributor);
+    let seeds = signer.seeds();
+
+    token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            token::Transfer {
+                from: ctx.accounts.from.to_account_info(),
+                to: ctx.accounts.clawback_receiver.to_account_info(),
+                authority: ctx.accounts.distributor.to_account_info(),
+            },
+        )
+        .with_signer(&[&seeds[..]]),
+        revoked_amount,
+    )?;
+
+    emit!(VestingRevokedEvent {
+        distributor: ctx.accounts.distributor.key(),
+        claimant,
+        vested_amount,
+        revoked_amount,
+    });
+
+    Ok(())
+}
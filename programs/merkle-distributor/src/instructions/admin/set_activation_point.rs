@@ -1,4 +1,7 @@
-use crate::state::merkle_distributor::MerkleDistributor;
+use crate::{
+    error::ErrorCode,
+    state::merkle_distributor::{DistributorLoader, MerkleDistributor},
+};
 use anchor_lang::{context::Context, prelude::*, Accounts, Key, Result};
 /// Accounts for [merkle_distributor::set_activation_point].
 #[derive(Accounts)]
@@ -6,12 +9,12 @@ pub struct SetActivationPoint<'info> {
     /// [MerkleDistributor].
     #[account(
         mut,
-        has_one = admin,
+        constraint = distributor.load_current().is_ok() @ ErrorCode::InvalidDistributorLayout,
     )]
     pub distributor: AccountLoader<'info, MerkleDistributor>,
 
     /// Payer to create the distributor.
-    #[account(mut)]
+    #[account(mut, address = distributor.load_current()?.admin)]
     pub admin: Signer<'info>,
 }
 
@@ -21,7 +24,8 @@ pub fn handle_set_activation_point(
     ctx: Context<SetActivationPoint>,
     activation_point: u64,
 ) -> Result<()> {
-    let mut distributor = ctx.accounts.distributor.load_mut()?;
+    let mut distributor = ctx.accounts.distributor.load_current_mut()?;
+    distributor.require_no_timelock()?;
     distributor.activation_point = activation_point;
     Ok(())
 }
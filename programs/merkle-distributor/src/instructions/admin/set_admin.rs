@@ -1,15 +1,22 @@
 use anchor_lang::{accounts::signer::Signer, context::Context, prelude::*, Accounts, Result};
 
-use crate::{error::ErrorCode, state::merkle_distributor::MerkleDistributor};
+use crate::{
+    error::ErrorCode,
+    state::merkle_distributor::{DistributorLoader, MerkleDistributor},
+};
 
 /// [merkle_distributor::set_clawback_receiver] accounts.
 #[derive(Accounts)]
 pub struct SetAdmin<'info> {
     /// The [MerkleDistributor].
-    #[account(mut, has_one = admin @ ErrorCode::Unauthorized)]
+    #[account(
+        mut,
+        constraint = distributor.load_current().is_ok() @ ErrorCode::InvalidDistributorLayout,
+    )]
     pub distributor: AccountLoader<'info, MerkleDistributor>,
 
     /// Admin signer
+    #[account(address = distributor.load_current()?.admin @ ErrorCode::Unauthorized)]
     pub admin: Signer<'info>,
 
     /// New admin account
@@ -20,15 +27,18 @@ pub struct SetAdmin<'info> {
 /// Sets new admin account
 /// CHECK:
 ///     1. The new admin is not the same as the old one
+/// Pending actions queued by the previous admin can no longer be executed.
 #[allow(clippy::result_large_err)]
 pub fn handle_set_admin(ctx: Context<SetAdmin>) -> Result<()> {
+    let mut distributor = ctx.accounts.distributor.load_current_mut()?;
+    distributor.require_no_timelock()?;
+
     require!(
         ctx.accounts.admin.key != &ctx.accounts.new_admin.key(),
         ErrorCode::SameAdmin
     );
 
-    let mut distributor = ctx.accounts.distributor.load_mut()?;
-    distributor.admin = ctx.accounts.new_admin.key();
+    distributor.set_admin(ctx.accounts.new_admin.key());
     // Note: might get truncated, do not rely on
     msg!("set new admin to {}", ctx.accounts.new_admin.key());
 
@@ -0,0 +1,47 @@
+use crate::{
+    error::ErrorCode,
+    state::merkle_distributor::{DistributorLoader, MerkleDistributor},
+};
+use anchor_lang::{accounts::signer::Signer, context::Context, prelude::*, Accounts, Result};
+
+/// [merkle_distributor::set_claim_cap] accounts.
+#[derive(Accounts)]
+pub struct SetClaimCap<'info> {
+    /// The [MerkleDistributor].
+    #[account(
+        mut,
+        constraint = distributor.load_current().is_ok() @ ErrorCode::InvalidDistributorLayout,
+    )]
+    pub distributor: AccountLoader<'info, MerkleDistributor>,
+
+    /// Admin signer
+    #[account(address = distributor.load_current()?.admin @ ErrorCode::Unauthorized)]
+    pub admin: Signer<'info>,
+}
+
+/// Sets the cap on new claims or claimed amount per window
+/// CHECK:
+///     1. The claim cap type is valid
+///     2. The cap and window length are non-zero when the cap is enabled
+#[allow(clippy::result_large_err)]
+pub fn handle_set_claim_cap(
+    ctx: Context<SetClaimCap>,
+    claim_cap_type: u8,
+    claim_cap: u64,
+    claim_window_length: u32,
+) -> Result<()> {
+    let mut distributor = ctx.accounts.distributor.load_current_mut()?;
+    distributor.require_no_timelock()?;
+
+    distributor.set_claim_cap(claim_cap_type, claim_cap, claim_window_length)?;
+
+    // Note: might get truncated, do not rely on
+    msg!(
+        "set claim cap type {} cap {} window length {}",
+        claim_cap_type,
+        claim_cap,
+        claim_window_length
+    );
+
+    Ok(())
+}
@@ -6,20 +6,27 @@ use anchor_lang::{
 };
 use anchor_spl::token::TokenAccount;
 
-use crate::{error::ErrorCode, state::merkle_distributor::MerkleDistributor};
+use crate::{
+    error::ErrorCode,
+    state::merkle_distributor::{DistributorLoader, MerkleDistributor},
+};
 
 /// [merkle_distributor::set_clawback_receiver] accounts.
 #[derive(Accounts)]
 pub struct SetClawbackReceiver<'info> {
     /// The [MerkleDistributor].
-    #[account(mut, has_one = admin @ ErrorCode::Unauthorized)]
+    #[account(
+        mut,
+        constraint = distributor.load_current().is_ok() @ ErrorCode::InvalidDistributorLayout,
+    )]
     pub distributor: AccountLoader<'info, MerkleDistributor>,
 
     /// New clawback account
-    #[account(token::mint=distributor.load()?.mint)]
+    #[account(token::mint=distributor.load_current()?.mint)]
     pub new_clawback_account: Account<'info, TokenAccount>,
 
     /// Admin signer
+    #[account(address = distributor.load_current()?.admin @ ErrorCode::Unauthorized)]
     pub admin: Signer<'info>,
 }
 
@@ -28,7 +35,8 @@ pub struct SetClawbackReceiver<'info> {
 ///     1. The new clawback receiver is not the same as the old one
 #[allow(clippy::result_large_err)]
 pub fn handle_set_clawback_receiver(ctx: Context<SetClawbackReceiver>) -> Result<()> {
-    let mut distributor = ctx.accounts.distributor.load_mut()?;
+    let mut distributor = ctx.accounts.distributor.load_current_mut()?;
+    distributor.require_no_timelock()?;
 
     require!(
         distributor.clawback_receiver.key() != ctx.accounts.new_clawback_account.key(),
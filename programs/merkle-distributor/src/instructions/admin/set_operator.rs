@@ -1,6 +1,6 @@
 use crate::{
     error::ErrorCode,
-    state::merkle_distributor::{ClaimType, MerkleDistributor},
+    state::merkle_distributor::{ClaimType, DistributorLoader, MerkleDistributor},
 };
 use anchor_lang::{
     accounts::signer::Signer, context::Context, prelude::*, Accounts, Result, ToAccountInfo,
@@ -10,17 +10,22 @@ use anchor_lang::{
 #[derive(Accounts)]
 pub struct SetOperator<'info> {
     /// The [MerkleDistributor].
-    #[account(mut, has_one = admin @ ErrorCode::Unauthorized)]
+    #[account(
+        mut,
+        constraint = distributor.load_current().is_ok() @ ErrorCode::InvalidDistributorLayout,
+    )]
     pub distributor: AccountLoader<'info, MerkleDistributor>,
 
     /// Admin signer
+    #[account(address = distributor.load_current()?.admin @ ErrorCode::Unauthorized)]
     pub admin: Signer<'info>,
 }
 
 /// Sets new operator
 #[allow(clippy::result_large_err)]
 pub fn handle_set_operator(ctx: Context<SetOperator>, new_operator: Pubkey) -> Result<()> {
-    let mut distributor = ctx.accounts.distributor.load_mut()?;
+    let mut distributor = ctx.accounts.distributor.load_current_mut()?;
+    distributor.require_no_timelock()?;
 
     let claim_type =
         ClaimType::try_from(distributor.claim_type).map_err(|_| ErrorCode::TypeCastedError)?;
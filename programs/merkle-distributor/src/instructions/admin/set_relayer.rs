@@ -0,0 +1,38 @@
+use crate::{
+    error::ErrorCode,
+    state::merkle_distributor::{DistributorLoader, MerkleDistributor},
+};
+use anchor_lang::{
+    accounts::signer::Signer, context::Context, prelude::*, Accounts, Result, ToAccountInfo,
+};
+
+/// [merkle_distributor::set_relayer] accounts.
+#[derive(Accounts)]
+pub struct SetRelayer<'info> {
+    /// The [MerkleDistributor].
+    #[account(
+        mut,
+        constraint = distributor.load_current().is_ok() @ ErrorCode::InvalidDistributorLayout,
+    )]
+    pub distributor: AccountLoader<'info, MerkleDistributor>,
+
+    /// Admin signer
+    #[account(address = distributor.load_current()?.admin @ ErrorCode::Unauthorized)]
+    pub admin: Signer<'info>,
+}
+
+/// Sets the relayer allowed to pay the claim status rent on behalf of claimants.
+/// Claim statuses already paid by the previous relayer are still refunded to it.
+#[allow(clippy::result_large_err)]
+pub fn handle_set_relayer(ctx: Context<SetRelayer>, new_relayer: Pubkey) -> Result<()> {
+    let mut distributor = ctx.accounts.distributor.load_current_mut()?;
+    distributor.require_no_timelock()?;
+    require!(distributor.relayer != new_relayer, ErrorCode::SameRelayer);
+
+    distributor.relayer = new_relayer;
+
+    // Note: might get truncated, do not rely on
+    msg!("set relayer {}", new_relayer);
+
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use anchor_lang::{context::CpiContext, prelude::*, Key, Result, ToAccountInfo};
+use anchor_spl::token::{self, TokenAccount};
+
+use crate::error::ErrorCode;
+
+/// Moves the claimed bonus from the bonus vault to the token vault, so the claim is paid
+/// from the token vault as usual. Nothing to move when the bonus is held in the token vault.
+#[allow(clippy::result_large_err)]
+pub fn transfer_bonus<'info>(
+    bonus_vault: &Option<Account<'info, TokenAccount>>,
+    expected_bonus_vault: Pubkey,
+    token_vault: AccountInfo<'info>,
+    distributor: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+    bonus: u64,
+) -> Result<()> {
+    if expected_bonus_vault == Pubkey::default() || bonus == 0 {
+        return Ok(());
+    }
+    let bonus_vault = bonus_vault.as_ref().ok_or(ErrorCode::InvalidBonusVault)?;
+    require!(
+        bonus_vault.key() == expected_bonus_vault,
+        ErrorCode::InvalidBonusVault
+    );
+
+    token::transfer(
+        CpiContext::new(
+            token_program,
+            token::Transfer {
+                from: bonus_vault.to_account_info(),
+                to: token_vault,
+                authority: distributor,
+            },
+        )
+        .with_signer(signer_seeds),
+        bonus,
+    )
+}
@@ -9,9 +9,13 @@ use anchor_spl::token::{self, Token, TokenAccount};
 
 use crate::{
     error::ErrorCode,
+    instructions::{transfer_lamports_fee, transfer_token_fee},
+    math::safe_math::SafeMath,
     state::{
-        claim_status::ClaimStatus, claimed_event::ClaimedEvent,
-        merkle_distributor::MerkleDistributor,
+        claim_status::ClaimStatus,
+        claimed_event::ClaimedEvent,
+        denylist::Denylist,
+        merkle_distributor::{DistributorLoader, MerkleDistributor},
     },
 };
 
@@ -19,7 +23,10 @@ use crate::{
 #[derive(Accounts)]
 pub struct ClaimLocked<'info> {
     /// The [MerkleDistributor].
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = distributor.load_current().is_ok() @ ErrorCode::InvalidDistributorLayout,
+    )]
     pub distributor: AccountLoader<'info, MerkleDistributor>,
 
     /// Claim Status PDA
@@ -33,9 +40,9 @@ pub struct ClaimLocked<'info> {
     /// Distributor ATA containing the tokens to distribute.
     #[account(
         mut,
-        associated_token::mint = distributor.load()?.mint,
+        associated_token::mint = distributor.load_current()?.mint,
         associated_token::authority = distributor.key(),
-        address = distributor.load()?.token_vault,
+        address = distributor.load_current()?.token_vault,
     )]
     pub from: Account<'info, TokenAccount>,
 
@@ -43,7 +50,8 @@ pub struct ClaimLocked<'info> {
     #[account(mut)]
     pub to: Account<'info, TokenAccount>,
 
-    /// Who is claiming the tokens.
+    /// Who is claiming the tokens, pays the lamports claim fee.
+    #[account(mut)]
     pub claimant: Signer<'info>,
 
     /// operator
@@ -51,6 +59,17 @@ pub struct ClaimLocked<'info> {
 
     /// SPL [Token] program.
     pub token_program: Program<'info, Token>,
+
+    /// The [System] program, required when the claim fee is in lamports.
+    pub system_program: Option<Program<'info, System>>,
+
+    /// CHECK: fee receiver wallet for lamports fee, or its token account for token fee
+    #[account(mut)]
+    pub fee_receiver: Option<UncheckedAccount<'info>>,
+
+    /// Denylist of the distributor, required when the distributor has a denylist
+    #[account(has_one = distributor)]
+    pub denylist: Option<AccountLoader<'info, Denylist>>,
 }
 
 /// Claim locked tokens as they become unlocked.
@@ -59,23 +78,27 @@ pub struct ClaimLocked<'info> {
 ///     2. The withdraw-able amount is greater than 0
 ///     3. The locked amount withdrawn is ≤ than the locked amount
 ///     4. The distributor amount claimed is ≤ than the max total claim
+///     5. The claim fee, if any, is paid to the fee receiver
+///     6. The claim cap of the current window is not reached
+///     7. The claimant is not denylisted
 #[allow(clippy::result_large_err)]
 pub fn handle_claim_locked(ctx: Context<ClaimLocked>) -> Result<()> {
-    let mut distributor = ctx.accounts.distributor.load_mut()?;
+    let mut distributor = ctx.accounts.distributor.load_current_mut()?;
 
     let mut claim_status = ctx.accounts.claim_status.load_mut()?;
-    let curr_ts = Clock::get()?.unix_timestamp;
 
     require!(!distributor.clawed_back(), ErrorCode::ClaimExpired);
 
     // check operator
     distributor.authorize_claim(&ctx.accounts.operator)?;
+    distributor.authorize_claimant(&ctx.accounts.denylist, &ctx.accounts.claimant.key())?;
 
     let activation_handler = distributor.get_activation_handler()?;
     activation_handler.validate_claim()?;
 
+    let curr_point = distributor.get_vesting_point()?;
     let amount =
-        claim_status.amount_withdrawable(curr_ts, distributor.start_ts, distributor.end_ts)?;
+        claim_status.amount_withdrawable(curr_point, distributor.start_ts, distributor.end_ts)?;
 
     require!(amount > 0, ErrorCode::InsufficientUnlockedTokens);
 
@@ -99,18 +122,26 @@ pub fn handle_claim_locked(ctx: Context<ClaimLocked>) -> Result<()> {
         ErrorCode::ExceededMaxClaim
     );
 
-    let remaining_seconds = match curr_ts < distributor.end_ts {
-        true => distributor.end_ts - curr_ts,
-        false => 0,
-    };
+    distributor.consume_claim_cap(activation_handler.curr_point, false, amount)?;
+
+    let remaining_seconds = distributor.get_remaining_vesting_seconds(curr_point)?;
+
+    let token_fee = distributor.get_token_fee(amount)?;
+    let lamports_fee = distributor.get_lamports_fee()?;
+    let net_amount = amount.safe_sub(token_fee)?;
+    // only one of them is non-zero
+    let fee_amount = token_fee.safe_add(lamports_fee)?;
+    claim_status.fee_amount = claim_status.fee_amount.safe_add(fee_amount)?;
+    let fee_receiver = distributor.fee_receiver;
 
     let days = remaining_seconds / (24 * 60 * 60); // number of days
     let seconds_after_days = remaining_seconds % (24 * 60 * 60); // Remaining seconds after subtracting full days
 
     // Note: might get truncated, do not rely on
     msg!(
-        "Withdrew amount {} with {} days and {} seconds left in lockup",
+        "Withdrew amount {} with fee {} with {} days and {} seconds left in lockup",
         amount,
+        fee_amount,
         days,
         seconds_after_days,
     );
@@ -129,12 +160,39 @@ pub fn handle_claim_locked(ctx: Context<ClaimLocked>) -> Result<()> {
             },
         )
         .with_signer(&[&seeds[..]]),
-        amount,
+        net_amount,
     )?;
 
+    transfer_token_fee(
+        &ctx.accounts.fee_receiver,
+        fee_receiver,
+        ctx.accounts.from.to_account_info(),
+        ctx.accounts.distributor.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        &[&seeds[..]],
+        token_fee,
+    )?;
+
+    if lamports_fee > 0 {
+        let system_program = ctx
+            .accounts
+            .system_program
+            .as_ref()
+            .ok_or(ErrorCode::InvalidFeeReceiver)?;
+        transfer_lamports_fee(
+            &ctx.accounts.fee_receiver,
+            fee_receiver,
+            ctx.accounts.claimant.to_account_info(),
+            system_program.to_account_info(),
+            lamports_fee,
+        )?;
+    }
+
     emit!(ClaimedEvent {
         claimant: ctx.accounts.claimant.key(),
-        amount,
+        amount: net_amount,
+        gross_amount: amount,
+        fee_amount,
     });
     Ok(())
 }
@@ -0,0 +1,246 @@
+use anchor_lang::{
+    accounts::{account::Account, program::Program, signer::Signer},
+    context::{Context, CpiContext},
+    emit,
+    prelude::*,
+    Accounts, Result, ToAccountInfo,
+};
+use anchor_spl::token::{self, Token, TokenAccount};
+
+use crate::{
+    error::ErrorCode,
+    instructions::{transfer_lamports_fee, transfer_token_fee},
+    math::safe_math::SafeMath,
+    state::{
+        claim_record_tree::{get_record_leaf, ClaimRecordTree},
+        claim_status::ClaimStatus,
+        claimed_event::{ClaimRecordEvent, ClaimedEvent},
+        denylist::Denylist,
+        merkle_distributor::{DistributorLoader, MerkleDistributor},
+    },
+};
+
+/// [merkle_distributor::claim_locked_compressed] accounts.
+#[derive(Accounts)]
+pub struct ClaimLockedCompressed<'info> {
+    /// The [MerkleDistributor].
+    #[account(
+        mut,
+        constraint = distributor.load_current().is_ok() @ ErrorCode::InvalidDistributorLayout,
+    )]
+    pub distributor: AccountLoader<'info, MerkleDistributor>,
+
+    /// Claim record tree of the distributor
+    #[account(
+        mut,
+        has_one = distributor,
+        address = distributor.load_current()?.claim_record_tree @ ErrorCode::InvalidClaimRecordTree
+    )]
+    pub claim_record_tree: AccountLoader<'info, ClaimRecordTree>,
+
+    /// Distributor ATA containing the tokens to distribute.
+    #[account(
+        mut,
+        associated_token::mint = distributor.load_current()?.mint,
+        associated_token::authority = distributor.key(),
+        address = distributor.load_current()?.token_vault,
+    )]
+    pub from: Account<'info, TokenAccount>,
+
+    /// Account to send the claimed tokens to.
+    #[account(mut)]
+    pub to: Account<'info, TokenAccount>,
+
+    /// Who is claiming the tokens, pays the lamports claim fee.
+    #[account(mut)]
+    pub claimant: Signer<'info>,
+
+    /// operator
+    pub operator: Option<Signer<'info>>,
+
+    /// SPL [Token] program.
+    pub token_program: Program<'info, Token>,
+
+    /// The [System] program, required when the claim fee is in lamports.
+    pub system_program: Option<Program<'info, System>>,
+
+    /// CHECK: fee receiver wallet for lamports fee, or its token account for token fee
+    #[account(mut)]
+    pub fee_receiver: Option<UncheckedAccount<'info>>,
+
+    /// Denylist of the distributor, required when the distributor has a denylist
+    #[account(has_one = distributor)]
+    pub denylist: Option<AccountLoader<'info, Denylist>>,
+}
+
+/// Claim locked tokens of a compressed claim as they become unlocked, the record is passed
+/// with its proof and replaced by the updated one.
+/// Check:
+///     1. The claim window has not expired and the distributor has not been clawed back
+///     2. The record proof is valid
+///     3. The withdraw-able amount is greater than 0
+///     4. The locked amount withdrawn is ≤ than the locked amount
+///     5. The distributor amount claimed is ≤ than the max total claim
+///     6. The claim fee, if any, is paid to the fee receiver
+///     7. The claim cap of the current window is not reached
+///     8. The claimant is not denylisted
+#[allow(clippy::result_large_err)]
+pub fn handle_claim_locked_compressed(
+    ctx: Context<ClaimLockedCompressed>,
+    amount_unlocked: u64,
+    amount_locked: u64,
+    locked_amount_withdrawn: u64,
+    bonus_amount: u64,
+    root: [u8; 32],
+    index: u32,
+    proof: Vec<[u8; 32]>,
+) -> Result<()> {
+    let mut distributor = ctx.accounts.distributor.load_current_mut()?;
+
+    require!(!distributor.clawed_back(), ErrorCode::ClaimExpired);
+
+    // check operator
+    distributor.authorize_claim(&ctx.accounts.operator)?;
+    distributor.authorize_claimant(&ctx.accounts.denylist, &ctx.accounts.claimant.key())?;
+
+    let activation_handler = distributor.get_activation_handler()?;
+    activation_handler.validate_claim()?;
+
+    // the record is never stored, reuse the claim status math
+    let mut record = ClaimStatus {
+        distributor: ctx.accounts.distributor.key(),
+        claimant: ctx.accounts.claimant.key(),
+        locked_amount: amount_locked,
+        locked_amount_withdrawn,
+        unlocked_amount: amount_unlocked,
+        bonus_amount,
+        ..Default::default()
+    };
+    let previous_leaf = get_record_leaf(&record);
+
+    let curr_point = distributor.get_vesting_point()?;
+    let amount =
+        record.amount_withdrawable(curr_point, distributor.start_ts, distributor.end_ts)?;
+
+    require!(amount > 0, ErrorCode::InsufficientUnlockedTokens);
+
+    record.locked_amount_withdrawn = record
+        .locked_amount_withdrawn
+        .checked_add(amount)
+        .ok_or(ErrorCode::ArithmeticError)?;
+
+    require!(
+        record.locked_amount_withdrawn <= record.locked_amount,
+        ErrorCode::ExceededMaxClaim
+    );
+
+    let claim_record_tree_info = ctx.accounts.claim_record_tree.to_account_info();
+    let mut claim_record_tree_data = claim_record_tree_info.try_borrow_mut_data()?;
+    let (claim_record_tree, canopy) =
+        ClaimRecordTree::load_with_canopy_mut(&mut claim_record_tree_data)?;
+    claim_record_tree.replace_leaf(
+        canopy,
+        root,
+        previous_leaf,
+        get_record_leaf(&record),
+        &proof,
+        index,
+    )?;
+    let sequence_number = claim_record_tree.sequence_number;
+    drop(claim_record_tree_data);
+
+    distributor.total_amount_claimed = distributor
+        .total_amount_claimed
+        .checked_add(amount)
+        .ok_or(ErrorCode::ArithmeticError)?;
+
+    require!(
+        distributor.total_amount_claimed <= distributor.max_total_claim,
+        ErrorCode::ExceededMaxClaim
+    );
+
+    distributor.consume_claim_cap(activation_handler.curr_point, false, amount)?;
+
+    let remaining_seconds = distributor.get_remaining_vesting_seconds(curr_point)?;
+
+    let token_fee = distributor.get_token_fee(amount)?;
+    let lamports_fee = distributor.get_lamports_fee()?;
+    let net_amount = amount.safe_sub(token_fee)?;
+    // only one of them is non-zero
+    let fee_amount = token_fee.safe_add(lamports_fee)?;
+    let fee_receiver = distributor.fee_receiver;
+
+    let days = remaining_seconds / (24 * 60 * 60); // number of days
+    let seconds_after_days = remaining_seconds % (24 * 60 * 60); // Remaining seconds after subtracting full days
+
+    // Note: might get truncated, do not rely on
+    msg!(
+        "Withdrew amount {} with fee {} at index {} with {} days and {} seconds left in lockup",
+        amount,
+        fee_amount,
+        index,
+        days,
+        seconds_after_days,
+    );
+
+    let signer = distributor.signer();
+    drop(distributor);
+    let seeds = signer.seeds();
+
+    token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            token::Transfer {
+                from: ctx.accounts.from.to_account_info(),
+                to: ctx.accounts.to.to_account_info(),
+                authority: ctx.accounts.distributor.to_account_info(),
+            },
+        )
+        .with_signer(&[&seeds[..]]),
+        net_amount,
+    )?;
+
+    transfer_token_fee(
+        &ctx.accounts.fee_receiver,
+        fee_receiver,
+        ctx.accounts.from.to_account_info(),
+        ctx.accounts.distributor.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        &[&seeds[..]],
+        token_fee,
+    )?;
+
+    if lamports_fee > 0 {
+        let system_program = ctx
+            .accounts
+            .system_program
+            .as_ref()
+            .ok_or(ErrorCode::InvalidFeeReceiver)?;
+        transfer_lamports_fee(
+            &ctx.accounts.fee_receiver,
+            fee_receiver,
+            ctx.accounts.claimant.to_account_info(),
+            system_program.to_account_info(),
+            lamports_fee,
+        )?;
+    }
+
+    emit!(ClaimRecordEvent {
+        claim_record_tree: ctx.accounts.claim_record_tree.key(),
+        sequence_number,
+        index,
+        claimant: ctx.accounts.claimant.key(),
+        unlocked_amount: record.unlocked_amount,
+        locked_amount: record.locked_amount,
+        locked_amount_withdrawn: record.locked_amount_withdrawn,
+        bonus_amount: record.bonus_amount,
+    });
+
+    emit!(ClaimedEvent {
+        claimant: ctx.accounts.claimant.key(),
+        amount: net_amount,
+        gross_amount: amount,
+        fee_amount,
+    });
+    Ok(())
+}
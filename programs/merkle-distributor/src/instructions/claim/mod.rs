@@ -1,4 +1,10 @@
 pub use claim_locked::*;
 pub use new_claim::*;
 pub mod claim_locked;
+pub mod claim_locked_compressed;
 pub mod new_claim;
+pub mod new_claim_compressed;
+pub use claim_locked_compressed::*;
+pub use new_claim_compressed::*;
+pub mod new_claim_batch;
+pub use new_claim_batch::*;
@@ -1,31 +1,29 @@
-use anchor_lang::{
-    context::Context, prelude::*, solana_program::hash::hashv, system_program::System, Accounts,
-    Key, Result,
-};
+use anchor_lang::{context::Context, prelude::*, system_program::System, Accounts, Key, Result};
 use anchor_spl::{
     token,
     token::{Token, TokenAccount},
 };
-use jito_merkle_verify::verify;
+use jito_merkle_verify::verify_with_hash_type;
 
 use crate::{
     error::ErrorCode,
+    instructions::{transfer_bonus, transfer_lamports_fee, transfer_token_fee},
     state::{
-        claim_status::ClaimStatus, claimed_event::NewClaimEvent,
-        merkle_distributor::MerkleDistributor,
+        claim_status::ClaimStatus,
+        claimed_event::NewClaimEvent,
+        denylist::Denylist,
+        merkle_distributor::{DistributorLoader, MerkleDistributor, NewClaimAmounts},
     },
 };
 
-// We need to discern between leaf and intermediate nodes to prevent trivial second
-// pre-image attacks.
-// https://flawed.net.nz/2018/02/21/attacking-merkle-trees-with-a-second-preimage-attack
-pub const LEAF_PREFIX: &[u8] = &[0];
-
 /// [merkle_distributor::new_claim] accounts.
 #[derive(Accounts)]
 pub struct NewClaim<'info> {
     /// The [MerkleDistributor].
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = distributor.load_current().is_ok() @ ErrorCode::InvalidDistributorLayout,
+    )]
     pub distributor: AccountLoader<'info, MerkleDistributor>,
 
     /// Claim status PDA
@@ -38,16 +36,16 @@ pub struct NewClaim<'info> {
         ],
         bump,
         space = 8 + ClaimStatus::INIT_SPACE,
-        payer = claimant,
+        payer = payer,
     )]
     pub claim_status: AccountLoader<'info, ClaimStatus>,
 
     /// Distributor ATA containing the tokens to distribute.
     #[account(
         mut,
-        associated_token::mint = distributor.load()?.mint,
+        associated_token::mint = distributor.load_current()?.mint,
         associated_token::authority = distributor.key(),
-        address = distributor.load()?.token_vault
+        address = distributor.load_current()?.token_vault
     )]
     pub from: Account<'info, TokenAccount>,
 
@@ -55,7 +53,7 @@ pub struct NewClaim<'info> {
     #[account(mut)]
     pub to: Account<'info, TokenAccount>,
 
-    /// Who is claiming the tokens.
+    /// Who is claiming the tokens, pays the lamports claim fee.
     #[account(mut)]
     pub claimant: Signer<'info>,
 
@@ -67,18 +65,42 @@ pub struct NewClaim<'info> {
 
     /// The [System] program.
     pub system_program: Program<'info, System>,
+
+    /// CHECK: fee receiver wallet for lamports fee, or its token account for token fee
+    #[account(mut)]
+    pub fee_receiver: Option<UncheckedAccount<'info>>,
+
+    /// Denylist of the distributor, required when the distributor has a denylist
+    #[account(has_one = distributor)]
+    pub denylist: Option<AccountLoader<'info, Denylist>>,
+
+    /// Bonus vault, required when the distributor has a bonus vault
+    #[account(mut, address = distributor.load_current()?.bonus_vault @ ErrorCode::InvalidBonusVault)]
+    pub bonus_vault: Option<Account<'info, TokenAccount>>,
+
+    /// Pays the claim status rent, the claimant or the relayer of the distributor
+    #[account(mut)]
+    pub payer: Signer<'info>,
 }
 
 /// Initializes a new claim from the [MerkleDistributor].
 /// 1. Increments num_nodes_claimed by 1
 /// 2. Initializes claim_status
-/// 3. Transfers claim_status.unlocked_amount to the claimant
-/// 4. Increments total_amount_claimed by claim_status.unlocked_amount
+/// 3. Withdraws the locked amount already vested, if any
+/// 4. Transfers claim_status.unlocked_amount and the vested locked amount to the claimant
+/// 5. Increments total_amount_claimed by the transferred amount
+/// 6. Charges the claim fee, if any, to the fee receiver
+/// 7. Moves the bonus from the bonus vault to the token vault, if the distributor has one
+/// 8. Records who paid the claim status rent, refunded to it when the claim status is closed
 /// CHECK:
 ///     1. The claim window has not expired and the distributor has not been clawed back
 ///     2. The claimant is the owner of the to account
 ///     3. Num nodes claimed is less than max_num_nodes
 ///     4. The merkle proof is valid
+///     5. The claim cap of the current window is not reached
+///     6. The claimant is not denylisted
+///     7. The distributor is not compressed
+///     8. The payer is the claimant or the relayer of the distributor
 #[allow(clippy::result_large_err)]
 pub fn handle_new_claim(
     ctx: Context<NewClaim>,
@@ -86,12 +108,21 @@ pub fn handle_new_claim(
     amount_locked: u64,
     proof: Vec<[u8; 32]>,
 ) -> Result<()> {
-    let mut distributor = ctx.accounts.distributor.load_mut()?;
+    let mut distributor = ctx.accounts.distributor.load_current_mut()?;
 
     require!(!distributor.clawed_back(), ErrorCode::ClaimExpired);
+    require!(
+        !distributor.is_compressed(),
+        ErrorCode::CompressedClaimRequired
+    );
 
     // check operator
     distributor.authorize_claim(&ctx.accounts.operator)?;
+    distributor.authorize_claimant(&ctx.accounts.denylist, &ctx.accounts.claimant.key())?;
+    require!(
+        distributor.is_valid_payer(&ctx.accounts.payer.key(), &ctx.accounts.claimant.key()),
+        ErrorCode::InvalidRelayer
+    );
 
     let activation_handler = distributor.get_activation_handler()?;
     activation_handler.validate_claim()?;
@@ -109,16 +140,15 @@ pub fn handle_new_claim(
     let claimant_account = &ctx.accounts.claimant;
 
     // Verify the merkle proof.
-    let node = hashv(&[
+    let hash_type = distributor.get_hash_type()?;
+    let node = hash_type.claim_leaf(
         &claimant_account.key().to_bytes(),
-        &amount_unlocked.to_le_bytes(),
-        &amount_locked.to_le_bytes(),
-    ]);
-
-    let node = hashv(&[LEAF_PREFIX, &node.to_bytes()]);
+        amount_unlocked,
+        amount_locked,
+    );
 
     require!(
-        verify(proof, distributor.root, node.to_bytes()),
+        verify_with_hash_type(proof, distributor.root, node, hash_type),
         ErrorCode::InvalidProof
     );
 
@@ -130,32 +160,30 @@ pub fn handle_new_claim(
     claim_status.locked_amount = amount_locked;
     claim_status.locked_amount_withdrawn = 0;
     claim_status.closable = distributor.closable;
-    claim_status.admin = distributor.admin;
+    claim_status.rent_payer = ctx.accounts.payer.key();
+    claim_status.rent_payer_stored = 1;
 
     claim_status.unlocked_amount = amount_unlocked;
-    claim_status.bonus_amount =
-        distributor.get_bonus_for_a_claimaint(amount_unlocked, &activation_handler)?;
-
-    let amount_with_bonus = claim_status.get_total_unlocked_amount()?;
 
-    distributor.total_amount_claimed = distributor
-        .total_amount_claimed
-        .checked_add(amount_with_bonus)
-        .ok_or(ErrorCode::ArithmeticError)?;
-
-    distributor.accumulate_bonus(claim_status.bonus_amount)?;
-
-    require!(
-        distributor.total_amount_claimed <= distributor.max_total_claim,
-        ErrorCode::ExceededMaxClaim
-    );
+    let NewClaimAmounts {
+        claim_amount,
+        net_amount,
+        bonus_amount,
+        token_fee,
+        lamports_fee,
+    } = distributor.record_new_claim(&mut claim_status, &activation_handler)?;
+    let fee_amount = claim_status.fee_amount;
+    let fee_receiver = distributor.fee_receiver;
+    let bonus_vault = distributor.bonus_vault;
 
     // Note: might get truncated, do not rely on
     msg!(
-        "Created new claim with locked {}, unlocked {} and bonus {} with lockup start:{} end:{}, activation_point {} current_point {}",
+        "Created new claim with locked {}, unlocked {} and bonus {}, vested locked {}, fee {} with lockup start:{} end:{}, activation_point {} current_point {}",
         claim_status.locked_amount,
         claim_status.unlocked_amount,
         claim_status.bonus_amount,
+        claim_status.locked_amount_withdrawn,
+        claim_status.fee_amount,
         distributor.start_ts,
         distributor.end_ts,
         activation_handler.activation_point,
@@ -166,6 +194,16 @@ pub fn handle_new_claim(
     drop(distributor);
     let seeds = signer.seeds();
 
+    transfer_bonus(
+        &ctx.accounts.bonus_vault,
+        bonus_vault,
+        ctx.accounts.from.to_account_info(),
+        ctx.accounts.distributor.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        &[&seeds[..]],
+        bonus_amount,
+    )?;
+
     token::transfer(
         CpiContext::new(
             ctx.accounts.token_program.to_account_info(),
@@ -176,12 +214,33 @@ pub fn handle_new_claim(
             },
         )
         .with_signer(&[&seeds[..]]),
-        amount_with_bonus,
+        net_amount,
+    )?;
+
+    transfer_token_fee(
+        &ctx.accounts.fee_receiver,
+        fee_receiver,
+        ctx.accounts.from.to_account_info(),
+        ctx.accounts.distributor.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        &[&seeds[..]],
+        token_fee,
+    )?;
+
+    transfer_lamports_fee(
+        &ctx.accounts.fee_receiver,
+        fee_receiver,
+        ctx.accounts.claimant.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        lamports_fee,
     )?;
 
     emit!(NewClaimEvent {
         claimant: claimant_account.key(),
-        timestamp: Clock::get()?.unix_timestamp
+        timestamp: Clock::get()?.unix_timestamp,
+        amount: net_amount,
+        gross_amount: claim_amount,
+        fee_amount,
     });
 
     Ok(())
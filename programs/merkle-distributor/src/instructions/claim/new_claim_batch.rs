@@ -0,0 +1,356 @@
+use anchor_lang::{
+    context::Context, prelude::*, solana_program::program_option::COption, system_program,
+    system_program::System, Accounts, AccountsExit, Key, Result,
+};
+use anchor_spl::{
+    token,
+    token::{Token, TokenAccount},
+};
+use jito_merkle_verify::verify_multiproof_with_hash_type;
+
+use crate::{
+    error::ErrorCode,
+    instructions::{transfer_bonus, transfer_lamports_fee, transfer_token_fee},
+    math::safe_math::SafeMath,
+    state::{
+        claim_status::ClaimStatus,
+        claimed_event::NewClaimEvent,
+        denylist::Denylist,
+        merkle_distributor::{DistributorLoader, MerkleDistributor, NewClaimAmounts},
+    },
+};
+
+/// Amounts of a leaf claimed with [merkle_distributor::new_claim_batch]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct BatchClaimLeaf {
+    pub amount_unlocked: u64,
+    pub amount_locked: u64,
+    /// Canonical bump of the claim status PDA of the leaf
+    pub claim_status_bump: u8,
+}
+
+/// [merkle_distributor::new_claim_batch] accounts.
+/// The remaining accounts are, for each leaf in the order of the tree, its claim status PDA and
+/// the token account to send the claimed tokens to.
+/// The claimant of a leaf is the wallet owning its token account, as with the other claims: the
+/// leaf, its claim status and its events are keyed by the wallet, which claims its locked amounts
+/// later on. The signer must be the owner or the delegate of each token account.
+#[derive(Accounts)]
+pub struct NewClaimBatch<'info> {
+    /// The [MerkleDistributor].
+    #[account(
+        mut,
+        constraint = distributor.load_current().is_ok() @ ErrorCode::InvalidDistributorLayout,
+    )]
+    pub distributor: AccountLoader<'info, MerkleDistributor>,
+
+    /// Distributor ATA containing the tokens to distribute.
+    #[account(
+        mut,
+        associated_token::mint = distributor.load_current()?.mint,
+        associated_token::authority = distributor.key(),
+        address = distributor.load_current()?.token_vault
+    )]
+    pub from: Account<'info, TokenAccount>,
+
+    /// Owner or delegate of the token accounts of all the leaves, pays the lamports claim fees.
+    #[account(mut)]
+    pub claimant: Signer<'info>,
+
+    /// Pays the claim status rents, the claimant or the relayer of the distributor
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// operator
+    pub operator: Option<Signer<'info>>,
+
+    /// SPL [Token] program.
+    pub token_program: Program<'info, Token>,
+
+    /// The [System] program.
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: fee receiver wallet for lamports fee, or its token account for token fee
+    #[account(mut)]
+    pub fee_receiver: Option<UncheckedAccount<'info>>,
+
+    /// Denylist of the distributor, required when the distributor has a denylist
+    #[account(has_one = distributor)]
+    pub denylist: Option<AccountLoader<'info, Denylist>>,
+
+    /// Bonus vault, required when the distributor has a bonus vault
+    #[account(mut, address = distributor.load_current()?.bonus_vault @ ErrorCode::InvalidBonusVault)]
+    pub bonus_vault: Option<Account<'info, TokenAccount>>,
+}
+
+/// Initializes the claims of several leaves of the [MerkleDistributor] at once, with a single
+/// multiproof. Each leaf is claimed as with [merkle_distributor::new_claim]:
+/// 1. Increments num_nodes_claimed by the number of leaves
+/// 2. Initializes the claim_status of each leaf, seeded by the owner of its token account
+/// 3. Withdraws the locked amounts already vested, if any
+/// 4. Transfers the unlocked and vested locked amounts of each leaf to its token account
+/// 5. Increments total_amount_claimed by the transferred amounts
+/// 6. Charges the claim fee of each leaf, if any, to the fee receiver
+/// 7. Moves the bonuses from the bonus vault to the token vault, if the distributor has one
+/// 8. Records who paid the claim status rents, refunded to it when the claim statuses are closed
+/// CHECK:
+///     1. The claim window has not expired and the distributor has not been clawed back
+///     2. There is a claim status PDA of the owner of each token account, with the canonical bump
+///     3. The signer is the owner or the delegate of the token accounts of all the leaves
+///     4. Num nodes claimed is less than max_num_nodes
+///     5. The merkle multiproof of the leaves is valid
+///     6. The claim cap of the current window is not reached
+///     7. Neither the signer nor the leaves are denylisted
+///     8. The distributor is not compressed
+///     9. The payer is the claimant or the relayer of the distributor
+#[allow(clippy::result_large_err)]
+pub fn handle_new_claim_batch<'info>(
+    ctx: Context<'_, '_, '_, 'info, NewClaimBatch<'info>>,
+    leaves: Vec<BatchClaimLeaf>,
+    proof: Vec<[u8; 32]>,
+    proof_flags: Vec<bool>,
+) -> Result<()> {
+    require!(
+        !leaves.is_empty() && ctx.remaining_accounts.len() == 2 * leaves.len(),
+        ErrorCode::InvalidBatchClaimAccounts
+    );
+
+    let mut distributor = ctx.accounts.distributor.load_current_mut()?;
+
+    require!(!distributor.clawed_back(), ErrorCode::ClaimExpired);
+    require!(
+        !distributor.is_compressed(),
+        ErrorCode::CompressedClaimRequired
+    );
+
+    // check operator
+    distributor.authorize_claim(&ctx.accounts.operator)?;
+    let claimant = ctx.accounts.claimant.key();
+    distributor.authorize_claimant(&ctx.accounts.denylist, &claimant)?;
+    require!(
+        distributor.is_valid_payer(&ctx.accounts.payer.key(), &claimant),
+        ErrorCode::InvalidRelayer
+    );
+
+    let activation_handler = distributor.get_activation_handler()?;
+    activation_handler.validate_claim()?;
+
+    // the claimant of each leaf is the owner of its token account, which the signer owns or is
+    // the delegate of
+    let distributor_key = ctx.accounts.distributor.key();
+    let mut destinations = Vec::with_capacity(leaves.len());
+    for (leaf, accounts) in leaves.iter().zip(ctx.remaining_accounts.chunks(2)) {
+        let to = Account::<TokenAccount>::try_from(&accounts[1])?;
+        require!(
+            to.owner == claimant || to.delegate == COption::Some(claimant),
+            ErrorCode::OwnerMismatch
+        );
+        distributor.authorize_claimant(&ctx.accounts.denylist, &to.owner)?;
+
+        verify_claim_status_bump(
+            &accounts[0].key(),
+            &to.owner,
+            &distributor_key,
+            leaf.claim_status_bump,
+        )?;
+
+        destinations.push(to);
+    }
+
+    distributor.num_nodes_claimed = distributor
+        .num_nodes_claimed
+        .checked_add(leaves.len() as u64)
+        .ok_or(ErrorCode::ArithmeticError)?;
+
+    require!(
+        distributor.num_nodes_claimed <= distributor.max_num_nodes,
+        ErrorCode::MaxNodesExceeded
+    );
+
+    // Verify the merkle multiproof of all the leaves.
+    let hash_type = distributor.get_hash_type()?;
+    let nodes = leaves
+        .iter()
+        .zip(destinations.iter())
+        .map(|(leaf, to)| {
+            hash_type.claim_leaf(
+                &to.owner.to_bytes(),
+                leaf.amount_unlocked,
+                leaf.amount_locked,
+            )
+        })
+        .collect();
+
+    require!(
+        verify_multiproof_with_hash_type(proof, proof_flags, distributor.root, nodes, hash_type),
+        ErrorCode::InvalidProof
+    );
+
+    let space = 8 + ClaimStatus::INIT_SPACE;
+    let rent_lamports = Rent::get()?.minimum_balance(space);
+
+    // net amount, gross amount and fee of each leaf
+    let mut claims = Vec::with_capacity(leaves.len());
+    let mut total_bonus_amount = 0u64;
+    let mut total_token_fee = 0u64;
+    let mut total_lamports_fee = 0u64;
+    for (i, (leaf, to)) in leaves.iter().zip(destinations.iter()).enumerate() {
+        let leaf_claimant = to.owner;
+        let claim_status_info = &ctx.remaining_accounts[2 * i];
+
+        // fails if the leaf is already claimed
+        system_program::create_account(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::CreateAccount {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: claim_status_info.clone(),
+                },
+            )
+            .with_signer(&[&[
+                b"ClaimStatus".as_ref(),
+                leaf_claimant.to_bytes().as_ref(),
+                distributor_key.to_bytes().as_ref(),
+                &[leaf.claim_status_bump],
+            ]]),
+            rent_lamports,
+            space as u64,
+            &crate::ID,
+        )?;
+
+        let claim_status_loader =
+            AccountLoader::<ClaimStatus>::try_from_unchecked(&crate::ID, claim_status_info)?;
+        let mut claim_status = claim_status_loader.load_init()?;
+
+        // Seed initial values
+        claim_status.distributor = distributor_key;
+        claim_status.claimant = leaf_claimant;
+        claim_status.locked_amount = leaf.amount_locked;
+        claim_status.locked_amount_withdrawn = 0;
+        claim_status.closable = distributor.closable;
+        claim_status.rent_payer = ctx.accounts.payer.key();
+        claim_status.rent_payer_stored = 1;
+
+        claim_status.unlocked_amount = leaf.amount_unlocked;
+
+        let NewClaimAmounts {
+            claim_amount,
+            net_amount,
+            bonus_amount,
+            token_fee,
+            lamports_fee,
+        } = distributor.record_new_claim(&mut claim_status, &activation_handler)?;
+
+        total_bonus_amount = total_bonus_amount.safe_add(bonus_amount)?;
+        total_token_fee = total_token_fee.safe_add(token_fee)?;
+        total_lamports_fee = total_lamports_fee.safe_add(lamports_fee)?;
+        claims.push((net_amount, claim_amount, claim_status.fee_amount));
+
+        drop(claim_status);
+        // writes the discriminator
+        claim_status_loader.exit(&crate::ID)?;
+    }
+
+    let fee_receiver = distributor.fee_receiver;
+    let bonus_vault = distributor.bonus_vault;
+
+    // Note: might get truncated, do not rely on
+    msg!(
+        "Created {} new claims with bonus {}, fee {}, activation_point {} current_point {}",
+        leaves.len(),
+        total_bonus_amount,
+        total_token_fee.safe_add(total_lamports_fee)?,
+        activation_handler.activation_point,
+        activation_handler.curr_point,
+    );
+
+    let signer = distributor.signer();
+    drop(distributor);
+    let seeds = signer.seeds();
+
+    transfer_bonus(
+        &ctx.accounts.bonus_vault,
+        bonus_vault,
+        ctx.accounts.from.to_account_info(),
+        ctx.accounts.distributor.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        &[&seeds[..]],
+        total_bonus_amount,
+    )?;
+
+    let timestamp = Clock::get()?.unix_timestamp;
+    for (to, (net_amount, claim_amount, fee_amount)) in destinations.iter().zip(claims) {
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.from.to_account_info(),
+                    to: to.to_account_info(),
+                    authority: ctx.accounts.distributor.to_account_info(),
+                },
+            )
+            .with_signer(&[&seeds[..]]),
+            net_amount,
+        )?;
+
+        emit!(NewClaimEvent {
+            claimant: to.owner,
+            timestamp,
+            amount: net_amount,
+            gross_amount: claim_amount,
+            fee_amount,
+        });
+    }
+
+    transfer_token_fee(
+        &ctx.accounts.fee_receiver,
+        fee_receiver,
+        ctx.accounts.from.to_account_info(),
+        ctx.accounts.distributor.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        &[&seeds[..]],
+        total_token_fee,
+    )?;
+
+    transfer_lamports_fee(
+        &ctx.accounts.fee_receiver,
+        fee_receiver,
+        ctx.accounts.claimant.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        total_lamports_fee,
+    )?;
+
+    Ok(())
+}
+
+/// Checks the claim status of a leaf is derived with the given bump, as the `bump = ...`
+/// constraint of Anchor does, and that the bump is the canonical one [merkle_distributor::new_claim]
+/// derives: no higher bump derives a PDA, otherwise the leaf could be claimed a second time. The
+/// canonical bump is usually the highest one, checked with a single derivation.
+fn verify_claim_status_bump(
+    claim_status: &Pubkey,
+    claimant: &Pubkey,
+    distributor: &Pubkey,
+    bump: u8,
+) -> Result<()> {
+    let derive = |bump: u8| {
+        Pubkey::create_program_address(
+            &[
+                b"ClaimStatus".as_ref(),
+                claimant.to_bytes().as_ref(),
+                distributor.to_bytes().as_ref(),
+                &[bump],
+            ],
+            &crate::ID,
+        )
+    };
+    require!(
+        derive(bump).ok().as_ref() == Some(claim_status),
+        ErrorCode::InvalidClaimStatus
+    );
+    require!(
+        (bump..u8::MAX).all(|lower| derive(lower + 1).is_err()),
+        ErrorCode::InvalidClaimStatus
+    );
+    Ok(())
+}
@@ -0,0 +1,260 @@
+use anchor_lang::{context::Context, prelude::*, system_program::System, Accounts, Key, Result};
+use anchor_spl::{
+    token,
+    token::{Token, TokenAccount},
+};
+use jito_merkle_verify::verify_with_path;
+
+use crate::{
+    error::ErrorCode,
+    instructions::{transfer_bonus, transfer_lamports_fee, transfer_token_fee},
+    state::{
+        claim_record_tree::{get_record_leaf, ClaimRecordTree, EMPTY_RECORD_LEAF},
+        claim_status::ClaimStatus,
+        claimed_event::{ClaimRecordEvent, NewClaimEvent},
+        denylist::Denylist,
+        merkle_distributor::{DistributorLoader, MerkleDistributor, NewClaimAmounts},
+    },
+};
+
+/// [merkle_distributor::new_claim_compressed] accounts.
+#[derive(Accounts)]
+pub struct NewClaimCompressed<'info> {
+    /// The [MerkleDistributor].
+    #[account(
+        mut,
+        constraint = distributor.load_current().is_ok() @ ErrorCode::InvalidDistributorLayout,
+    )]
+    pub distributor: AccountLoader<'info, MerkleDistributor>,
+
+    /// Claim record tree of the distributor
+    #[account(
+        mut,
+        has_one = distributor,
+        address = distributor.load_current()?.claim_record_tree @ ErrorCode::InvalidClaimRecordTree
+    )]
+    pub claim_record_tree: AccountLoader<'info, ClaimRecordTree>,
+
+    /// Distributor ATA containing the tokens to distribute.
+    #[account(
+        mut,
+        associated_token::mint = distributor.load_current()?.mint,
+        associated_token::authority = distributor.key(),
+        address = distributor.load_current()?.token_vault
+    )]
+    pub from: Account<'info, TokenAccount>,
+
+    /// Account to send the claimed tokens to.
+    #[account(mut)]
+    pub to: Account<'info, TokenAccount>,
+
+    /// Who is claiming the tokens, pays the lamports claim fee.
+    #[account(mut)]
+    pub claimant: Signer<'info>,
+
+    /// operator
+    pub operator: Option<Signer<'info>>,
+
+    /// SPL [Token] program.
+    pub token_program: Program<'info, Token>,
+
+    /// The [System] program, required when the claim fee is in lamports.
+    pub system_program: Option<Program<'info, System>>,
+
+    /// CHECK: fee receiver wallet for lamports fee, or its token account for token fee
+    #[account(mut)]
+    pub fee_receiver: Option<UncheckedAccount<'info>>,
+
+    /// Denylist of the distributor, required when the distributor has a denylist
+    #[account(has_one = distributor)]
+    pub denylist: Option<AccountLoader<'info, Denylist>>,
+
+    /// Bonus vault, required when the distributor has a bonus vault
+    #[account(mut, address = distributor.load_current()?.bonus_vault @ ErrorCode::InvalidBonusVault)]
+    pub bonus_vault: Option<Account<'info, TokenAccount>>,
+}
+
+/// Initializes a new claim from a compressed [MerkleDistributor], same as new_claim except that
+/// the claim is recorded as a leaf of the claim record tree instead of a claim status.
+/// 1. Increments num_nodes_claimed by 1
+/// 2. Writes the claim record at the record index of the claimant, the path of its airdrop leaf
+/// 3. Withdraws the locked amount already vested, if any
+/// 4. Transfers the unlocked amount and the vested locked amount to the claimant
+/// 5. Increments total_amount_claimed by the transferred amount
+/// 6. Charges the claim fee, if any, to the fee receiver
+/// 7. Moves the bonus from the bonus vault to the token vault, if the distributor has one
+/// CHECK:
+///     1. The claim window has not expired and the distributor has not been clawed back
+///     2. The claimant is the owner of the to account
+///     3. Num nodes claimed is less than max_num_nodes
+///     4. The merkle proof of the airdrop leaf against the distributor root is valid
+///     5. The record proof of the empty leaf at the record index is valid, so the airdrop leaf
+///        has not been claimed yet
+///     6. The claim cap of the current window is not reached
+///     7. The claimant is not denylisted
+#[allow(clippy::result_large_err)]
+pub fn handle_new_claim_compressed(
+    ctx: Context<NewClaimCompressed>,
+    amount_unlocked: u64,
+    amount_locked: u64,
+    root: [u8; 32],
+    proof: Vec<[u8; 32]>,
+    airdrop_proof: Vec<[u8; 32]>,
+) -> Result<()> {
+    let mut distributor = ctx.accounts.distributor.load_current_mut()?;
+
+    require!(!distributor.clawed_back(), ErrorCode::ClaimExpired);
+
+    // check operator
+    distributor.authorize_claim(&ctx.accounts.operator)?;
+    distributor.authorize_claimant(&ctx.accounts.denylist, &ctx.accounts.claimant.key())?;
+
+    let activation_handler = distributor.get_activation_handler()?;
+    activation_handler.validate_claim()?;
+
+    distributor.num_nodes_claimed = distributor
+        .num_nodes_claimed
+        .checked_add(1)
+        .ok_or(ErrorCode::ArithmeticError)?;
+
+    require!(
+        distributor.num_nodes_claimed <= distributor.max_num_nodes,
+        ErrorCode::MaxNodesExceeded
+    );
+
+    let claimant_account = &ctx.accounts.claimant;
+
+    let hash_type = distributor.get_hash_type()?;
+    let node = hash_type.claim_leaf(
+        &claimant_account.key().to_bytes(),
+        amount_unlocked,
+        amount_locked,
+    );
+
+    // each airdrop leaf has a single path, hence a single record index
+    let index = verify_with_path(airdrop_proof, distributor.root, node, hash_type)
+        .and_then(|path| u32::try_from(path).ok())
+        .ok_or(ErrorCode::InvalidProof)?;
+
+    // the record is never stored, reuse the claim status math
+    let mut record = ClaimStatus {
+        distributor: ctx.accounts.distributor.key(),
+        claimant: claimant_account.key(),
+        locked_amount: amount_locked,
+        unlocked_amount: amount_unlocked,
+        ..Default::default()
+    };
+    let NewClaimAmounts {
+        claim_amount,
+        net_amount,
+        bonus_amount,
+        token_fee,
+        lamports_fee,
+    } = distributor.record_new_claim(&mut record, &activation_handler)?;
+    let fee_amount = record.fee_amount;
+    let fee_receiver = distributor.fee_receiver;
+
+    let claim_record_tree_info = ctx.accounts.claim_record_tree.to_account_info();
+    let mut claim_record_tree_data = claim_record_tree_info.try_borrow_mut_data()?;
+    let (claim_record_tree, canopy) =
+        ClaimRecordTree::load_with_canopy_mut(&mut claim_record_tree_data)?;
+    claim_record_tree.replace_leaf(
+        canopy,
+        root,
+        EMPTY_RECORD_LEAF,
+        get_record_leaf(&record),
+        &proof,
+        index,
+    )?;
+    let sequence_number = claim_record_tree.sequence_number;
+    drop(claim_record_tree_data);
+    let bonus_vault = distributor.bonus_vault;
+
+    // Note: might get truncated, do not rely on
+    msg!(
+        "Created new compressed claim at index {} with locked {}, unlocked {} and bonus {}, vested locked {}, fee {} with lockup start:{} end:{}, activation_point {} current_point {}",
+        index,
+        record.locked_amount,
+        record.unlocked_amount,
+        record.bonus_amount,
+        record.locked_amount_withdrawn,
+        fee_amount,
+        distributor.start_ts,
+        distributor.end_ts,
+        activation_handler.activation_point,
+        activation_handler.curr_point,
+    );
+
+    let signer = distributor.signer();
+    drop(distributor);
+    let seeds = signer.seeds();
+
+    transfer_bonus(
+        &ctx.accounts.bonus_vault,
+        bonus_vault,
+        ctx.accounts.from.to_account_info(),
+        ctx.accounts.distributor.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        &[&seeds[..]],
+        bonus_amount,
+    )?;
+
+    token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            token::Transfer {
+                from: ctx.accounts.from.to_account_info(),
+                to: ctx.accounts.to.to_account_info(),
+                authority: ctx.accounts.distributor.to_account_info(),
+            },
+        )
+        .with_signer(&[&seeds[..]]),
+        net_amount,
+    )?;
+
+    transfer_token_fee(
+        &ctx.accounts.fee_receiver,
+        fee_receiver,
+        ctx.accounts.from.to_account_info(),
+        ctx.accounts.distributor.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        &[&seeds[..]],
+        token_fee,
+    )?;
+
+    if lamports_fee > 0 {
+        let system_program = ctx
+            .accounts
+            .system_program
+            .as_ref()
+            .ok_or(ErrorCode::InvalidFeeReceiver)?;
+        transfer_lamports_fee(
+            &ctx.accounts.fee_receiver,
+            fee_receiver,
+            claimant_account.to_account_info(),
+            system_program.to_account_info(),
+            lamports_fee,
+        )?;
+    }
+
+    emit!(ClaimRecordEvent {
+        claim_record_tree: ctx.accounts.claim_record_tree.key(),
+        sequence_number,
+        index,
+        claimant: claimant_account.key(),
+        unlocked_amount: record.unlocked_amount,
+        locked_amount: record.locked_amount,
+        locked_amount_withdrawn: record.locked_amount_withdrawn,
+        bonus_amount: record.bonus_amount,
+    });
+
+    emit!(NewClaimEvent {
+        claimant: claimant_account.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+        amount: net_amount,
+        gross_amount: claim_amount,
+        fee_amount,
+    });
+
+    Ok(())
+}
@@ -9,9 +9,13 @@ use anchor_spl::token::{Token, TokenAccount};
 
 use crate::{
     error::ErrorCode,
+    instructions::{transfer_lamports_fee, transfer_token_fee},
+    math::safe_math::SafeMath,
     state::{
-        claim_status::ClaimStatus, claimed_event::ClaimedEvent,
-        merkle_distributor::MerkleDistributor,
+        claim_status::ClaimStatus,
+        claimed_event::ClaimedEvent,
+        denylist::Denylist,
+        merkle_distributor::{DistributorLoader, MerkleDistributor},
     },
 };
 use locked_voter::{self as voter, Escrow};
@@ -21,7 +25,10 @@ use locked_voter::{program::LockedVoter as Voter, Locker};
 #[derive(Accounts)]
 pub struct ClaimLockedAndStake<'info> {
     /// The [MerkleDistributor].
-    #[account(mut, has_one = locker)]
+    #[account(
+        mut,
+        constraint = distributor.load_current().is_ok() @ ErrorCode::InvalidDistributorLayout,
+    )]
     pub distributor: AccountLoader<'info, MerkleDistributor>,
 
     /// Claim Status PDA
@@ -35,13 +42,14 @@ pub struct ClaimLockedAndStake<'info> {
     /// Distributor ATA containing the tokens to distribute.
     #[account(
         mut,
-        associated_token::mint = distributor.load()?.mint,
+        associated_token::mint = distributor.load_current()?.mint,
         associated_token::authority = distributor.key(),
-        address = distributor.load()?.token_vault,
+        address = distributor.load_current()?.token_vault,
     )]
     pub from: Account<'info, TokenAccount>,
 
-    /// Who is claiming the tokens.
+    /// Who is claiming the tokens, pays the lamports claim fee.
+    #[account(mut)]
     pub claimant: Signer<'info>,
 
     /// operator
@@ -54,7 +62,7 @@ pub struct ClaimLockedAndStake<'info> {
     pub voter_program: Program<'info, Voter>,
 
     /// CHECK: Locker
-    #[account(mut)]
+    #[account(mut, address = distributor.load_current()?.locker)]
     pub locker: Box<Account<'info, Locker>>,
 
     /// CHECK: escrow
@@ -64,6 +72,17 @@ pub struct ClaimLockedAndStake<'info> {
     /// CHECK: escrow_tokens
     #[account(mut)]
     pub escrow_tokens: UncheckedAccount<'info>,
+
+    /// The [System] program, required when the claim fee is in lamports.
+    pub system_program: Option<Program<'info, System>>,
+
+    /// CHECK: fee receiver wallet for lamports fee, or its token account for token fee
+    #[account(mut)]
+    pub fee_receiver: Option<UncheckedAccount<'info>>,
+
+    /// Denylist of the distributor, required when the distributor has a denylist
+    #[account(has_one = distributor)]
+    pub denylist: Option<AccountLoader<'info, Denylist>>,
 }
 
 /// Claim locked tokens as they become unlocked.
@@ -72,29 +91,44 @@ pub struct ClaimLockedAndStake<'info> {
 ///     2. The withdraw-able amount is greater than 0
 ///     3. The locked amount withdrawn is ≤ than the locked amount
 ///     4. The distributor amount claimed is ≤ than the max total claim
+///     5. The escrow is locked for at least min_lock_duration, otherwise the lock is extended
+///     6. The claim fee, if any, is paid to the fee receiver
+///     7. The claim cap of the current window is not reached
+///     8. The claimant is not denylisted
 #[allow(clippy::result_large_err)]
 pub fn handle_claim_locked_and_stake(ctx: Context<ClaimLockedAndStake>) -> Result<()> {
-    let mut distributor = ctx.accounts.distributor.load_mut()?;
+    let mut distributor = ctx.accounts.distributor.load_current_mut()?;
 
     require!(!distributor.clawed_back(), ErrorCode::ClaimExpired);
 
     // check operator
     distributor.authorize_claim_and_stake(&ctx.accounts.operator)?;
+    distributor.authorize_claimant(&ctx.accounts.denylist, &ctx.accounts.claimant.key())?;
 
     let mut claim_status = ctx.accounts.claim_status.load_mut()?;
 
     let curr_ts = Clock::get()?.unix_timestamp;
 
     let escrow = &ctx.accounts.escrow;
-    require!(escrow.is_max_lock, ErrorCode::EscrowIsNotMaxLock);
+    let lock_duration_to_extend = distributor.get_lock_duration_to_extend(
+        escrow.is_max_lock,
+        escrow.escrow_ends_at,
+        curr_ts,
+    )?;
+    // escrow owner must sign to extend the lock
+    require!(
+        lock_duration_to_extend == 0 || escrow.owner == ctx.accounts.claimant.key(),
+        ErrorCode::InvalidEscrowOwner
+    );
 
     require!(!distributor.clawed_back(), ErrorCode::ClaimExpired);
 
     let activation_handler = distributor.get_activation_handler()?;
     activation_handler.validate_claim()?;
 
+    let curr_point = distributor.get_vesting_point()?;
     let amount =
-        claim_status.amount_withdrawable(curr_ts, distributor.start_ts, distributor.end_ts)?;
+        claim_status.amount_withdrawable(curr_point, distributor.start_ts, distributor.end_ts)?;
 
     require!(amount > 0, ErrorCode::InsufficientUnlockedTokens);
 
@@ -118,18 +152,26 @@ pub fn handle_claim_locked_and_stake(ctx: Context<ClaimLockedAndStake>) -> Resul
         ErrorCode::ExceededMaxClaim
     );
 
-    let remaining_seconds = match curr_ts < distributor.end_ts {
-        true => distributor.end_ts - curr_ts,
-        false => 0,
-    };
+    distributor.consume_claim_cap(activation_handler.curr_point, false, amount)?;
+
+    let remaining_seconds = distributor.get_remaining_vesting_seconds(curr_point)?;
+
+    let token_fee = distributor.get_token_fee(amount)?;
+    let lamports_fee = distributor.get_lamports_fee()?;
+    let net_amount = amount.safe_sub(token_fee)?;
+    // only one of them is non-zero
+    let fee_amount = token_fee.safe_add(lamports_fee)?;
+    claim_status.fee_amount = claim_status.fee_amount.safe_add(fee_amount)?;
+    let fee_receiver = distributor.fee_receiver;
 
     let days = remaining_seconds / (24 * 60 * 60); // number of days
     let seconds_after_days = remaining_seconds % (24 * 60 * 60); // Remaining seconds after subtracting full days
 
     // Note: might get truncated, do not rely on
     msg!(
-        "Withdrew amount {} with {} days and {} seconds left in lockup",
+        "Withdrew amount {} with fee {} with {} days and {} seconds left in lockup",
         amount,
+        fee_amount,
         days,
         seconds_after_days,
     );
@@ -140,6 +182,18 @@ pub fn handle_claim_locked_and_stake(ctx: Context<ClaimLockedAndStake>) -> Resul
 
     let seeds = &[&seeds[..]];
 
+    if lock_duration_to_extend > 0 {
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.voter_program.to_account_info(),
+            voter::cpi::accounts::ExtendLockDuration {
+                locker: ctx.accounts.locker.to_account_info(),
+                escrow: ctx.accounts.escrow.to_account_info(),
+                escrow_owner: ctx.accounts.claimant.to_account_info(),
+            },
+        );
+        voter::cpi::extend_lock_duration(cpi_ctx, lock_duration_to_extend)?;
+    }
+
     // CPI to voter
     let cpi_ctx = CpiContext::new(
         ctx.accounts.voter_program.to_account_info(),
@@ -154,11 +208,38 @@ pub fn handle_claim_locked_and_stake(ctx: Context<ClaimLockedAndStake>) -> Resul
     )
     .with_signer(seeds);
 
-    voter::cpi::increase_locked_amount(cpi_ctx, amount)?;
+    voter::cpi::increase_locked_amount(cpi_ctx, net_amount)?;
+
+    transfer_token_fee(
+        &ctx.accounts.fee_receiver,
+        fee_receiver,
+        ctx.accounts.from.to_account_info(),
+        ctx.accounts.distributor.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        seeds,
+        token_fee,
+    )?;
+
+    if lamports_fee > 0 {
+        let system_program = ctx
+            .accounts
+            .system_program
+            .as_ref()
+            .ok_or(ErrorCode::InvalidFeeReceiver)?;
+        transfer_lamports_fee(
+            &ctx.accounts.fee_receiver,
+            fee_receiver,
+            ctx.accounts.claimant.to_account_info(),
+            system_program.to_account_info(),
+            lamports_fee,
+        )?;
+    }
 
     emit!(ClaimedEvent {
         claimant: ctx.accounts.claimant.key(),
-        amount,
+        amount: net_amount,
+        gross_amount: amount,
+        fee_amount,
     });
     Ok(())
 }
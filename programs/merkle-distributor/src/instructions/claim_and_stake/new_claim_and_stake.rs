@@ -1,17 +1,16 @@
-use crate::LEAF_PREFIX;
 use crate::{
     error::ErrorCode,
+    instructions::{transfer_bonus, transfer_lamports_fee, transfer_token_fee},
     state::{
-        claim_status::ClaimStatus, claimed_event::NewClaimEvent,
-        merkle_distributor::MerkleDistributor,
+        claim_status::ClaimStatus,
+        claimed_event::NewClaimEvent,
+        denylist::Denylist,
+        merkle_distributor::{DistributorLoader, MerkleDistributor, NewClaimAmounts},
     },
 };
-use anchor_lang::{
-    context::Context, prelude::*, solana_program::hash::hashv, system_program::System, Accounts,
-    Key, Result,
-};
+use anchor_lang::{context::Context, prelude::*, system_program::System, Accounts, Key, Result};
 use anchor_spl::token::{Token, TokenAccount};
-use jito_merkle_verify::verify;
+use jito_merkle_verify::verify_with_hash_type;
 
 use locked_voter::program::LockedVoter as Voter;
 use locked_voter::{self as voter, Escrow, Locker};
@@ -20,7 +19,10 @@ use locked_voter::{self as voter, Escrow, Locker};
 #[derive(Accounts)]
 pub struct NewClaimAndStake<'info> {
     /// The [MerkleDistributor].
-    #[account(mut, has_one = locker)]
+    #[account(
+        mut,
+        constraint = distributor.load_current().is_ok() @ ErrorCode::InvalidDistributorLayout,
+    )]
     pub distributor: AccountLoader<'info, MerkleDistributor>,
 
     /// Claim status PDA
@@ -33,20 +35,20 @@ pub struct NewClaimAndStake<'info> {
         ],
         bump,
         space = 8 + ClaimStatus::INIT_SPACE,
-        payer = claimant
+        payer = payer
     )]
     pub claim_status: AccountLoader<'info, ClaimStatus>,
 
     /// Distributor ATA containing the tokens to distribute.
     #[account(
         mut,
-        associated_token::mint = distributor.load()?.mint,
+        associated_token::mint = distributor.load_current()?.mint,
         associated_token::authority = distributor.key(),
-        address = distributor.load()?.token_vault
+        address = distributor.load_current()?.token_vault
     )]
     pub from: Account<'info, TokenAccount>,
 
-    /// Who is claiming the tokens.
+    /// Who is claiming the tokens, pays the lamports claim fee.
     #[account(mut)]
     pub claimant: Signer<'info>,
 
@@ -63,28 +65,54 @@ pub struct NewClaimAndStake<'info> {
     pub voter_program: Program<'info, Voter>,
 
     /// CHECK: Locker
-    #[account(mut)]
+    #[account(mut, address = distributor.load_current()?.locker)]
     pub locker: Box<Account<'info, Locker>>,
 
-    /// CHECK: escrow
+    /// CHECK: escrow, created with max lock if it does not exist
     #[account(mut)]
-    pub escrow: Box<Account<'info, Escrow>>,
+    pub escrow: UncheckedAccount<'info>,
 
-    /// CHECK: escrow_tokens
+    /// CHECK: escrow_tokens, should be created previously (can be in the same transaction)
     #[account(mut)]
     pub escrow_tokens: UncheckedAccount<'info>,
+
+    /// CHECK: fee receiver wallet for lamports fee, or its token account for token fee
+    #[account(mut)]
+    pub fee_receiver: Option<UncheckedAccount<'info>>,
+
+    /// Denylist of the distributor, required when the distributor has a denylist
+    #[account(has_one = distributor)]
+    pub denylist: Option<AccountLoader<'info, Denylist>>,
+
+    /// Bonus vault, required when the distributor has a bonus vault
+    #[account(mut, address = distributor.load_current()?.bonus_vault @ ErrorCode::InvalidBonusVault)]
+    pub bonus_vault: Option<Account<'info, TokenAccount>>,
+
+    /// Pays the claim status rent, the claimant or the relayer of the distributor
+    #[account(mut)]
+    pub payer: Signer<'info>,
 }
 
 /// Initializes a new claim from the [MerkleDistributor].
 /// 1. Increments num_nodes_claimed by 1
 /// 2. Initializes claim_status
-/// 3. Transfers claim_status.unlocked_amount to the claimant
-/// 4. Increments total_amount_claimed by claim_status.unlocked_amount
+/// 3. Withdraws the locked amount already vested, if any
+/// 4. Transfers claim_status.unlocked_amount and the vested locked amount to the escrow
+/// 5. Increments total_amount_claimed by the transferred amount
+/// 6. Creates the claimant escrow with max lock if it does not exist
+/// 7. Extends the escrow lock if it is shorter than min_lock_duration
+/// 8. Charges the claim fee, if any, to the fee receiver
+/// 9. Moves the bonus from the bonus vault to the token vault, if the distributor has one
+/// 10. Records who paid the claim status rent, refunded to it when the claim status is closed
 /// CHECK:
 ///     1. The claim window has not expired and the distributor has not been clawed back
 ///     2. The claimant is the owner of the to account
 ///     3. Num nodes claimed is less than max_num_nodes
 ///     4. The merkle proof is valid
+///     5. The claim cap of the current window is not reached
+///     6. The claimant is not denylisted
+///     7. The distributor is not compressed
+///     8. The payer is the claimant or the relayer of the distributor
 #[allow(clippy::result_large_err)]
 pub fn handle_new_claim_and_stake(
     ctx: Context<NewClaimAndStake>,
@@ -92,15 +120,40 @@ pub fn handle_new_claim_and_stake(
     amount_locked: u64,
     proof: Vec<[u8; 32]>,
 ) -> Result<()> {
-    let mut distributor = ctx.accounts.distributor.load_mut()?;
+    let mut distributor = ctx.accounts.distributor.load_current_mut()?;
 
     require!(!distributor.clawed_back(), ErrorCode::ClaimExpired);
+    require!(
+        !distributor.is_compressed(),
+        ErrorCode::CompressedClaimRequired
+    );
 
     // check operator
     distributor.authorize_claim_and_stake(&ctx.accounts.operator)?;
+    distributor.authorize_claimant(&ctx.accounts.denylist, &ctx.accounts.claimant.key())?;
+    require!(
+        distributor.is_valid_payer(&ctx.accounts.payer.key(), &ctx.accounts.claimant.key()),
+        ErrorCode::InvalidRelayer
+    );
 
-    let escrow = &ctx.accounts.escrow;
-    require!(escrow.is_max_lock, ErrorCode::EscrowIsNotMaxLock);
+    // a new escrow is set to max lock, so it never needs to be extended
+    let should_create_escrow = ctx.accounts.escrow.data_is_empty();
+    let lock_duration_to_extend = if should_create_escrow {
+        0
+    } else {
+        let escrow = Account::<Escrow>::try_from(&ctx.accounts.escrow)?;
+        let lock_duration_to_extend = distributor.get_lock_duration_to_extend(
+            escrow.is_max_lock,
+            escrow.escrow_ends_at,
+            Clock::get()?.unix_timestamp,
+        )?;
+        // escrow owner must sign to extend the lock
+        require!(
+            lock_duration_to_extend == 0 || escrow.owner == ctx.accounts.claimant.key(),
+            ErrorCode::InvalidEscrowOwner
+        );
+        lock_duration_to_extend
+    };
 
     let activation_handler = distributor.get_activation_handler()?;
     activation_handler.validate_claim()?;
@@ -118,16 +171,15 @@ pub fn handle_new_claim_and_stake(
     let claimant_account = &ctx.accounts.claimant;
 
     // Verify the merkle proof.
-    let node = hashv(&[
+    let hash_type = distributor.get_hash_type()?;
+    let node = hash_type.claim_leaf(
         &claimant_account.key().to_bytes(),
-        &amount_unlocked.to_le_bytes(),
-        &amount_locked.to_le_bytes(),
-    ]);
-
-    let node = hashv(&[LEAF_PREFIX, &node.to_bytes()]);
+        amount_unlocked,
+        amount_locked,
+    );
 
     require!(
-        verify(proof, distributor.root, node.to_bytes()),
+        verify_with_hash_type(proof, distributor.root, node, hash_type),
         ErrorCode::InvalidProof
     );
 
@@ -140,31 +192,28 @@ pub fn handle_new_claim_and_stake(
     claim_status.unlocked_amount = amount_unlocked;
     claim_status.locked_amount_withdrawn = 0;
     claim_status.closable = distributor.closable;
-    claim_status.admin = distributor.admin;
-
-    claim_status.bonus_amount =
-        distributor.get_bonus_for_a_claimaint(amount_unlocked, &activation_handler)?;
-
-    let amount_with_bonus = claim_status.get_total_unlocked_amount()?;
-
-    distributor.total_amount_claimed = distributor
-        .total_amount_claimed
-        .checked_add(amount_with_bonus)
-        .ok_or(ErrorCode::ArithmeticError)?;
-
-    distributor.accumulate_bonus(claim_status.bonus_amount)?;
-
-    require!(
-        distributor.total_amount_claimed <= distributor.max_total_claim,
-        ErrorCode::ExceededMaxClaim
-    );
+    claim_status.rent_payer = ctx.accounts.payer.key();
+    claim_status.rent_payer_stored = 1;
+
+    let NewClaimAmounts {
+        claim_amount,
+        net_amount,
+        bonus_amount,
+        token_fee,
+        lamports_fee,
+    } = distributor.record_new_claim(&mut claim_status, &activation_handler)?;
+    let fee_amount = claim_status.fee_amount;
+    let fee_receiver = distributor.fee_receiver;
+    let bonus_vault = distributor.bonus_vault;
 
     // Note: might get truncated, do not rely on
     msg!(
-        "Created new claim with locked {}, unlocked {} and bonus {} with lockup start:{} end:{}",
+        "Created new claim with locked {}, unlocked {} and bonus {}, vested locked {}, fee {} with lockup start:{} end:{}",
         claim_status.locked_amount,
         claim_status.unlocked_amount,
         claim_status.bonus_amount,
+        claim_status.locked_amount_withdrawn,
+        claim_status.fee_amount,
         distributor.start_ts,
         distributor.end_ts,
     );
@@ -175,6 +224,53 @@ pub fn handle_new_claim_and_stake(
 
     let seeds = &[&seeds[..]];
 
+    transfer_bonus(
+        &ctx.accounts.bonus_vault,
+        bonus_vault,
+        ctx.accounts.from.to_account_info(),
+        ctx.accounts.distributor.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        seeds,
+        bonus_amount,
+    )?;
+
+    if should_create_escrow {
+        // CPI to voter, payer pays for the escrow
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.voter_program.to_account_info(),
+            voter::cpi::accounts::NewEscrow {
+                locker: ctx.accounts.locker.to_account_info(),
+                escrow: ctx.accounts.escrow.to_account_info(),
+                escrow_owner: ctx.accounts.claimant.to_account_info(),
+                payer: ctx.accounts.payer.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+            },
+        );
+        voter::cpi::new_escrow(cpi_ctx)?;
+
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.voter_program.to_account_info(),
+            voter::cpi::accounts::ToggleMaxLock {
+                locker: ctx.accounts.locker.to_account_info(),
+                escrow: ctx.accounts.escrow.to_account_info(),
+                escrow_owner: ctx.accounts.claimant.to_account_info(),
+            },
+        );
+        voter::cpi::toggle_max_lock(cpi_ctx, true)?;
+    }
+
+    if lock_duration_to_extend > 0 {
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.voter_program.to_account_info(),
+            voter::cpi::accounts::ExtendLockDuration {
+                locker: ctx.accounts.locker.to_account_info(),
+                escrow: ctx.accounts.escrow.to_account_info(),
+                escrow_owner: ctx.accounts.claimant.to_account_info(),
+            },
+        );
+        voter::cpi::extend_lock_duration(cpi_ctx, lock_duration_to_extend)?;
+    }
+
     // CPI to voter
     let cpi_ctx = CpiContext::new(
         ctx.accounts.voter_program.to_account_info(),
@@ -188,11 +284,32 @@ pub fn handle_new_claim_and_stake(
         },
     )
     .with_signer(seeds);
-    voter::cpi::increase_locked_amount(cpi_ctx, amount_with_bonus)?;
+    voter::cpi::increase_locked_amount(cpi_ctx, net_amount)?;
+
+    transfer_token_fee(
+        &ctx.accounts.fee_receiver,
+        fee_receiver,
+        ctx.accounts.from.to_account_info(),
+        ctx.accounts.distributor.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        seeds,
+        token_fee,
+    )?;
+
+    transfer_lamports_fee(
+        &ctx.accounts.fee_receiver,
+        fee_receiver,
+        ctx.accounts.claimant.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        lamports_fee,
+    )?;
 
     emit!(NewClaimEvent {
         claimant: claimant_account.key(),
-        timestamp: Clock::get()?.unix_timestamp
+        timestamp: Clock::get()?.unix_timestamp,
+        amount: net_amount,
+        gross_amount: claim_amount,
+        fee_amount,
     });
 
     Ok(())
@@ -0,0 +1,74 @@
+use anchor_lang::{
+    context::CpiContext,
+    prelude::*,
+    system_program::{self, Transfer},
+    Key, Result, ToAccountInfo,
+};
+use anchor_spl::token::{self, TokenAccount};
+
+use crate::error::ErrorCode;
+
+/// Transfers the lamports claim fee from the claimant to the fee receiver wallet
+#[allow(clippy::result_large_err)]
+pub fn transfer_lamports_fee<'info>(
+    fee_receiver: &Option<UncheckedAccount<'info>>,
+    expected_fee_receiver: Pubkey,
+    claimant: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    fee: u64,
+) -> Result<()> {
+    if fee == 0 {
+        return Ok(());
+    }
+    let fee_receiver = fee_receiver.as_ref().ok_or(ErrorCode::InvalidFeeReceiver)?;
+    require!(
+        fee_receiver.key() == expected_fee_receiver,
+        ErrorCode::InvalidFeeReceiver
+    );
+
+    system_program::transfer(
+        CpiContext::new(
+            system_program,
+            Transfer {
+                from: claimant,
+                to: fee_receiver.to_account_info(),
+            },
+        ),
+        fee,
+    )
+}
+
+/// Transfers the token claim fee from the vault to the token account of the fee receiver
+#[allow(clippy::result_large_err)]
+pub fn transfer_token_fee<'info>(
+    fee_receiver: &Option<UncheckedAccount<'info>>,
+    expected_fee_receiver: Pubkey,
+    from: AccountInfo<'info>,
+    distributor: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+    fee: u64,
+) -> Result<()> {
+    if fee == 0 {
+        return Ok(());
+    }
+    let fee_receiver = fee_receiver.as_ref().ok_or(ErrorCode::InvalidFeeReceiver)?;
+    let fee_receiver_token = Account::<TokenAccount>::try_from(fee_receiver)?;
+    require!(
+        fee_receiver_token.owner == expected_fee_receiver,
+        ErrorCode::InvalidFeeReceiver
+    );
+
+    token::transfer(
+        CpiContext::new(
+            token_program,
+            token::Transfer {
+                from,
+                to: fee_receiver.to_account_info(),
+                authority: distributor,
+            },
+        )
+        .with_signer(signer_seeds),
+        fee,
+    )
+}
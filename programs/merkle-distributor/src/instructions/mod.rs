@@ -4,3 +4,9 @@ pub mod claim;
 pub use claim::*;
 pub mod claim_and_stake;
 pub use claim_and_stake::*;
+pub mod claim_fee;
+pub use claim_fee::*;
+pub mod bonus_vault;
+pub use bonus_vault::*;
+pub mod registry;
+pub use registry::*;
@@ -0,0 +1,90 @@
+use anchor_lang::{context::CpiContext, prelude::*, system_program, Discriminator, Result};
+
+use crate::{
+    error::ErrorCode,
+    math::safe_math::SafeMath,
+    state::registry::{Registry, RegistryEntry},
+};
+
+/// Appends a distributor to the registry of its base and mint, creating the registry
+/// with the first distributor. The payer covers the rent of the new entry.
+#[allow(clippy::result_large_err)]
+pub fn append_to_registry<'info>(
+    registry: AccountInfo<'info>,
+    payer: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    registry_seeds: &[&[&[u8]]],
+    base: Pubkey,
+    mint: Pubkey,
+    entry: RegistryEntry,
+) -> Result<()> {
+    let is_new = registry.data_is_empty();
+    let len = if is_new {
+        0
+    } else {
+        let registry_loader = AccountLoader::<Registry>::try_from(&registry)?;
+        let registry_state = registry_loader.load()?;
+        require!(
+            registry_state.base == base && registry_state.mint == mint,
+            ErrorCode::InvalidRegistry
+        );
+        registry_state.len
+    };
+
+    let new_len = len.safe_add(1)?;
+    let new_space = Registry::space(new_len)?;
+    let rent_due = Rent::get()?
+        .minimum_balance(new_space)
+        .saturating_sub(registry.lamports());
+    if rent_due > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                system_program::Transfer {
+                    from: payer,
+                    to: registry.clone(),
+                },
+            ),
+            rent_due,
+        )?;
+    }
+
+    if is_new {
+        system_program::allocate(
+            CpiContext::new(
+                system_program.clone(),
+                system_program::Allocate {
+                    account_to_allocate: registry.clone(),
+                },
+            )
+            .with_signer(registry_seeds),
+            new_space as u64,
+        )?;
+        system_program::assign(
+            CpiContext::new(
+                system_program,
+                system_program::Assign {
+                    account_to_assign: registry.clone(),
+                },
+            )
+            .with_signer(registry_seeds),
+            &crate::ID,
+        )?;
+        registry.try_borrow_mut_data()?[..8].copy_from_slice(&Registry::DISCRIMINATOR);
+    } else {
+        registry.realloc(new_space, false)?;
+    }
+
+    {
+        let mut data = registry.try_borrow_mut_data()?;
+        Registry::entries_mut(&mut data, new_len)?[len as usize] = entry;
+    }
+
+    let registry_loader = AccountLoader::<Registry>::try_from(&registry)?;
+    let mut registry_state = registry_loader.load_mut()?;
+    registry_state.base = base;
+    registry_state.mint = mint;
+    registry_state.len = new_len;
+
+    Ok(())
+}
@@ -84,6 +84,94 @@ pub mod merkle_distributor {
         handle_set_operator(ctx, new_operator)
     }
 
+    #[allow(clippy::result_large_err)]
+    pub fn set_relayer(ctx: Context<SetRelayer>, new_relayer: Pubkey) -> Result<()> {
+        handle_set_relayer(ctx, new_relayer)
+    }
+
+    #[allow(clippy::result_large_err)]
+    pub fn set_claim_cap(
+        ctx: Context<SetClaimCap>,
+        claim_cap_type: u8,
+        claim_cap: u64,
+        claim_window_length: u32,
+    ) -> Result<()> {
+        handle_set_claim_cap(ctx, claim_cap_type, claim_cap, claim_window_length)
+    }
+
+    #[allow(clippy::result_large_err)]
+    pub fn new_denylist(ctx: Context<NewDenylist>) -> Result<()> {
+        handle_new_denylist(ctx)
+    }
+
+    #[allow(clippy::result_large_err)]
+    pub fn add_to_denylist(ctx: Context<AddToDenylist>, claimant: Pubkey) -> Result<()> {
+        handle_add_to_denylist(ctx, claimant)
+    }
+
+    #[allow(clippy::result_large_err)]
+    pub fn remove_from_denylist(ctx: Context<RemoveFromDenylist>, claimant: Pubkey) -> Result<()> {
+        handle_remove_from_denylist(ctx, claimant)
+    }
+
+    #[allow(clippy::result_large_err)]
+    pub fn revoke_vesting(ctx: Context<RevokeVesting>) -> Result<()> {
+        handle_revoke_vesting(ctx)
+    }
+
+    #[allow(clippy::result_large_err)]
+    pub fn migrate_distributor(ctx: Context<MigrateDistributor>) -> Result<()> {
+        handle_migrate_distributor(ctx)
+    }
+
+    #[allow(clippy::result_large_err)]
+    pub fn new_bonus_vault(ctx: Context<NewBonusVault>) -> Result<()> {
+        handle_new_bonus_vault(ctx)
+    }
+
+    #[allow(clippy::result_large_err)]
+    pub fn clawback_bonus(ctx: Context<ClawbackBonus>) -> Result<()> {
+        handle_clawback_bonus(ctx)
+    }
+
+    #[allow(clippy::result_large_err)]
+    pub fn new_claim_record_tree(
+        ctx: Context<NewClaimRecordTree>,
+        max_depth: u32,
+        canopy_depth: u32,
+    ) -> Result<()> {
+        handle_new_claim_record_tree(ctx, max_depth, canopy_depth)
+    }
+
+    #[allow(clippy::result_large_err)]
+    pub fn queue_action(
+        ctx: Context<QueueAction>,
+        action_type: u8,
+        new_pubkey: Pubkey,
+        new_value: u64,
+        claim_cap_type: u8,
+        claim_window_length: u32,
+    ) -> Result<()> {
+        handle_queue_action(
+            ctx,
+            action_type,
+            new_pubkey,
+            new_value,
+            claim_cap_type,
+            claim_window_length,
+        )
+    }
+
+    #[allow(clippy::result_large_err)]
+    pub fn execute_action(ctx: Context<ExecuteAction>) -> Result<()> {
+        handle_execute_action(ctx)
+    }
+
+    #[allow(clippy::result_large_err)]
+    pub fn cancel_action(ctx: Context<CancelAction>) -> Result<()> {
+        handle_cancel_action(ctx)
+    }
+
     //// END ADMIN FUNCTIONS ////
     /// USER FUNCTIONS /////
     #[allow(clippy::result_large_err)]
@@ -115,5 +203,57 @@ pub mod merkle_distributor {
     pub fn claim_locked_and_stake(ctx: Context<ClaimLockedAndStake>) -> Result<()> {
         handle_claim_locked_and_stake(ctx)
     }
+
+    #[allow(clippy::result_large_err)]
+    pub fn new_claim_compressed(
+        ctx: Context<NewClaimCompressed>,
+        amount_unlocked: u64,
+        amount_locked: u64,
+        root: [u8; 32],
+        proof: Vec<[u8; 32]>,
+        airdrop_proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        handle_new_claim_compressed(
+            ctx,
+            amount_unlocked,
+            amount_locked,
+            root,
+            proof,
+            airdrop_proof,
+        )
+    }
+
+    #[allow(clippy::result_large_err)]
+    pub fn claim_locked_compressed(
+        ctx: Context<ClaimLockedCompressed>,
+        amount_unlocked: u64,
+        amount_locked: u64,
+        locked_amount_withdrawn: u64,
+        bonus_amount: u64,
+        root: [u8; 32],
+        index: u32,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        handle_claim_locked_compressed(
+            ctx,
+            amount_unlocked,
+            amount_locked,
+            locked_amount_withdrawn,
+            bonus_amount,
+            root,
+            index,
+            proof,
+        )
+    }
+
+    #[allow(clippy::result_large_err)]
+    pub fn new_claim_batch<'info>(
+        ctx: Context<'_, '_, '_, 'info, NewClaimBatch<'info>>,
+        leaves: Vec<BatchClaimLeaf>,
+        proof: Vec<[u8; 32]>,
+        proof_flags: Vec<bool>,
+    ) -> Result<()> {
+        handle_new_claim_batch(ctx, leaves, proof, proof_flags)
+    }
     // END USER FUNCTIONS //
 }
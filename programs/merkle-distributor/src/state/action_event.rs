@@ -0,0 +1,40 @@
+use anchor_lang::{event, prelude::*};
+
+/// Emitted when an admin action is queued.
+#[event]
+pub struct ActionQueuedEvent {
+    /// Distributor of the action.
+    pub distributor: Pubkey,
+    /// Pending action account.
+    pub pending_action: Pubkey,
+    /// Action type.
+    pub action_type: u8,
+    /// New pubkey.
+    pub new_pubkey: Pubkey,
+    /// New value.
+    pub new_value: u64,
+    /// Timestamp from which the action can be executed.
+    pub executable_ts: i64,
+}
+
+/// Emitted when a queued admin action is executed.
+#[event]
+pub struct ActionExecutedEvent {
+    /// Distributor of the action.
+    pub distributor: Pubkey,
+    /// Pending action account.
+    pub pending_action: Pubkey,
+    /// Action type.
+    pub action_type: u8,
+}
+
+/// Emitted when a queued admin action is cancelled.
+#[event]
+pub struct ActionCancelledEvent {
+    /// Distributor of the action.
+    pub distributor: Pubkey,
+    /// Pending action account.
+    pub pending_action: Pubkey,
+    /// Action type.
+    pub action_type: u8,
+}
@@ -0,0 +1,404 @@
+use anchor_lang::{prelude::*, solana_program::hash::hashv};
+
+use crate::{error::ErrorCode, state::claim_status::ClaimStatus};
+use static_assertions::const_assert;
+
+/// Max depth of a claim record tree, 2^23 claimants as record indexes take one level more than
+/// the airdrop merkle tree
+pub const MAX_RECORD_TREE_DEPTH: usize = 24;
+/// Max depth of the canopy, the largest one fitting the 10MB account size limit
+pub const MAX_CANOPY_DEPTH: usize = 17;
+/// Number of root updates kept to fast-forward proofs built against an older root
+pub const RECORD_CHANGELOG_SIZE: usize = 64;
+
+/// Prefix of the record leaves, the leaves of the airdrop merkle tree are prefixed with 0
+pub const RECORD_PREFIX: &[u8] = &[2];
+/// Prefix of the intermediate nodes, same as the airdrop merkle tree
+pub const RECORD_INTERMEDIATE_PREFIX: &[u8] = &[1];
+/// Leaf of a record slot not claimed yet
+pub const EMPTY_RECORD_LEAF: [u8; 32] = [0u8; 32];
+
+/// Root update of a [ClaimRecordTree]
+#[zero_copy]
+pub struct RecordChangeLog {
+    /// Root after the update
+    pub root: [u8; 32],
+    /// Nodes from the updated leaf up to, but excluding, the root
+    pub path: [[u8; 32]; MAX_RECORD_TREE_DEPTH],
+    /// Index of the updated leaf
+    pub index: u32,
+    /// padding
+    pub padding: u32,
+}
+
+/// Concurrent merkle tree holding the claim records of a compressed distributor.
+/// The tree starts empty and a claim writes the record of the claimant at its record index, the
+/// path of its leaf in the airdrop merkle tree, so a leaf can only be claimed once.
+/// The top canopy_depth levels below the root are stored after the tree in the account data, so
+/// proofs stop at the canopy and fit in a transaction next to the airdrop proof.
+#[account(zero_copy)]
+pub struct ClaimRecordTree {
+    /// distributor
+    pub distributor: Pubkey,
+    /// Depth of the tree
+    pub max_depth: u32,
+    /// Index of the changelog holding the current root
+    pub active_index: u32,
+    /// Number of updates since the tree was initialized
+    pub sequence_number: u64,
+    /// Number of levels stored in the canopy, proofs have max_depth - canopy_depth nodes
+    pub canopy_depth: u32,
+    /// padding
+    pub padding: u32,
+    /// Ring buffer of the last root updates
+    pub changelog: [RecordChangeLog; RECORD_CHANGELOG_SIZE],
+}
+
+const_assert!(std::mem::size_of::<ClaimRecordTree>() == 51768);
+
+impl ClaimRecordTree {
+    pub const SPACE: usize = 8 + std::mem::size_of::<ClaimRecordTree>();
+
+    /// Account size of a tree with a canopy of canopy_depth levels
+    pub fn space(canopy_depth: u32) -> usize {
+        Self::SPACE + get_canopy_len(canopy_depth) * 32
+    }
+
+    /// Splits the data of a claim record tree account into the tree and its canopy
+    #[allow(clippy::result_large_err)]
+    pub fn load_with_canopy_mut(data: &mut [u8]) -> Result<(&mut Self, &mut [[u8; 32]])> {
+        require!(data.len() >= Self::SPACE, ErrorCode::InvalidClaimRecordTree);
+        let (tree, canopy) = data.split_at_mut(Self::SPACE);
+        let tree: &mut Self = bytemuck::from_bytes_mut(&mut tree[8..]);
+        let canopy: &mut [[u8; 32]] =
+            bytemuck::try_cast_slice_mut(canopy).map_err(|_| ErrorCode::InvalidClaimRecordTree)?;
+        require!(
+            canopy.len() == get_canopy_len(tree.canopy_depth),
+            ErrorCode::InvalidClaimRecordTree
+        );
+        Ok((tree, canopy))
+    }
+
+    /// Initializes an empty tree, the canopy is left zeroed as a zero node stands for an empty
+    /// subtree
+    pub fn initialize(&mut self, distributor: Pubkey, max_depth: u32, canopy_depth: u32) {
+        self.distributor = distributor;
+        self.max_depth = max_depth;
+        self.canopy_depth = canopy_depth;
+        self.active_index = 0;
+        self.sequence_number = 0;
+        self.changelog[0].root = get_empty_nodes(max_depth as usize)[max_depth as usize];
+    }
+
+    pub fn get_root(&self) -> [u8; 32] {
+        self.changelog[self.active_index as usize].root
+    }
+
+    /// Replaces the leaf at index, the proof may have been built against any root still in the
+    /// changelog, it is then fast-forwarded with the updates that happened since and completed
+    /// with the canopy, which is always up to date
+    #[allow(clippy::result_large_err)]
+    pub fn replace_leaf(
+        &mut self,
+        canopy: &mut [[u8; 32]],
+        root: [u8; 32],
+        previous_leaf: [u8; 32],
+        new_leaf: [u8; 32],
+        proof: &[[u8; 32]],
+        index: u32,
+    ) -> Result<()> {
+        let max_depth = self.max_depth as usize;
+        let canopy_start = max_depth - self.canopy_depth as usize;
+        require!(proof.len() == canopy_start, ErrorCode::InvalidProof);
+        require!(
+            (index as u64) < (1u64 << max_depth),
+            ErrorCode::InvalidProof
+        );
+
+        let mut proof = proof.to_vec();
+        self.fast_forward_proof(root, index, &mut proof)?;
+        if canopy_start < max_depth {
+            let empty_nodes = get_empty_nodes(max_depth);
+            for level in canopy_start..max_depth {
+                let node = canopy[self.canopy_index(level, (index >> level) ^ 1)];
+                proof.push(if node == EMPTY_RECORD_LEAF {
+                    empty_nodes[level]
+                } else {
+                    node
+                });
+            }
+        }
+
+        require!(
+            compute_root(previous_leaf, &proof, index) == self.get_root(),
+            ErrorCode::InvalidProof
+        );
+
+        let mut path = [[0u8; 32]; MAX_RECORD_TREE_DEPTH];
+        let mut node = new_leaf;
+        for (level, sibling) in proof.iter().enumerate() {
+            path[level] = node;
+            node = hash_pair(node, *sibling, index >> level);
+        }
+        for level in canopy_start..max_depth {
+            canopy[self.canopy_index(level, index >> level)] = path[level];
+        }
+
+        self.active_index = (self.active_index + 1) % RECORD_CHANGELOG_SIZE as u32;
+        self.sequence_number = self
+            .sequence_number
+            .checked_add(1)
+            .ok_or(ErrorCode::ArithmeticError)?;
+        self.changelog[self.active_index as usize] = RecordChangeLog {
+            root: node,
+            path,
+            index,
+            padding: 0,
+        };
+
+        Ok(())
+    }
+
+    /// Replaces the nodes of the proof modified by the updates after root
+    #[allow(clippy::result_large_err)]
+    fn fast_forward_proof(&self, root: [u8; 32], index: u32, proof: &mut [[u8; 32]]) -> Result<()> {
+        let num_changelogs = self
+            .sequence_number
+            .saturating_add(1)
+            .min(RECORD_CHANGELOG_SIZE as u64) as u32;
+        let updates_since = (0..num_changelogs)
+            .find(|i| self.changelog[self.changelog_index(*i)].root == root)
+            .ok_or(ErrorCode::RecordRootNotFound)?;
+
+        for i in (0..updates_since).rev() {
+            let changelog = &self.changelog[self.changelog_index(i)];
+            require!(changelog.index != index, ErrorCode::RecordLeafChanged);
+            // the paths meet at the highest differing bit, the sibling there is on the updated path
+            let level = (31 - (changelog.index ^ index).leading_zeros()) as usize;
+            // the nodes from the canopy are already up to date
+            if level < proof.len() {
+                proof[level] = changelog.path[level];
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Index in the canopy of the node at position of a level, the canopy holds the levels below
+    /// the root one after the other
+    fn canopy_index(&self, level: usize, position: u32) -> usize {
+        let depth = self.max_depth as usize - level;
+        (1 << depth) - 2 + position as usize
+    }
+
+    /// Index of the changelog i updates before the active one
+    fn changelog_index(&self, i: u32) -> usize {
+        ((self.active_index + RECORD_CHANGELOG_SIZE as u32 - i) % RECORD_CHANGELOG_SIZE as u32)
+            as usize
+    }
+}
+
+/// Returns the number of nodes of a canopy of canopy_depth levels
+pub fn get_canopy_len(canopy_depth: u32) -> usize {
+    (1 << (canopy_depth + 1)) - 2
+}
+
+/// Returns the root of an empty subtree of each level, up to max_depth
+fn get_empty_nodes(max_depth: usize) -> [[u8; 32]; MAX_RECORD_TREE_DEPTH + 1] {
+    let mut empty_nodes = [EMPTY_RECORD_LEAF; MAX_RECORD_TREE_DEPTH + 1];
+    for level in 0..max_depth {
+        empty_nodes[level + 1] = hash_pair(empty_nodes[level], empty_nodes[level], 0);
+    }
+    empty_nodes
+}
+
+fn hash_pair(node: [u8; 32], sibling: [u8; 32], position: u32) -> [u8; 32] {
+    if position & 1 == 0 {
+        hashv(&[RECORD_INTERMEDIATE_PREFIX, &node, &sibling]).to_bytes()
+    } else {
+        hashv(&[RECORD_INTERMEDIATE_PREFIX, &sibling, &node]).to_bytes()
+    }
+}
+
+fn compute_root(leaf: [u8; 32], proof: &[[u8; 32]], index: u32) -> [u8; 32] {
+    proof
+        .iter()
+        .enumerate()
+        .fold(leaf, |node, (level, sibling)| {
+            hash_pair(node, *sibling, index >> level)
+        })
+}
+
+/// Returns the record leaf of a claim
+pub fn get_record_leaf(claim_status: &ClaimStatus) -> [u8; 32] {
+    hashv(&[
+        RECORD_PREFIX,
+        &claim_status.claimant.to_bytes(),
+        &claim_status.unlocked_amount.to_le_bytes(),
+        &claim_status.locked_amount.to_le_bytes(),
+        &claim_status.locked_amount_withdrawn.to_le_bytes(),
+        &claim_status.bonus_amount.to_le_bytes(),
+    ])
+    .to_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::{
+        solana_program::{instruction::Instruction, message::Message, pubkey},
+        system_program, InstructionData, ToAccountMetas,
+    };
+    use bytemuck::Zeroable;
+
+    /// Max size of a transaction, solana_sdk::packet::PACKET_DATA_SIZE
+    const PACKET_DATA_SIZE: usize = 1232;
+
+    fn new_tree(max_depth: u32, canopy_depth: u32) -> (Box<ClaimRecordTree>, Vec<[u8; 32]>) {
+        let mut tree = Box::new(ClaimRecordTree::zeroed());
+        tree.initialize(Pubkey::new_unique(), max_depth, canopy_depth);
+        (tree, vec![[0u8; 32]; get_canopy_len(canopy_depth)])
+    }
+
+    /// Levels of the tree of the leaves, from the leaves up to the root
+    fn build_levels(leaves: &[[u8; 32]]) -> Vec<Vec<[u8; 32]>> {
+        let mut levels = vec![leaves.to_vec()];
+        while levels[levels.len() - 1].len() > 1 {
+            let level = levels[levels.len() - 1]
+                .chunks(2)
+                .map(|pair| hash_pair(pair[0], pair[1], 0))
+                .collect();
+            levels.push(level);
+        }
+        levels
+    }
+
+    fn get_proof(levels: &[Vec<[u8; 32]>], index: u32, len: usize) -> Vec<[u8; 32]> {
+        (0..len)
+            .map(|level| levels[level][((index >> level) ^ 1) as usize])
+            .collect()
+    }
+
+    #[test]
+    fn test_replace_leaf_with_canopy() {
+        let (max_depth, canopy_depth) = (5, 2);
+        let proof_len = (max_depth - canopy_depth) as usize;
+        let (mut tree, mut canopy) = new_tree(max_depth, canopy_depth);
+        let mut leaves = vec![EMPTY_RECORD_LEAF; 1 << max_depth];
+        let levels = build_levels(&leaves);
+        assert_eq!(tree.get_root(), levels[max_depth as usize][0]);
+
+        for (i, index) in [3u32, 17, 30].into_iter().enumerate() {
+            let root = tree.get_root();
+            let proof = get_proof(&build_levels(&leaves), index, proof_len);
+            leaves[index as usize] = [i as u8 + 1; 32];
+            tree.replace_leaf(
+                &mut canopy,
+                root,
+                EMPTY_RECORD_LEAF,
+                leaves[index as usize],
+                &proof,
+                index,
+            )
+            .unwrap();
+            assert_eq!(
+                tree.get_root(),
+                build_levels(&leaves)[max_depth as usize][0]
+            );
+        }
+
+        // the proof of 12 is built before the updates of its sibling and of the other half
+        let stale_root = tree.get_root();
+        let stale_proof = get_proof(&build_levels(&leaves), 12, proof_len);
+        for index in [13u32, 28] {
+            let root = tree.get_root();
+            let proof = get_proof(&build_levels(&leaves), index, proof_len);
+            leaves[index as usize] = [index as u8; 32];
+            tree.replace_leaf(
+                &mut canopy,
+                root,
+                EMPTY_RECORD_LEAF,
+                leaves[index as usize],
+                &proof,
+                index,
+            )
+            .unwrap();
+        }
+        leaves[12] = [12; 32];
+        tree.replace_leaf(
+            &mut canopy,
+            stale_root,
+            EMPTY_RECORD_LEAF,
+            leaves[12],
+            &stale_proof,
+            12,
+        )
+        .unwrap();
+        let levels = build_levels(&leaves);
+        assert_eq!(tree.get_root(), levels[max_depth as usize][0]);
+
+        // the slot is no longer empty
+        let root = tree.get_root();
+        let proof = get_proof(&levels, 12, proof_len);
+        assert!(tree
+            .replace_leaf(&mut canopy, root, EMPTY_RECORD_LEAF, [1; 32], &proof, 12)
+            .is_err());
+        // the proof stops at the canopy
+        let proof = get_proof(&levels, 12, max_depth as usize);
+        assert!(tree
+            .replace_leaf(&mut canopy, root, leaves[12], [1; 32], &proof, 12)
+            .is_err());
+    }
+
+    #[test]
+    fn test_new_claim_compressed_transaction_size() {
+        // a record tree of depth 20 fits an airdrop merkle tree of 2^19 leaves, whose proofs
+        // have 19 nodes, the canopy leaves 3 nodes to the record proof
+        let (max_depth, canopy_depth) = (20u32, MAX_CANOPY_DEPTH as u32);
+        let claimant = Pubkey::new_unique();
+
+        let set_compute_unit_price = Instruction::new_with_bytes(
+            pubkey!("ComputeBudget111111111111111111111111111111"),
+            &[&[3u8][..], &u64::MAX.to_le_bytes()].concat(),
+            vec![],
+        );
+        let new_claim_compressed = Instruction {
+            program_id: crate::ID,
+            accounts: crate::accounts::NewClaimCompressed {
+                distributor: Pubkey::new_unique(),
+                claim_record_tree: Pubkey::new_unique(),
+                from: Pubkey::new_unique(),
+                to: Pubkey::new_unique(),
+                claimant,
+                operator: None,
+                token_program: anchor_spl::token::ID,
+                system_program: Some(system_program::ID),
+                fee_receiver: Some(Pubkey::new_unique()),
+                denylist: None,
+                bonus_vault: None,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::NewClaimCompressed {
+                amount_unlocked: u64::MAX,
+                amount_locked: u64::MAX,
+                root: [1; 32],
+                proof: vec![[2; 32]; (max_depth - canopy_depth) as usize],
+                airdrop_proof: vec![[3; 32]; max_depth as usize - 1],
+            }
+            .data(),
+        };
+
+        let message = Message::new(
+            &[set_compute_unit_price, new_claim_compressed],
+            Some(&claimant),
+        );
+        let transaction_size =
+            1 + 64 * message.header.num_required_signatures as usize + message.serialize().len();
+        assert!(
+            transaction_size <= PACKET_DATA_SIZE,
+            "transaction of {} bytes",
+            transaction_size
+        );
+        assert!(ClaimRecordTree::space(canopy_depth) <= 10 * 1024 * 1024);
+    }
+}
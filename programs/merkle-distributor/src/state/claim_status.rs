@@ -7,8 +7,9 @@ use static_assertions::const_assert;
 #[account(zero_copy)]
 #[derive(Default, InitSpace)]
 pub struct ClaimStatus {
-    /// admin of merkle tree, store for for testing purpose
-    pub admin: Pubkey,
+    /// Who paid the rent, refunded to it when the claim status is closed. Claim statuses
+    /// created before the rent payer was stored hold the admin of the distributor instead
+    pub rent_payer: Pubkey,
     /// distributor
     pub distributor: Pubkey,
     /// Authority that claimed the tokens.
@@ -23,10 +24,16 @@ pub struct ClaimStatus {
     pub bonus_amount: u64,
     /// indicate that whether admin can close this account, for testing purpose
     pub closable: u8,
+    /// Whether the vesting has been revoked, locked_amount is then frozen at the amount vested when revoked
+    pub revoked: u8,
+    /// Whether rent_payer holds the rent payer
+    pub rent_payer_stored: u8,
     /// padding 0
-    pub padding_0: [u8; 7],
+    pub padding_0: [u8; 5],
+    /// Total claim fee paid, in lamports or tokens depending on the distributor claim fee type
+    pub fee_amount: u64,
     /// padding 1
-    pub padding_1: u128,
+    pub padding_1: u64,
 }
 
 const_assert!(ClaimStatus::INIT_SPACE == 152);
@@ -46,6 +53,7 @@ impl ClaimStatus {
 
     /// Total amount unlocked
     /// Equal to (time_into_unlock / total_unlock_time) * locked_amount  
+    /// curr_ts, start_ts and end_ts are slots when the distributor vests by slot
     /// Multiplication safety:
     ///    The maximum possible product is (2^64 -1) * (2^64 -1) = 2^128 - 2^65 + 1
     ///    which is less than 2^128 - 1 (the maximum value of a u128), meaning that
@@ -61,6 +69,9 @@ impl ClaimStatus {
     ///     Since b is a i64, this is always true, so no truncation can occur
     #[allow(clippy::result_large_err)]
     pub fn unlocked_amount(&self, curr_ts: i64, start_ts: i64, end_ts: i64) -> Result<u64> {
+        if self.revoked() {
+            return Ok(self.locked_amount);
+        }
         if curr_ts >= start_ts {
             if curr_ts >= end_ts {
                 Ok(self.locked_amount)
@@ -92,6 +103,19 @@ impl ClaimStatus {
     pub fn closable(&self) -> bool {
         self.closable == 1
     }
+
+    pub fn revoked(&self) -> bool {
+        self.revoked == 1
+    }
+
+    /// Who the rent is refunded to, the claimant paid it when the rent payer was not stored
+    pub fn rent_receiver(&self) -> Pubkey {
+        if self.rent_payer_stored == 1 {
+            self.rent_payer
+        } else {
+            self.claimant
+        }
+    }
 }
 
 // #[test]
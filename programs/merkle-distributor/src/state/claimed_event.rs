@@ -7,6 +7,12 @@ pub struct NewClaimEvent {
     pub claimant: Pubkey,
     /// Timestamp.
     pub timestamp: i64,
+    /// Amount of tokens distributed to the claimant, net of token fee.
+    pub amount: u64,
+    /// Amount of tokens released from the vault, including token fee.
+    pub gross_amount: u64,
+    /// Claim fee paid, in lamports or tokens depending on the distributor claim fee type.
+    pub fee_amount: u64,
 }
 
 /// Emitted when tokens are claimed.
@@ -14,6 +20,32 @@ pub struct NewClaimEvent {
 pub struct ClaimedEvent {
     /// User that claimed.
     pub claimant: Pubkey,
-    /// Amount of tokens to distribute.
+    /// Amount of tokens to distribute, net of token fee.
     pub amount: u64,
+    /// Amount of tokens released from the vault, including token fee.
+    pub gross_amount: u64,
+    /// Claim fee paid, in lamports or tokens depending on the distributor claim fee type.
+    pub fee_amount: u64,
+}
+
+/// Emitted when a claim record of a compressed distributor is created or updated, indexers
+/// replay these events to rebuild the claim record tree.
+#[event]
+pub struct ClaimRecordEvent {
+    /// Claim record tree.
+    pub claim_record_tree: Pubkey,
+    /// Sequence number of the tree after the update.
+    pub sequence_number: u64,
+    /// Index of the record leaf.
+    pub index: u32,
+    /// User that claimed.
+    pub claimant: Pubkey,
+    /// Unlocked amount.
+    pub unlocked_amount: u64,
+    /// Locked amount.
+    pub locked_amount: u64,
+    /// Locked amount withdrawn.
+    pub locked_amount_withdrawn: u64,
+    /// Bonus amount.
+    pub bonus_amount: u64,
 }
@@ -0,0 +1,52 @@
+use crate::error::ErrorCode;
+use anchor_lang::prelude::*;
+use static_assertions::const_assert;
+
+/// Header of the denylist of a [MerkleDistributor], followed by `len` claimants
+/// sorted in ascending order, so the list can be searched without deserializing it.
+#[account(zero_copy)]
+#[derive(Default, Debug, InitSpace)]
+pub struct Denylist {
+    /// distributor
+    pub distributor: Pubkey,
+    /// Number of denylisted claimants
+    pub len: u64,
+    /// padding
+    pub padding: [u64; 4],
+}
+
+const_assert!(Denylist::INIT_SPACE == 72);
+
+impl Denylist {
+    /// Offset of the first claimant in the account data
+    pub const ENTRIES_OFFSET: usize = 8 + Denylist::INIT_SPACE;
+
+    /// Account size to store `len` claimants
+    pub fn space(len: u64) -> Result<usize> {
+        let len = usize::try_from(len).map_err(|_| ErrorCode::TypeCastedError)?;
+        Ok(Self::ENTRIES_OFFSET + len * 32)
+    }
+
+    /// Sorted claimants stored after the header
+    pub fn entries(data: &[u8], len: u64) -> Result<&[Pubkey]> {
+        let end = Self::space(len)?;
+        require!(data.len() >= end, ErrorCode::InvalidDenylist);
+        Ok(bytemuck::cast_slice(&data[Self::ENTRIES_OFFSET..end]))
+    }
+
+    /// Mutable sorted claimants stored after the header
+    pub fn entries_mut(data: &mut [u8], len: u64) -> Result<&mut [Pubkey]> {
+        let end = Self::space(len)?;
+        require!(data.len() >= end, ErrorCode::InvalidDenylist);
+        Ok(bytemuck::cast_slice_mut(
+            &mut data[Self::ENTRIES_OFFSET..end],
+        ))
+    }
+
+    /// Returns whether the claimant is in the denylist
+    pub fn contains(denylist: &AccountLoader<Denylist>, claimant: &Pubkey) -> Result<bool> {
+        let len = denylist.load()?.len;
+        let data = denylist.as_ref().try_borrow_data()?;
+        Ok(Self::entries(&data, len)?.binary_search(claimant).is_ok())
+    }
+}
@@ -0,0 +1,19 @@
+use anchor_lang::{event, prelude::*};
+
+/// Emitted when a claimant is added to the denylist.
+#[event]
+pub struct DenylistAddedEvent {
+    /// Distributor of the denylist.
+    pub distributor: Pubkey,
+    /// Denylisted claimant.
+    pub claimant: Pubkey,
+}
+
+/// Emitted when a claimant is removed from the denylist.
+#[event]
+pub struct DenylistRemovedEvent {
+    /// Distributor of the denylist.
+    pub distributor: Pubkey,
+    /// Claimant removed from the denylist.
+    pub claimant: Pubkey,
+}
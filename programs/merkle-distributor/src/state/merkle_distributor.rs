@@ -1,11 +1,15 @@
 use crate::error::ErrorCode;
 use crate::math::safe_math::SafeMath;
+use crate::state::{claim_status::ClaimStatus, denylist::Denylist};
 use anchor_lang::{
     account,
     prelude::{Pubkey, *},
+    solana_program::clock::DEFAULT_MS_PER_SLOT,
 };
+use jito_merkle_verify::HashType;
 use num_enum::{IntoPrimitive, TryFromPrimitive};
 use static_assertions::const_assert;
+use std::cell::{Ref, RefMut};
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, IntoPrimitive, TryFromPrimitive)]
 #[repr(u8)]
@@ -25,6 +29,42 @@ pub enum ClaimType {
     PermissionedWithStaking,   // 3, require double signing
 }
 
+#[derive(Copy, Clone, Debug, PartialEq, Eq, IntoPrimitive, TryFromPrimitive)]
+#[repr(u8)]
+/// Type of the claim fee
+pub enum ClaimFeeType {
+    None,     // 0
+    Lamports, // 1, fixed lamports paid by the claimant on each claim
+    Bps,      // 2, basis points of the claimed tokens
+}
+
+/// Max basis points
+pub const MAX_BPS: u64 = 10_000;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, IntoPrimitive, TryFromPrimitive)]
+#[repr(u8)]
+/// Type of the claim throughput cap
+pub enum ClaimCapType {
+    None,   // 0
+    Claims, // 1, number of new claims per window
+    Amount, // 2, claimed amount per window
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, IntoPrimitive, TryFromPrimitive)]
+#[repr(u8)]
+/// Type of the locked amount vesting
+pub enum VestingType {
+    Timestamp, // 0, start_ts and end_ts are unix timestamps
+    Slot,      // 1, start_ts and end_ts are slots
+}
+
+/// Size of the layout before versioning, excluding the discriminator.
+/// Accounts of this size are layout version 0 and must be migrated.
+pub const LAYOUT_V0_SPACE: usize = 440;
+
+/// Layout version of newly created and migrated distributors
+pub const CURRENT_LAYOUT_VERSION: u8 = 1;
+
 /// State for the account which distributes tokens.
 #[account(zero_copy)]
 #[derive(Default, Debug, InitSpace)]
@@ -55,9 +95,9 @@ pub struct MerkleDistributor {
     pub total_amount_claimed: u64,
     /// Number of nodes that have been claimed.
     pub num_nodes_claimed: u64,
-    /// Lockup time start (Unix Timestamp)
+    /// Lockup start (Unix Timestamp or slot depending on vesting_type)
     pub start_ts: i64,
-    /// Lockup time end (Unix Timestamp)
+    /// Lockup end (Unix Timestamp or slot depending on vesting_type)
     pub end_ts: i64,
     /// Clawback start (Unix Timestamp)
     pub clawback_start_ts: i64,
@@ -73,12 +113,56 @@ pub struct MerkleDistributor {
     pub clawed_back: u8,
     /// indicate that whether admin can close this pool, for testing purpose
     pub closable: u8,
-    /// Padding 0
-    pub padding_0: [u8; 3],
+    /// claim fee type, 0 means no fee, 1 means lamports, 2 means basis points of claimed tokens
+    pub claim_fee_type: u8,
+    /// claim cap type, 0 means no cap, 1 means number of new claims, 2 means claimed amount
+    pub claim_cap_type: u8,
+    /// Whether a denylist has been created, claims must then provide it
+    pub has_denylist: u8,
     // bonus multiplier
     pub airdrop_bonus: AirdropBonus,
-    // padding 2
-    pub padding_2: [u128; 5],
+    /// Minimum remaining lock (seconds) of the escrow when claiming and staking, 0 means escrow must be max lock
+    pub min_lock_duration: u32,
+    /// Delay (seconds) before a queued admin action can be executed, 0 means timelock is disabled
+    pub timelock_delay: u32,
+    /// Number of admin actions that have been queued, used as seed of [PendingAction]
+    pub num_queued_actions: u64,
+    /// Fee receiver wallet, token fees are paid to its token account
+    pub fee_receiver: Pubkey,
+    /// Claim fee, in lamports or basis points depending on claim_fee_type
+    pub claim_fee: u64,
+    /// Max number of new claims or claimed amount in a window, depending on claim_cap_type
+    pub claim_cap: u64,
+    /// Length of the claim cap window, in slots or seconds depending on activation_type
+    pub claim_window_length: u32,
+    /// Index of the current claim cap window, counted from activation_point
+    pub claim_window_index: u32,
+    /// Number of new claims or claimed amount in the current window
+    pub claimed_in_window: u64,
+    /// Layout version of the account, see [CURRENT_LAYOUT_VERSION]
+    pub layout_version: u8,
+    /// vesting type, 0 means timestamp, 1 means slot
+    pub vesting_type: u8,
+    /// Whether or not the bonus vault has been clawed back, no bonus is paid afterwards
+    pub bonus_clawed_back: u8,
+    /// hash type of the merkle tree, 0 means sha256, 1 means keccak256 for trees shared with EVM chains
+    pub hash_type: u8,
+    /// Padding 1
+    pub padding_1: [u8; 4],
+    /// Bonus vault, default pubkey means the bonus is held in the token vault
+    pub bonus_vault: Pubkey,
+    /// Relayer allowed to pay the claim status rent on behalf of claimants, refunded to it when
+    /// the claim status is closed. Default pubkey means no relayer
+    pub relayer: Pubkey,
+    /// Claim record tree of a compressed distributor, claims then go through the compressed
+    /// instructions instead of creating a claim status. Default pubkey means not compressed
+    pub claim_record_tree: Pubkey,
+    /// Id of the first pending action queued by the current admin, actions queued by a
+    /// previous admin can't be executed
+    pub first_valid_action_id: u64,
+    /// Reserved for fields added by later layout versions, a zero value must keep the
+    /// previous behaviour unless the layout version is bumped
+    pub padding_2: [u64; 3],
 }
 
 #[zero_copy]
@@ -92,6 +176,18 @@ pub struct AirdropBonus {
     pub total_claimed_bonus: u64,
 }
 
+/// Amounts of a new claim, see [MerkleDistributor::record_new_claim]
+pub struct NewClaimAmounts {
+    /// Unlocked amount, bonus and locked amount already vested
+    pub claim_amount: u64,
+    /// Claim amount less the token fee, transferred to the claimant
+    pub net_amount: u64,
+    /// Moved from the bonus vault, if any, to the token vault
+    pub bonus_amount: u64,
+    pub token_fee: u64,
+    pub lamports_fee: u64,
+}
+
 pub struct ActivationHandler {
     /// current slot or current timestamp
     pub curr_point: u64,
@@ -144,6 +240,37 @@ impl MerkleDistributor {
             airdrop_bonus: self.airdrop_bonus,
         })
     }
+    /// Current slot or timestamp, depending on the vesting type
+    pub fn get_vesting_point(&self) -> Result<i64> {
+        let vesting_type =
+            VestingType::try_from(self.vesting_type).map_err(|_| ErrorCode::InvalidVestingType)?;
+        let curr_point = match vesting_type {
+            VestingType::Timestamp => Clock::get()?.unix_timestamp,
+            VestingType::Slot => Clock::get()?.slot as i64,
+        };
+        Ok(curr_point)
+    }
+
+    pub fn get_hash_type(&self) -> Result<HashType> {
+        Ok(HashType::try_from(self.hash_type).map_err(|_| ErrorCode::InvalidHashType)?)
+    }
+
+    /// Remaining seconds in lockup, estimated from the default slot duration for slot vesting
+    pub fn get_remaining_vesting_seconds(&self, curr_point: i64) -> Result<i64> {
+        if curr_point >= self.end_ts {
+            return Ok(0);
+        }
+        let remaining = self.end_ts.safe_sub(curr_point)?;
+        let vesting_type =
+            VestingType::try_from(self.vesting_type).map_err(|_| ErrorCode::InvalidVestingType)?;
+        match vesting_type {
+            VestingType::Timestamp => Ok(remaining),
+            VestingType::Slot => Ok(remaining
+                .safe_mul(DEFAULT_MS_PER_SLOT as i64)?
+                .safe_div(1000)?),
+        }
+    }
+
     pub fn accumulate_bonus(&mut self, bonus: u64) -> Result<()> {
         self.airdrop_bonus.total_claimed_bonus =
             self.airdrop_bonus.total_claimed_bonus.safe_add(bonus)?;
@@ -164,9 +291,29 @@ impl MerkleDistributor {
         unlocked_amount: u64,
         activation_handler: &ActivationHandler,
     ) -> Result<u64> {
+        if self.bonus_clawed_back() {
+            return Ok(0);
+        }
         let max_bonus = self.get_max_bonus_for_a_claimant(unlocked_amount)?;
         activation_handler.get_bonus_for_a_claimaint(max_bonus)
     }
+
+    /// Returns whether the payer of a new claim is the claimant or the relayer of the distributor
+    pub fn is_valid_payer(&self, payer: &Pubkey, claimant: &Pubkey) -> bool {
+        payer == claimant || (self.relayer != Pubkey::default() && payer == &self.relayer)
+    }
+
+    pub fn is_compressed(&self) -> bool {
+        self.claim_record_tree != Pubkey::default()
+    }
+
+    pub fn has_bonus_vault(&self) -> bool {
+        self.bonus_vault != Pubkey::default()
+    }
+
+    pub fn bonus_clawed_back(&self) -> bool {
+        self.bonus_clawed_back == 1
+    }
     pub fn clawed_back(&self) -> bool {
         self.clawed_back == 1
     }
@@ -178,6 +325,194 @@ impl MerkleDistributor {
         self.closable == 1
     }
 
+    /// Returns the token fee charged on the claimed amount, 0 if the fee is not in basis points
+    pub fn get_token_fee(&self, gross_amount: u64) -> Result<u64> {
+        let claim_fee_type =
+            ClaimFeeType::try_from(self.claim_fee_type).map_err(|_| ErrorCode::TypeCastedError)?;
+        if claim_fee_type != ClaimFeeType::Bps {
+            return Ok(0);
+        }
+        // round down in favour of the claimant
+        let fee = (gross_amount as u128)
+            .safe_mul(self.claim_fee as u128)?
+            .safe_div(MAX_BPS as u128)?;
+        Ok(u64::try_from(fee).map_err(|_| ErrorCode::TypeCastedError)?)
+    }
+
+    /// Returns the lamports fee paid by the claimant, 0 if the fee is not in lamports
+    pub fn get_lamports_fee(&self) -> Result<u64> {
+        let claim_fee_type =
+            ClaimFeeType::try_from(self.claim_fee_type).map_err(|_| ErrorCode::TypeCastedError)?;
+        if claim_fee_type != ClaimFeeType::Lamports {
+            return Ok(0);
+        }
+        Ok(self.claim_fee)
+    }
+
+    /// Accounts a claim in the current claim cap window, fails if the cap of the window is reached
+    pub fn consume_claim_cap(
+        &mut self,
+        curr_point: u64,
+        is_new_claim: bool,
+        amount: u64,
+    ) -> Result<()> {
+        let claim_cap_type =
+            ClaimCapType::try_from(self.claim_cap_type).map_err(|_| ErrorCode::TypeCastedError)?;
+        let consumed = match claim_cap_type {
+            ClaimCapType::None => return Ok(()),
+            ClaimCapType::Claims => {
+                if !is_new_claim {
+                    return Ok(());
+                }
+                1
+            }
+            ClaimCapType::Amount => amount,
+        };
+
+        let window_index = curr_point
+            .saturating_sub(self.activation_point)
+            .safe_div(self.claim_window_length.into())?;
+        let window_index = u32::try_from(window_index).map_err(|_| ErrorCode::TypeCastedError)?;
+        if window_index != self.claim_window_index {
+            self.claim_window_index = window_index;
+            self.claimed_in_window = 0;
+        }
+
+        self.claimed_in_window = self.claimed_in_window.safe_add(consumed)?;
+        require!(
+            self.claimed_in_window <= self.claim_cap,
+            ErrorCode::ClaimCapExceeded
+        );
+        Ok(())
+    }
+
+    /// Records a new claim of a claim status whose unlocked and locked amounts are set:
+    /// 1. Sets the bonus of the claimant
+    /// 2. Withdraws the locked amount already vested, for late claimers
+    /// 3. Adds the claimed amount to total_amount_claimed and to the claim cap of the window
+    /// 4. Sets the claim fee, only one of the token and lamports fees is non-zero
+    pub fn record_new_claim(
+        &mut self,
+        claim_status: &mut ClaimStatus,
+        activation_handler: &ActivationHandler,
+    ) -> Result<NewClaimAmounts> {
+        claim_status.bonus_amount =
+            self.get_bonus_for_a_claimaint(claim_status.unlocked_amount, activation_handler)?;
+        claim_status.locked_amount_withdrawn = claim_status.amount_withdrawable(
+            self.get_vesting_point()?,
+            self.start_ts,
+            self.end_ts,
+        )?;
+
+        let claim_amount = claim_status
+            .get_total_unlocked_amount()?
+            .safe_add(claim_status.locked_amount_withdrawn)?;
+
+        self.total_amount_claimed = self
+            .total_amount_claimed
+            .checked_add(claim_amount)
+            .ok_or(ErrorCode::ArithmeticError)?;
+
+        self.accumulate_bonus(claim_status.bonus_amount)?;
+
+        require!(
+            self.total_amount_claimed <= self.max_total_claim,
+            ErrorCode::ExceededMaxClaim
+        );
+
+        self.consume_claim_cap(activation_handler.curr_point, true, claim_amount)?;
+
+        let token_fee = self.get_token_fee(claim_amount)?;
+        let lamports_fee = self.get_lamports_fee()?;
+        claim_status.fee_amount = token_fee.safe_add(lamports_fee)?;
+
+        Ok(NewClaimAmounts {
+            claim_amount,
+            net_amount: claim_amount.safe_sub(token_fee)?,
+            bonus_amount: claim_status.bonus_amount,
+            token_fee,
+            lamports_fee,
+        })
+    }
+
+    pub fn has_denylist(&self) -> bool {
+        self.has_denylist == 1
+    }
+
+    /// Fails if the claimant is in the denylist of the distributor
+    pub fn authorize_claimant(
+        &self,
+        denylist: &Option<AccountLoader<Denylist>>,
+        claimant: &Pubkey,
+    ) -> Result<()> {
+        if !self.has_denylist() {
+            return Ok(());
+        }
+        let denylist = denylist.as_ref().ok_or(ErrorCode::InvalidDenylist)?;
+        require!(
+            !Denylist::contains(denylist, claimant)?,
+            ErrorCode::ClaimantDenylisted
+        );
+        Ok(())
+    }
+
+    pub fn timelock_enabled(&self) -> bool {
+        self.timelock_delay > 0
+    }
+
+    /// Setters of the distributor parameters are only allowed directly without a timelock, they
+    /// are queued as pending actions otherwise. Instructions that can't be used against
+    /// claimants (denylist, vesting revocation, migration, compression before the first claim)
+    /// are not timelocked.
+    #[allow(clippy::result_large_err)]
+    pub fn require_no_timelock(&self) -> Result<()> {
+        require!(!self.timelock_enabled(), ErrorCode::TimelockEnabled);
+        Ok(())
+    }
+
+    /// Sets the admin and invalidates the actions queued by the previous one
+    pub fn set_admin(&mut self, new_admin: Pubkey) {
+        self.admin = new_admin;
+        self.first_valid_action_id = self.num_queued_actions;
+    }
+
+    pub fn validate_claim_cap(
+        claim_cap_type: u8,
+        claim_cap: u64,
+        claim_window_length: u32,
+    ) -> Result<()> {
+        let claim_cap_type =
+            ClaimCapType::try_from(claim_cap_type).map_err(|_| ErrorCode::InvalidClaimCap)?;
+        if claim_cap_type == ClaimCapType::None {
+            require!(
+                claim_cap == 0 && claim_window_length == 0,
+                ErrorCode::InvalidClaimCap
+            );
+        } else {
+            require!(
+                claim_cap > 0 && claim_window_length > 0,
+                ErrorCode::InvalidClaimCap
+            );
+        }
+        Ok(())
+    }
+
+    pub fn set_claim_cap(
+        &mut self,
+        claim_cap_type: u8,
+        claim_cap: u64,
+        claim_window_length: u32,
+    ) -> Result<()> {
+        Self::validate_claim_cap(claim_cap_type, claim_cap, claim_window_length)?;
+        self.claim_cap_type = claim_cap_type;
+        self.claim_cap = claim_cap;
+        self.claim_window_length = claim_window_length;
+        // restart counting from the next claim
+        self.claim_window_index = 0;
+        self.claimed_in_window = 0;
+        Ok(())
+    }
+
     pub fn authorize_claim<'info>(&self, operator: &Option<Signer<'info>>) -> Result<()> {
         // check operator
         let claim_type =
@@ -215,6 +550,40 @@ impl MerkleDistributor {
         Ok(())
     }
 
+    /// Returns the lock duration, counted from now, the escrow must be extended to so that it
+    /// remains locked for at least min_lock_duration. Returns 0 if no extension is needed.
+    pub fn get_lock_duration_to_extend(
+        &self,
+        is_max_lock: bool,
+        escrow_ends_at: i64,
+        curr_ts: i64,
+    ) -> Result<i64> {
+        if is_max_lock {
+            return Ok(0);
+        }
+        // legacy distributor, only accept max lock escrow
+        require!(self.min_lock_duration > 0, ErrorCode::EscrowIsNotMaxLock);
+
+        let min_escrow_ends_at = curr_ts.safe_add(i64::from(self.min_lock_duration))?;
+        if escrow_ends_at >= min_escrow_ends_at {
+            return Ok(0);
+        }
+        // the voter extends the lock to the duration from now, not by it
+        Ok(i64::from(self.min_lock_duration))
+    }
+
+    /// Fills in the default values of the fields added after the account layout version.
+    /// The realloc zero-initializes new fields, only non-zero defaults need to be set here.
+    pub fn migrate(&mut self) -> Result<()> {
+        require!(
+            self.layout_version < CURRENT_LAYOUT_VERSION,
+            ErrorCode::DistributorAlreadyMigrated
+        );
+        // version 1: adds layout_version and the reserved padding, no non-zero default
+        self.layout_version = CURRENT_LAYOUT_VERSION;
+        Ok(())
+    }
+
     pub fn signer(&self) -> MerkleDistributorSigner {
         MerkleDistributorSigner {
             base: self.base.to_bytes(),
@@ -225,7 +594,46 @@ impl MerkleDistributor {
     }
 }
 
-const_assert!(MerkleDistributor::INIT_SPACE == 440);
+const_assert!(MerkleDistributor::INIT_SPACE == 576);
+
+/// Loads a [MerkleDistributor] at the current layout version. Distributors of an older layout
+/// fail with InvalidDistributorLayout until they are migrated, instead of being read past the
+/// end of their data.
+pub trait DistributorLoader {
+    #[allow(clippy::result_large_err)]
+    fn load_current(&self) -> Result<Ref<'_, MerkleDistributor>>;
+
+    #[allow(clippy::result_large_err)]
+    fn load_current_mut(&self) -> Result<RefMut<'_, MerkleDistributor>>;
+}
+
+impl<'info> DistributorLoader for AccountLoader<'info, MerkleDistributor> {
+    fn load_current(&self) -> Result<Ref<'_, MerkleDistributor>> {
+        require!(
+            self.as_ref().data_len() >= 8 + MerkleDistributor::INIT_SPACE,
+            ErrorCode::InvalidDistributorLayout
+        );
+        let distributor = self.load()?;
+        require!(
+            distributor.layout_version == CURRENT_LAYOUT_VERSION,
+            ErrorCode::InvalidDistributorLayout
+        );
+        Ok(distributor)
+    }
+
+    fn load_current_mut(&self) -> Result<RefMut<'_, MerkleDistributor>> {
+        require!(
+            self.as_ref().data_len() >= 8 + MerkleDistributor::INIT_SPACE,
+            ErrorCode::InvalidDistributorLayout
+        );
+        let distributor = self.load_mut()?;
+        require!(
+            distributor.layout_version == CURRENT_LAYOUT_VERSION,
+            ErrorCode::InvalidDistributorLayout
+        );
+        Ok(distributor)
+    }
+}
 
 pub struct MerkleDistributorSigner {
     base: [u8; 32],
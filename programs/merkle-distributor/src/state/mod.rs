@@ -1,3 +1,10 @@
+pub mod action_event;
+pub mod claim_record_tree;
 pub mod claim_status;
 pub mod claimed_event;
+pub mod denylist;
+pub mod denylist_event;
 pub mod merkle_distributor;
+pub mod pending_action;
+pub mod registry;
+pub mod revoke_event;
@@ -0,0 +1,126 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+use num_enum::{IntoPrimitive, TryFromPrimitive};
+use static_assertions::const_assert;
+
+use crate::{
+    error::ErrorCode,
+    state::merkle_distributor::{ClaimType, MerkleDistributor},
+};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, IntoPrimitive, TryFromPrimitive)]
+#[repr(u8)]
+/// Type of the timelocked admin action
+pub enum ActionType {
+    SetActivationPoint,  // 0, uses new_value
+    SetClawbackReceiver, // 1, uses new_pubkey
+    SetOperator,         // 2, uses new_pubkey
+    SetAdmin,            // 3, uses new_pubkey
+    SetTimelockDelay,    // 4, uses new_value
+    SetRelayer,          // 5, uses new_pubkey
+    SetClaimCap,         // 6, uses new_value as the cap, claim_cap_type and claim_window_length
+}
+
+/// Admin action queued in the timelock of a [MerkleDistributor].
+#[account(zero_copy)]
+#[derive(Default, Debug, InitSpace)]
+pub struct PendingAction {
+    /// distributor
+    pub distributor: Pubkey,
+    /// Admin that queued the action, receives the rent back
+    pub payer: Pubkey,
+    /// New pubkey, for set clawback receiver, operator, admin and relayer
+    pub new_pubkey: Pubkey,
+    /// Id of the action in the distributor
+    pub action_id: u64,
+    /// New value, for set activation point and timelock delay
+    pub new_value: u64,
+    /// Timestamp when the action was queued
+    pub queued_ts: i64,
+    /// Timestamp from which the action can be executed
+    pub executable_ts: i64,
+    /// action type
+    pub action_type: u8,
+    /// New claim cap type, for set claim cap
+    pub claim_cap_type: u8,
+    /// padding 0
+    pub padding_0: [u8; 2],
+    /// New claim window length, for set claim cap
+    pub claim_window_length: u32,
+    /// padding 1
+    pub padding_1: u128,
+}
+
+const_assert!(PendingAction::INIT_SPACE == 152);
+
+impl PendingAction {
+    pub fn is_executable(&self, curr_ts: i64) -> bool {
+        curr_ts >= self.executable_ts
+    }
+
+    /// Whether the action was queued by the current admin of the distributor
+    pub fn is_queued_by_admin(&self, distributor: &MerkleDistributor) -> bool {
+        self.payer == distributor.admin && self.action_id >= distributor.first_valid_action_id
+    }
+
+    /// Runs the checks of the direct admin instruction, when the action is queued and again
+    /// when it is executed, as the distributor may have changed in between
+    pub fn validate(
+        &self,
+        distributor: &MerkleDistributor,
+        new_clawback_account: &Option<Account<TokenAccount>>,
+    ) -> Result<()> {
+        let action_type =
+            ActionType::try_from(self.action_type).map_err(|_| ErrorCode::InvalidActionType)?;
+        match action_type {
+            ActionType::SetActivationPoint => {}
+            ActionType::SetTimelockDelay => {
+                u32::try_from(self.new_value).map_err(|_| ErrorCode::TypeCastedError)?;
+            }
+            ActionType::SetClawbackReceiver => {
+                // the mint is checked by the account constraint
+                let new_clawback_account = new_clawback_account
+                    .as_ref()
+                    .ok_or(ErrorCode::InvalidActionType)?;
+                require!(
+                    new_clawback_account.key() == self.new_pubkey,
+                    ErrorCode::InvalidActionType
+                );
+                require!(
+                    distributor.clawback_receiver != self.new_pubkey,
+                    ErrorCode::SameClawbackReceiver
+                );
+            }
+            ActionType::SetOperator => {
+                let claim_type = ClaimType::try_from(distributor.claim_type)
+                    .map_err(|_| ErrorCode::TypeCastedError)?;
+                require!(
+                    claim_type == ClaimType::Permissioned
+                        || claim_type == ClaimType::PermissionedWithStaking,
+                    ErrorCode::InvalidClaimType
+                );
+                require!(
+                    distributor.operator != self.new_pubkey,
+                    ErrorCode::SameOperator
+                );
+            }
+            ActionType::SetAdmin => {
+                require!(distributor.admin != self.new_pubkey, ErrorCode::SameAdmin);
+            }
+            ActionType::SetRelayer => {
+                require!(
+                    distributor.relayer != self.new_pubkey,
+                    ErrorCode::SameRelayer
+                );
+            }
+            ActionType::SetClaimCap => {
+                MerkleDistributor::validate_claim_cap(
+                    self.claim_cap_type,
+                    self.new_value,
+                    self.claim_window_length,
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
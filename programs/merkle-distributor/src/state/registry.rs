@@ -0,0 +1,78 @@
+use crate::error::ErrorCode;
+use anchor_lang::{prelude::*, Discriminator};
+use static_assertions::const_assert;
+
+/// Header of the registry of every [MerkleDistributor] created with a base and mint,
+/// followed by `len` entries in creation order.
+#[account(zero_copy)]
+#[derive(Default, Debug, InitSpace)]
+pub struct Registry {
+    /// Base key of the distributors
+    pub base: Pubkey,
+    /// Mint of the distributors
+    pub mint: Pubkey,
+    /// Number of registered distributors
+    pub len: u64,
+    /// padding
+    pub padding: [u64; 4],
+}
+
+const_assert!(Registry::INIT_SPACE == 104);
+
+/// A distributor recorded in the [Registry]
+#[zero_copy]
+#[derive(Default, Debug, InitSpace)]
+pub struct RegistryEntry {
+    /// Version of the distributor
+    pub version: u64,
+    /// Distributor
+    pub distributor: Pubkey,
+    /// Merkle root of the distributor when it was created
+    pub root: [u8; 32],
+}
+
+const_assert!(RegistryEntry::INIT_SPACE == 72);
+
+impl Registry {
+    /// Offset of the first entry in the account data
+    pub const ENTRIES_OFFSET: usize = 8 + Registry::INIT_SPACE;
+
+    /// Account size to store `len` entries
+    pub fn space(len: u64) -> Result<usize> {
+        let len = usize::try_from(len).map_err(|_| ErrorCode::TypeCastedError)?;
+        Ok(Self::ENTRIES_OFFSET + len * RegistryEntry::INIT_SPACE)
+    }
+
+    /// Entries stored after the header
+    pub fn entries(data: &[u8], len: u64) -> Result<&[RegistryEntry]> {
+        let end = Self::space(len)?;
+        require!(data.len() >= end, ErrorCode::InvalidRegistry);
+        Ok(bytemuck::cast_slice(&data[Self::ENTRIES_OFFSET..end]))
+    }
+
+    /// Copies the header and the entries out of account data of any alignment, such as data
+    /// fetched over RPC, which can't be cast in place
+    pub fn read_unaligned(data: &[u8]) -> Result<(Registry, Vec<RegistryEntry>)> {
+        require!(
+            data.len() >= Self::ENTRIES_OFFSET && data[..8] == Registry::DISCRIMINATOR,
+            ErrorCode::InvalidRegistry
+        );
+        let registry: Registry = bytemuck::pod_read_unaligned(&data[8..Self::ENTRIES_OFFSET]);
+        let end = Self::space(registry.len)?;
+        require!(data.len() >= end, ErrorCode::InvalidRegistry);
+        let entries = data[Self::ENTRIES_OFFSET..end]
+            .chunks_exact(RegistryEntry::INIT_SPACE)
+            .map(bytemuck::pod_read_unaligned)
+            .collect();
+        Ok((registry, entries))
+    }
+
+    /// Mutable entries stored after the header
+    pub fn entries_mut(data: &mut [u8], len: u64) -> Result<&mut [RegistryEntry]> {
+        let end = Self::space(len)?;
+        require!(data.len() >= end, ErrorCode::InvalidRegistry);
+        Ok(bytemuck::cast_slice_mut(
+            &mut data[Self::ENTRIES_OFFSET..end],
+        ))
+    }
+}
@@ -0,0 +1,14 @@
+use anchor_lang::{event, prelude::*};
+
+/// Emitted when the vesting of a claimant is revoked.
+#[event]
+pub struct VestingRevokedEvent {
+    /// Distributor of the claim status.
+    pub distributor: Pubkey,
+    /// Claimant whose vesting is revoked.
+    pub claimant: Pubkey,
+    /// Locked amount vested when revoked, still withdrawable by the claimant.
+    pub vested_amount: u64,
+    /// Unvested amount returned to the clawback receiver.
+    pub revoked_amount: u64,
+}
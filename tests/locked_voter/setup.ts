@@ -103,5 +103,23 @@ export async function createNewEscrowWithMaxLock(params: CreateNewEscrowParams)
 
     await getOrCreateAssociatedTokenAccountWrap(program.provider.connection, escrowOwner, lockerState.tokenMint, escrow);
 
+    return escrow
+}
+
+export async function createNewEscrow(params: CreateNewEscrowParams) {
+    let { locker, escrowOwner } = params;
+    let program = createLockedVoterProgram(new Wallet(escrowOwner));
+    let lockerState = await program.account.locker.fetch(locker);
+    let escrow = deriveEscrow(locker, escrowOwner.publicKey);
+    await program.methods.newEscrow().accounts({
+        locker,
+        escrow,
+        escrowOwner: escrowOwner.publicKey,
+        payer: escrowOwner.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+    }).rpc().catch(console.log).then(console.log);
+
+    await getOrCreateAssociatedTokenAccountWrap(program.provider.connection, escrowOwner, lockerState.tokenMint, escrow);
+
     return escrow
 }
\ No newline at end of file
@@ -7,7 +7,7 @@ import {
     IDL as MerkleDistributorIDL,
 } from "../../target/types/merkle_distributor";
 import { encodeU64, getOrCreateAssociatedTokenAccountWrap } from "../common";
-import { getAssociatedTokenAddressSync, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { createAssociatedTokenAccountIdempotentInstruction, getAssociatedTokenAddressSync, TOKEN_PROGRAM_ID } from "@solana/spl-token";
 import { LOCKED_VOTER_PROGRAM_ID } from "../locked_voter/setup";
 
 const MERKLE_DISTRIBUTOR_PROGRAM_ID = new web3.PublicKey(
@@ -47,10 +47,93 @@ export function deriveClaimStatus(
 
 
 
+export function derivePendingAction(
+    distributor: web3.PublicKey,
+    actionId: number,
+) {
+    let [pk, _] = web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("PendingAction"), distributor.toBuffer(), encodeU64(actionId)],
+        MERKLE_DISTRIBUTOR_PROGRAM_ID
+    );
+    return pk
+}
+
+export function deriveDenylist(
+    distributor: web3.PublicKey,
+) {
+    let [pk, _] = web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("Denylist"), distributor.toBuffer()],
+        MERKLE_DISTRIBUTOR_PROGRAM_ID
+    );
+    return pk
+}
+
+export function deriveBonusVault(
+    distributor: web3.PublicKey,
+) {
+    let [pk, _] = web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("BonusVault"), distributor.toBuffer()],
+        MERKLE_DISTRIBUTOR_PROGRAM_ID
+    );
+    return pk
+}
+
+export function deriveRegistry(
+    base: web3.PublicKey,
+    mint: web3.PublicKey,
+) {
+    let [pk, _] = web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("Registry"), base.toBuffer(), mint.toBuffer()],
+        MERKLE_DISTRIBUTOR_PROGRAM_ID
+    );
+    return pk
+}
+
+// registry entries follow the 8 bytes discriminator and the 104 bytes header
+export async function getRegistryEntries(connection: web3.Connection, registry: PublicKey) {
+    let account = await connection.getAccountInfo(registry);
+    let len = new BN(account.data.subarray(72, 80), "le").toNumber();
+    let entries = [];
+    for (let i = 0; i < len; i++) {
+        let offset = 112 + i * 72;
+        entries.push({
+            version: new BN(account.data.subarray(offset, offset + 8), "le"),
+            distributor: new PublicKey(account.data.subarray(offset + 8, offset + 40)),
+            root: Array.from(account.data.subarray(offset + 40, offset + 72)),
+        });
+    }
+    return entries;
+}
+
 export const ADMIN = Keypair.fromSecretKey(new Uint8Array(JSON.parse(res)));
 
 export const ADMIN_PUBKEY = ADMIN.publicKey;
 
+// claim fee type: 0 no fee, 1 lamports, 2 basis points of claimed tokens
+export function getFeeReceiverAccount(distributorState: { claimFeeType: number, feeReceiver: PublicKey, mint: PublicKey }) {
+    if (distributorState.claimFeeType == 1) {
+        return distributorState.feeReceiver;
+    }
+    if (distributorState.claimFeeType == 2) {
+        return getAssociatedTokenAddressSync(distributorState.mint, distributorState.feeReceiver, true);
+    }
+    return null;
+}
+
+export function getDenylistAccount(distributor: PublicKey, distributorState: { hasDenylist: number }) {
+    if (distributorState.hasDenylist == 1) {
+        return deriveDenylist(distributor);
+    }
+    return null;
+}
+
+export function getBonusVaultAccount(distributorState: { bonusVault: PublicKey }) {
+    if (!distributorState.bonusVault.equals(PublicKey.default)) {
+        return distributorState.bonusVault;
+    }
+    return null;
+}
+
 export function createDistributorProgram(wallet: Wallet): Program<MerkleDistributor> {
     const provider = new AnchorProvider(AnchorProvider.env().connection, wallet, {
         maxRetries: 3,
@@ -83,6 +166,14 @@ export interface CreateNewDisitrbutorParams {
     locker: PublicKey;
     mint: PublicKey;
     clawbackReceiver: PublicKey;
+    minLockDuration?: number;
+    timelockDelay?: number;
+    claimFeeType?: number;
+    claimFee?: BN;
+    feeReceiver?: PublicKey;
+    vestingType?: number;
+    hashType?: number;
+    base?: Keypair;
 }
 
 export async function createNewDistributor(
@@ -100,11 +191,12 @@ export async function createNewDistributor(
         activationPoint,
         activationType,
         closable, totalBonus, bonusVestingDuration,
-        claimType, operator, locker, mint, clawbackReceiver
+        claimType, operator, locker, mint, clawbackReceiver, minLockDuration, timelockDelay,
+        claimFeeType, claimFee, feeReceiver, vestingType, hashType
     } = params;
     const program = createDistributorProgram(new Wallet(admin));
 
-    let base = Keypair.generate();
+    let base = params.base ?? Keypair.generate();
 
     let distributor = deriveDistributor(base.publicKey, mint, version);
     let tokenVault = await getOrCreateAssociatedTokenAccountWrap(program.provider.connection, admin, mint, distributor);
@@ -125,9 +217,17 @@ export async function createNewDistributor(
             claimType,
             operator,
             locker,
+            minLockDuration: minLockDuration ?? 0,
+            timelockDelay: timelockDelay ?? 0,
+            claimFeeType: claimFeeType ?? 0,
+            claimFee: claimFee ?? new BN(0),
+            feeReceiver: feeReceiver ?? web3.SystemProgram.programId,
+            vestingType: vestingType ?? 0,
+            hashType: hashType ?? 0,
         })
         .accounts({
             distributor,
+            registry: deriveRegistry(base.publicKey, mint),
             mint,
             clawbackReceiver,
             tokenVault,
@@ -135,6 +235,7 @@ export async function createNewDistributor(
             base: base.publicKey,
             systemProgram: web3.SystemProgram.programId,
             tokenProgram: TOKEN_PROGRAM_ID,
+            locker: minLockDuration ? locker : null,
         }).signers([base])
         .rpc().catch(console.log).then(console.log);
 
@@ -144,6 +245,8 @@ export async function createNewDistributor(
 export interface ClaimParams {
     claimant: Keypair;
     operator?: Keypair;
+    // pays the claim status rent instead of the claimant, e.g. a relayer
+    payer?: Keypair;
     distributor: PublicKey,
     amountUnlocked: BN;
     amountLocked: BN;
@@ -155,6 +258,7 @@ export async function claim(
     params: ClaimParams
 ) {
     let { claimant, amountUnlocked, amountLocked, proof, distributor, operator } = params;
+    let payer = params.payer ?? claimant;
     const program = createDistributorProgram(new Wallet(claimant));
 
     let distributorState = await program.account.merkleDistributor.fetch(distributor);
@@ -165,24 +269,32 @@ export async function claim(
         await program.methods.newClaim(amountUnlocked, amountLocked, proof).accounts({
             distributor,
             claimant: claimant.publicKey,
+            payer: payer.publicKey,
             claimStatus,
             from: distributorState.tokenVault,
             to,
             systemProgram: web3.SystemProgram.programId,
             tokenProgram: TOKEN_PROGRAM_ID,
             operator: null,
-        }).rpc().catch(console.log).then(console.log);
+            feeReceiver: getFeeReceiverAccount(distributorState),
+            denylist: getDenylistAccount(distributor, distributorState),
+            bonusVault: getBonusVaultAccount(distributorState),
+        }).signers(payer == claimant ? [] : [payer]).rpc().catch(console.log).then(console.log);
     } else {
         // user sign tx firstly (need to verify signature to avoid spaming)
         let tx = await program.methods.newClaim(amountUnlocked, amountLocked, proof).accounts({
             distributor,
             claimant: claimant.publicKey,
+            payer: payer.publicKey,
             claimStatus,
             from: distributorState.tokenVault,
             to,
             systemProgram: web3.SystemProgram.programId,
             tokenProgram: TOKEN_PROGRAM_ID,
             operator: operator.publicKey,
+            feeReceiver: getFeeReceiverAccount(distributorState),
+            denylist: getDenylistAccount(distributor, distributorState),
+            bonusVault: getBonusVaultAccount(distributorState),
         }).transaction();
 
         // pass tx to operator to sign
@@ -192,6 +304,9 @@ export async function claim(
         tx.recentBlockhash = blockhash;
         tx.lastValidBlockHeight = lastValidBlockHeight;
         tx.partialSign(operator);
+        if (payer != claimant) {
+            tx.partialSign(payer);
+        }
 
         // pass back user to sign
         const signedTx = await new Wallet(claimant).signTransaction(tx);
@@ -201,6 +316,52 @@ export async function claim(
 }
 
 
+export interface ClaimBatchParams {
+    // owner or delegate of the token accounts of the leaves
+    claimant: Keypair;
+    distributor: PublicKey,
+    // in the order of the tree, the tokens of each leaf are sent to the associated token account
+    // of its claimant
+    leaves: { claimant: PublicKey, amountUnlocked: BN, amountLocked: BN }[];
+    proof: Array<number>[];
+    proofFlags: boolean[];
+}
+
+// errors are thrown to the caller
+export async function claimBatch(
+    params: ClaimBatchParams
+) {
+    let { claimant, distributor, leaves, proof, proofFlags } = params;
+    const program = createDistributorProgram(new Wallet(claimant));
+
+    let distributorState = await program.account.merkleDistributor.fetch(distributor);
+    let remainingAccounts = [];
+    let batchLeaves = leaves.map(({ claimant: leafClaimant, amountUnlocked, amountLocked }) => {
+        let [claimStatus, claimStatusBump] = web3.PublicKey.findProgramAddressSync(
+            [Buffer.from("ClaimStatus"), leafClaimant.toBuffer(), distributor.toBuffer()],
+            MERKLE_DISTRIBUTOR_PROGRAM_ID
+        );
+        remainingAccounts.push(
+            { pubkey: claimStatus, isSigner: false, isWritable: true },
+            { pubkey: getAssociatedTokenAddressSync(distributorState.mint, leafClaimant), isSigner: false, isWritable: true },
+        );
+        return { amountUnlocked, amountLocked, claimStatusBump };
+    });
+
+    await program.methods.newClaimBatch(batchLeaves, proof, proofFlags).accounts({
+        distributor,
+        from: distributorState.tokenVault,
+        claimant: claimant.publicKey,
+        payer: claimant.publicKey,
+        operator: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: web3.SystemProgram.programId,
+        feeReceiver: getFeeReceiverAccount(distributorState),
+        denylist: getDenylistAccount(distributor, distributorState),
+        bonusVault: getBonusVaultAccount(distributorState),
+    }).remainingAccounts(remainingAccounts).rpc().then(console.log);
+}
+
 export interface ClaimAndStakeParams {
     claimant: Keypair;
     escrow: PublicKey,
@@ -219,11 +380,17 @@ export async function claimAndStake(
 
     let distributorState = await program.account.merkleDistributor.fetch(distributor);
     let claimStatus = deriveClaimStatus(distributor, claimant.publicKey);
+    let escrowTokens = getAssociatedTokenAddressSync(distributorState.mint, escrow, true);
+    // escrow may be created in the claim, so its token account is created beforehand
+    let preInstructions = [
+        createAssociatedTokenAccountIdempotentInstruction(claimant.publicKey, escrowTokens, escrow, distributorState.mint),
+    ];
 
     if (operator == null) {
         await program.methods.newClaimAndStake(amountUnlocked, amountLocked, proof).accounts({
             distributor,
             claimant: claimant.publicKey,
+            payer: claimant.publicKey,
             claimStatus,
             from: distributorState.tokenVault,
             systemProgram: web3.SystemProgram.programId,
@@ -232,13 +399,16 @@ export async function claimAndStake(
             voterProgram: LOCKED_VOTER_PROGRAM_ID,
             locker: distributorState.locker,
             escrow,
-            escrowTokens: getAssociatedTokenAddressSync(distributorState.mint, escrow, true),
-
-        }).rpc().catch(console.log).then(console.log);
+            escrowTokens,
+            feeReceiver: getFeeReceiverAccount(distributorState),
+            denylist: getDenylistAccount(distributor, distributorState),
+            bonusVault: getBonusVaultAccount(distributorState),
+        }).preInstructions(preInstructions).rpc().catch(console.log).then(console.log);
     } else {
         await program.methods.newClaimAndStake(amountUnlocked, amountLocked, proof).accounts({
             distributor,
             claimant: claimant.publicKey,
+            payer: claimant.publicKey,
             claimStatus,
             from: distributorState.tokenVault,
             systemProgram: web3.SystemProgram.programId,
@@ -247,8 +417,11 @@ export async function claimAndStake(
             voterProgram: LOCKED_VOTER_PROGRAM_ID,
             locker: distributorState.locker,
             escrow,
-            escrowTokens: getAssociatedTokenAddressSync(distributorState.mint, escrow, true),
-        }).signers([operator]).rpc().catch(console.log).then(console.log);
+            escrowTokens,
+            feeReceiver: getFeeReceiverAccount(distributorState),
+            denylist: getDenylistAccount(distributor, distributorState),
+            bonusVault: getBonusVaultAccount(distributorState),
+        }).preInstructions(preInstructions).signers([operator]).rpc().catch(console.log).then(console.log);
     }
 }
 
@@ -278,6 +451,9 @@ export async function claimLocked(
             to,
             tokenProgram: TOKEN_PROGRAM_ID,
             operator: null,
+            systemProgram: web3.SystemProgram.programId,
+            feeReceiver: getFeeReceiverAccount(distributorState),
+            denylist: getDenylistAccount(distributor, distributorState),
         }).rpc().catch(console.log).then(console.log);
     } else {
         await program.methods.claimLocked().accounts({
@@ -288,6 +464,9 @@ export async function claimLocked(
             to,
             tokenProgram: TOKEN_PROGRAM_ID,
             operator: operator.publicKey,
+            systemProgram: web3.SystemProgram.programId,
+            feeReceiver: getFeeReceiverAccount(distributorState),
+            denylist: getDenylistAccount(distributor, distributorState),
         }).signers([operator]).rpc().catch(console.log).then(console.log);
     }
 }
@@ -322,6 +501,9 @@ export async function claimLockedAndStake(
             locker: distributorState.locker,
             escrow,
             escrowTokens: getAssociatedTokenAddressSync(distributorState.mint, escrow, true),
+            systemProgram: web3.SystemProgram.programId,
+            feeReceiver: getFeeReceiverAccount(distributorState),
+            denylist: getDenylistAccount(distributor, distributorState),
         }).rpc().catch(console.log).then(console.log);
     } else {
         await program.methods.claimLockedAndStake().accounts({
@@ -335,6 +517,9 @@ export async function claimLockedAndStake(
             locker: distributorState.locker,
             escrow,
             escrowTokens: getAssociatedTokenAddressSync(distributorState.mint, escrow, true),
+            systemProgram: web3.SystemProgram.programId,
+            feeReceiver: getFeeReceiverAccount(distributorState),
+            denylist: getDenylistAccount(distributor, distributorState),
         }).signers([operator]).rpc().catch(console.log).then(console.log);
     }
 }
@@ -359,4 +544,387 @@ export async function clawBack(
         clawbackReceiver: distributorState.clawbackReceiver,
         tokenProgram: TOKEN_PROGRAM_ID,
     }).rpc().catch(console.log).then(console.log);
-}
\ No newline at end of file
+}
+
+
+export interface SetClaimCapParams {
+    admin: Keypair;
+    distributor: PublicKey;
+    claimCapType: number;
+    claimCap: BN;
+    claimWindowLength: number;
+}
+
+export async function setClaimCap(
+    params: SetClaimCapParams
+) {
+    let { admin, distributor, claimCapType, claimCap, claimWindowLength } = params;
+    const program = createDistributorProgram(new Wallet(admin));
+
+    await program.methods.setClaimCap(claimCapType, claimCap, claimWindowLength).accounts({
+        distributor,
+        admin: admin.publicKey,
+    }).rpc().catch(console.log).then(console.log);
+}
+
+
+export interface QueueActionParams {
+    admin: Keypair;
+    distributor: PublicKey;
+    actionType: number;
+    newPubkey: PublicKey;
+    newValue: BN;
+    newClawbackAccount?: PublicKey;
+    // only for set claim cap, newValue is then the cap
+    claimCapType?: number;
+    claimWindowLength?: number;
+}
+
+export async function queueAction(
+    params: QueueActionParams
+) {
+    let { admin, distributor, actionType, newPubkey, newValue, newClawbackAccount, claimCapType, claimWindowLength } = params;
+    const program = createDistributorProgram(new Wallet(admin));
+
+    let distributorState = await program.account.merkleDistributor.fetch(distributor);
+    let pendingAction = derivePendingAction(distributor, distributorState.numQueuedActions.toNumber());
+
+    await program.methods.queueAction(actionType, newPubkey, newValue, claimCapType ?? 0, claimWindowLength ?? 0).accounts({
+        distributor,
+        pendingAction,
+        newClawbackAccount: newClawbackAccount ?? null,
+        admin: admin.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+    }).rpc().catch(console.log).then(console.log);
+
+    return pendingAction;
+}
+
+export interface ExecuteActionParams {
+    payer: Keypair;
+    distributor: PublicKey;
+    pendingAction: PublicKey;
+}
+
+export async function executeAction(
+    params: ExecuteActionParams
+) {
+    let { payer, distributor, pendingAction } = params;
+    const program = createDistributorProgram(new Wallet(payer));
+
+    let pendingActionState = await program.account.pendingAction.fetch(pendingAction);
+    await program.methods.executeAction().accounts({
+        distributor,
+        pendingAction,
+        payer: pendingActionState.payer,
+        // set clawback receiver
+        newClawbackAccount: pendingActionState.actionType == 1 ? pendingActionState.newPubkey : null,
+    }).rpc().catch(console.log).then(console.log);
+}
+
+export interface CancelActionParams {
+    admin: Keypair;
+    distributor: PublicKey;
+    pendingAction: PublicKey;
+}
+
+export async function cancelAction(
+    params: CancelActionParams
+) {
+    let { admin, distributor, pendingAction } = params;
+    const program = createDistributorProgram(new Wallet(admin));
+
+    let pendingActionState = await program.account.pendingAction.fetch(pendingAction);
+    await program.methods.cancelAction().accounts({
+        distributor,
+        pendingAction,
+        payer: pendingActionState.payer,
+        admin: admin.publicKey,
+    }).rpc().catch(console.log).then(console.log);
+}
+
+export interface NewDenylistParams {
+    admin: Keypair;
+    distributor: PublicKey;
+}
+
+export async function newDenylist(
+    params: NewDenylistParams
+) {
+    let { admin, distributor } = params;
+    const program = createDistributorProgram(new Wallet(admin));
+
+    let denylist = deriveDenylist(distributor);
+    await program.methods.newDenylist().accounts({
+        distributor,
+        denylist,
+        admin: admin.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+    }).rpc().catch(console.log).then(console.log);
+
+    return denylist;
+}
+
+export interface DenylistClaimantParams {
+    admin: Keypair;
+    distributor: PublicKey;
+    claimant: PublicKey;
+}
+
+export async function addToDenylist(
+    params: DenylistClaimantParams
+) {
+    let { admin, distributor, claimant } = params;
+    const program = createDistributorProgram(new Wallet(admin));
+
+    await program.methods.addToDenylist(claimant).accounts({
+        distributor,
+        denylist: deriveDenylist(distributor),
+        admin: admin.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+    }).rpc().catch(console.log).then(console.log);
+}
+
+export async function removeFromDenylist(
+    params: DenylistClaimantParams
+) {
+    let { admin, distributor, claimant } = params;
+    const program = createDistributorProgram(new Wallet(admin));
+
+    await program.methods.removeFromDenylist(claimant).accounts({
+        distributor,
+        denylist: deriveDenylist(distributor),
+        admin: admin.publicKey,
+    }).rpc().catch(console.log).then(console.log);
+}
+
+export interface RevokeVestingParams {
+    admin: Keypair;
+    distributor: PublicKey;
+    claimant: PublicKey;
+}
+
+export async function revokeVesting(
+    params: RevokeVestingParams
+) {
+    let { admin, distributor, claimant } = params;
+    const program = createDistributorProgram(new Wallet(admin));
+
+    let distributorState = await program.account.merkleDistributor.fetch(distributor);
+    await program.methods.revokeVesting().accounts({
+        distributor,
+        claimStatus: deriveClaimStatus(distributor, claimant),
+        from: distributorState.tokenVault,
+        clawbackReceiver: distributorState.clawbackReceiver,
+        admin: admin.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+    }).rpc().catch(console.log).then(console.log);
+}
+
+export interface MigrateDistributorParams {
+    admin: Keypair;
+    distributor: PublicKey;
+}
+
+export async function migrateDistributor(
+    params: MigrateDistributorParams
+) {
+    let { admin, distributor } = params;
+    const program = createDistributorProgram(new Wallet(admin));
+
+    await program.methods.migrateDistributor().accounts({
+        distributor,
+        admin: admin.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+    }).rpc().catch(console.log).then(console.log);
+}
+
+export interface NewBonusVaultParams {
+    admin: Keypair;
+    distributor: PublicKey;
+}
+
+export async function newBonusVault(
+    params: NewBonusVaultParams
+) {
+    let { admin, distributor } = params;
+    const program = createDistributorProgram(new Wallet(admin));
+
+    let distributorState = await program.account.merkleDistributor.fetch(distributor);
+    let bonusVault = deriveBonusVault(distributor);
+    await program.methods.newBonusVault().accounts({
+        distributor,
+        bonusVault,
+        mint: distributorState.mint,
+        admin: admin.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+    }).rpc().catch(console.log).then(console.log);
+
+    return bonusVault;
+}
+
+export async function clawbackBonus(
+    params: ClawbackParams
+) {
+    let { payer, distributor } = params;
+    const program = createDistributorProgram(new Wallet(payer));
+
+    let distributorState = await program.account.merkleDistributor.fetch(distributor);
+
+    await program.methods.clawbackBonus().accounts({
+        distributor,
+        bonusVault: distributorState.bonusVault,
+        clawbackReceiver: distributorState.clawbackReceiver,
+        tokenProgram: TOKEN_PROGRAM_ID,
+    }).rpc().catch(console.log).then(console.log);
+}
+
+export interface SetRelayerParams {
+    admin: Keypair;
+    distributor: PublicKey;
+    relayer: PublicKey;
+}
+
+export async function setRelayer(
+    params: SetRelayerParams
+) {
+    let { admin, distributor, relayer } = params;
+    const program = createDistributorProgram(new Wallet(admin));
+
+    await program.methods.setRelayer(relayer).accounts({
+        distributor,
+        admin: admin.publicKey,
+    }).rpc().catch(console.log).then(console.log);
+}
+
+export interface CloseClaimStatusParams {
+    admin: Keypair;
+    distributor: PublicKey;
+    claimant: PublicKey;
+}
+
+export async function closeClaimStatus(
+    params: CloseClaimStatusParams
+) {
+    let { admin, distributor, claimant } = params;
+    const program = createDistributorProgram(new Wallet(admin));
+
+    let claimStatus = deriveClaimStatus(distributor, claimant);
+    let claimStatusState = await program.account.claimStatus.fetch(claimStatus);
+    let rentPaidByRelayer = claimStatusState.rentPayerStored == 1 && !claimStatusState.rentPayer.equals(claimant);
+    await program.methods.closeClaimStatus().accounts({
+        claimStatus,
+        distributor,
+        claimant,
+        rentPayer: rentPaidByRelayer ? claimStatusState.rentPayer : null,
+        admin: admin.publicKey,
+    }).rpc().catch(console.log).then(console.log);
+}
+
+export const CLAIM_RECORD_TREE_SPACE = 51776;
+
+// account size of a claim record tree, the canopy is stored after the tree
+export function getClaimRecordTreeSpace(canopyDepth: number): number {
+    return CLAIM_RECORD_TREE_SPACE + ((1 << (canopyDepth + 1)) - 2) * 32;
+}
+
+export interface NewClaimRecordTreeParams {
+    admin: Keypair;
+    distributor: PublicKey;
+    maxDepth: number;
+    canopyDepth: number;
+}
+
+export async function newClaimRecordTree(
+    params: NewClaimRecordTreeParams
+) {
+    let { admin, distributor, maxDepth, canopyDepth } = params;
+    const program = createDistributorProgram(new Wallet(admin));
+
+    let claimRecordTree = Keypair.generate();
+    let space = getClaimRecordTreeSpace(canopyDepth);
+    let lamports = await program.provider.connection.getMinimumBalanceForRentExemption(space);
+    await program.methods.newClaimRecordTree(maxDepth, canopyDepth).accounts({
+        distributor,
+        claimRecordTree: claimRecordTree.publicKey,
+        admin: admin.publicKey,
+    }).preInstructions([
+        web3.SystemProgram.createAccount({
+            fromPubkey: admin.publicKey,
+            newAccountPubkey: claimRecordTree.publicKey,
+            lamports,
+            space,
+            programId: MERKLE_DISTRIBUTOR_PROGRAM_ID,
+        }),
+    ]).signers([claimRecordTree]).rpc().catch(console.log).then(console.log);
+
+    return claimRecordTree.publicKey;
+}
+
+export interface ClaimCompressedParams {
+    claimant: Keypair;
+    distributor: PublicKey,
+    amountUnlocked: BN;
+    amountLocked: BN;
+    root: Buffer;
+    proof: Array<number>[];
+}
+
+export interface NewClaimCompressedParams extends ClaimCompressedParams {
+    // proof of the leaf in the airdrop merkle tree, the record index is its path
+    airdropProof: Array<number>[];
+}
+
+export async function claimCompressed(
+    params: NewClaimCompressedParams
+) {
+    let { claimant, distributor, amountUnlocked, amountLocked, root, proof, airdropProof } = params;
+    const program = createDistributorProgram(new Wallet(claimant));
+
+    let distributorState = await program.account.merkleDistributor.fetch(distributor);
+    let to = await getOrCreateAssociatedTokenAccountWrap(program.provider.connection, claimant, distributorState.mint, claimant.publicKey);
+
+    await program.methods.newClaimCompressed(amountUnlocked, amountLocked, Array.from(new Uint8Array(root)), proof, airdropProof).accounts({
+        distributor,
+        claimRecordTree: distributorState.claimRecordTree,
+        from: distributorState.tokenVault,
+        to,
+        claimant: claimant.publicKey,
+        operator: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: web3.SystemProgram.programId,
+        feeReceiver: getFeeReceiverAccount(distributorState),
+        denylist: getDenylistAccount(distributor, distributorState),
+        bonusVault: getBonusVaultAccount(distributorState),
+    }).rpc().catch(console.log).then(console.log);
+}
+
+export interface ClaimLockedCompressedParams extends ClaimCompressedParams {
+    index: number;
+    lockedAmountWithdrawn: BN;
+    bonusAmount: BN;
+}
+
+export async function claimLockedCompressed(
+    params: ClaimLockedCompressedParams
+) {
+    let { claimant, distributor, amountUnlocked, amountLocked, lockedAmountWithdrawn, bonusAmount, root, index, proof } = params;
+    const program = createDistributorProgram(new Wallet(claimant));
+
+    let distributorState = await program.account.merkleDistributor.fetch(distributor);
+    let to = await getOrCreateAssociatedTokenAccountWrap(program.provider.connection, claimant, distributorState.mint, claimant.publicKey);
+
+    await program.methods.claimLockedCompressed(amountUnlocked, amountLocked, lockedAmountWithdrawn, bonusAmount, Array.from(new Uint8Array(root)), index, proof).accounts({
+        distributor,
+        claimRecordTree: distributorState.claimRecordTree,
+        from: distributorState.tokenVault,
+        to,
+        claimant: claimant.publicKey,
+        operator: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: web3.SystemProgram.programId,
+        feeReceiver: getFeeReceiverAccount(distributorState),
+        denylist: getDenylistAccount(distributor, distributorState),
+    }).rpc().catch(console.log).then(console.log);
+}
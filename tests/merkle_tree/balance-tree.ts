@@ -1,16 +1,18 @@
 import { BN, web3 } from "@coral-xyz/anchor";
-import { sha256 } from "js-sha256";
 
-import { MerkleTree } from "./merkle-tree";
+import { getHashFn, MerkleTree } from "./merkle-tree";
 
 
 export class BalanceTree {
   private readonly _tree: MerkleTree;
-  constructor(balances: { account: web3.PublicKey; amountUnlocked: BN, amountLocked: BN }[]) {
+  private readonly _hashType: number;
+  constructor(balances: { account: web3.PublicKey; amountUnlocked: BN, amountLocked: BN }[], hashType: number = 0) {
+    this._hashType = hashType;
     this._tree = new MerkleTree(
       balances.map(({ account, amountUnlocked, amountLocked }, index) => {
-        return BalanceTree.toNode(account, amountUnlocked, amountLocked);
-      })
+        return BalanceTree.toNode(account, amountUnlocked, amountLocked, hashType);
+      }),
+      hashType
     );
   }
 
@@ -19,31 +21,43 @@ export class BalanceTree {
     amountUnlocked: BN,
     amountLocked: BN,
     proof: Buffer[],
-    root: Buffer
+    root: Buffer,
+    hashType: number = 0
   ): boolean {
-    let pair = BalanceTree.toNode(account, amountUnlocked, amountLocked);
+    let pair = BalanceTree.toNode(account, amountUnlocked, amountLocked, hashType);
     for (const item of proof) {
-      pair = MerkleTree.combinedHash(pair, item);
+      pair = MerkleTree.combinedHash(pair, item, hashType);
     }
 
     return pair.equals(root);
   }
 
-  // keccak256(abi.encode(index, account, amount))
-  static toNode(account: web3.PublicKey, amountUnlocked: BN, amountLocked: BN): Buffer {
+  // sha256: sha256(0 || sha256(account || amountUnlocked || amountLocked)), amounts in little endian
+  // keccak256: keccak256(keccak256(abi.encode(account, amountUnlocked, amountLocked))), the leaf of
+  // an OpenZeppelin StandardMerkleTree of (bytes32, uint64, uint64)
+  static toNode(account: web3.PublicKey, amountUnlocked: BN, amountLocked: BN, hashType: number = 0): Buffer {
+    const hashFn = getHashFn(hashType);
+    if (hashType == 1) {
+      const encoded = Buffer.concat([
+        account.toBuffer(),
+        new BN(amountUnlocked).toArrayLike(Buffer, "be", 32),
+        new BN(amountLocked).toArrayLike(Buffer, "be", 32),
+      ]);
+      return hashFn(hashFn(encoded));
+    }
     const buf = Buffer.concat([
       account.toBuffer(),
       new BN(amountUnlocked).toArrayLike(Buffer, "le", 8),
       new BN(amountLocked).toArrayLike(Buffer, "le", 8),
     ]);
 
-    const hashedBuff = Buffer.from(sha256(buf), "hex");
+    const hashedBuff = hashFn(buf);
     const bufWithPrefix = Buffer.concat([
       Buffer.from([0]),
       hashedBuff
     ]);
 
-    return Buffer.from(sha256(bufWithPrefix), "hex");
+    return hashFn(bufWithPrefix);
   }
 
   getHexRoot(): string {
@@ -52,7 +66,7 @@ export class BalanceTree {
 
   // returns the hex bytes32 values of the proof
   getHexProof(account: web3.PublicKey, amountUnlocked: BN, amountLocked: BN): string[] {
-    return this._tree.getHexProof(BalanceTree.toNode(account, amountUnlocked, amountLocked));
+    return this._tree.getHexProof(BalanceTree.toNode(account, amountUnlocked, amountLocked, this._hashType));
   }
 
   getRoot(): Buffer {
@@ -60,6 +74,17 @@ export class BalanceTree {
   }
 
   getProof(account: web3.PublicKey, amountUnlocked: BN, amountLocked: BN): Buffer[] {
-    return this._tree.getProof(BalanceTree.toNode(account, amountUnlocked, amountLocked));
+    return this._tree.getProof(BalanceTree.toNode(account, amountUnlocked, amountLocked, this._hashType));
+  }
+
+  // multiproof of the balances, order holds the indices of the balances in the order of the tree,
+  // which is the order of the leaves of a batch claim
+  getMultiProof(balances: { account: web3.PublicKey; amountUnlocked: BN, amountLocked: BN }[]): { order: number[]; proof: Buffer[]; proofFlags: boolean[] } {
+    const nodes = balances.map(({ account, amountUnlocked, amountLocked }) => {
+      return BalanceTree.toNode(account, amountUnlocked, amountLocked, this._hashType);
+    });
+    const { leaves, proof, proofFlags } = this._tree.getMultiProof(nodes);
+    const order = leaves.map((leaf) => nodes.findIndex((node) => node.equals(leaf)));
+    return { order, proof, proofFlags };
   }
 }
@@ -1,2 +1,3 @@
 export * from "./balance-tree";
 export * from "./merkle-tree";
+export * from "./record-tree";
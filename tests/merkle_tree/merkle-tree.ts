@@ -1,6 +1,22 @@
 import { sha256 } from "js-sha256";
+import { keccak256 } from "js-sha3";
 import invariant from "tiny-invariant";
 
+export type HashFn = (data: Buffer) => Buffer;
+
+export const sha256Hash: HashFn = (data) => Buffer.from(sha256(data), "hex");
+export const keccak256Hash: HashFn = (data) => Buffer.from(keccak256(data), "hex");
+
+// hash function of the hash type of a distributor, 0 is sha256 and 1 is keccak256
+export function getHashFn(hashType: number): HashFn {
+  return hashType == 1 ? keccak256Hash : sha256Hash;
+}
+
+// prefix of the intermediate nodes, keccak256 trees have none to match OpenZeppelin MerkleProof
+function getIntermediatePrefix(hashType: number): Buffer {
+  return hashType == 1 ? Buffer.alloc(0) : Buffer.from([1]);
+}
+
 function getPairElement(idx: number, layer: Buffer[]): Buffer | null {
   const pairIdx = idx % 2 === 0 ? idx + 1 : idx - 1;
 
@@ -27,8 +43,8 @@ function bufArrToHexArr(arr: Buffer[]): string[] {
   return arr.map((el) => "0x" + el.toString("hex"));
 }
 
-function sortAndConcat(...args: Buffer[]): Buffer {
-  return Buffer.concat([Buffer.from([1]), Buffer.concat([...args].sort(Buffer.compare.bind(null)))]);
+function sortAndConcat(prefix: Buffer, ...args: Buffer[]): Buffer {
+  return Buffer.concat([prefix, Buffer.concat([...args].sort(Buffer.compare.bind(null)))]);
 }
 
 export class MerkleTree {
@@ -37,8 +53,10 @@ export class MerkleTree {
     [hexElement: string]: number;
   };
   private readonly _layers: Buffer[][];
+  private readonly _hashType: number;
 
-  constructor(elements: Buffer[]) {
+  constructor(elements: Buffer[], hashType: number = 0) {
+    this._hashType = hashType;
     this._elements = [...elements];
     // Sort elements
     this._elements.sort(Buffer.compare.bind(null));
@@ -79,14 +97,14 @@ export class MerkleTree {
       if (idx % 2 === 0) {
         // Hash the current element with its pair element
         const pairEl = arr[idx + 1];
-        layer.push(MerkleTree.combinedHash(el, pairEl));
+        layer.push(MerkleTree.combinedHash(el, pairEl, this._hashType));
       }
 
       return layer;
     }, []);
   }
 
-  static combinedHash(first: Buffer, second: Buffer | undefined): Buffer {
+  static combinedHash(first: Buffer, second: Buffer | undefined, hashType: number = 0): Buffer {
     if (!first) {
       invariant(second, "second element of pair must exist");
       return second;
@@ -96,7 +114,7 @@ export class MerkleTree {
       return first;
     }
 
-    return Buffer.from(sha256(sortAndConcat(first, second)), "hex");
+    return getHashFn(hashType)(sortAndConcat(getIntermediatePrefix(hashType), first, second));
   }
 
   getRoot(): Buffer {
@@ -130,6 +148,45 @@ export class MerkleTree {
     }, []);
   }
 
+  // multiproof of the elements, verified by verify_multiproof of jito-merkle-verify, with the
+  // elements in the order of the tree. The unpaired last node of a layer is passed up without
+  // hashing, so it can't be one of the proven nodes
+  getMultiProof(els: Buffer[]): { leaves: Buffer[]; proof: Buffer[]; proofFlags: boolean[] } {
+    let indices = [...new Set(els.map((el) => {
+      const idx = this._bufferElementPositionIndex[el.toString("hex")];
+      if (typeof idx !== "number") {
+        throw new Error("Element does not exist in Merkle tree");
+      }
+      return idx;
+    }))].sort((a, b) => a - b);
+    const leaves = indices.map((idx) => this._elements[idx]);
+
+    const proof: Buffer[] = [];
+    const proofFlags: boolean[] = [];
+    for (const layer of this._layers.slice(0, -1)) {
+      const parentIndices: number[] = [];
+      for (let i = 0; i < indices.length; i++) {
+        const idx = indices[i];
+        const pairElement = getPairElement(idx, layer);
+        if (!pairElement) {
+          throw new Error("Unpaired node can't be in a multiproof");
+        }
+        if (indices[i + 1] === idx + 1 && idx % 2 === 0) {
+          // both nodes are known, no proof element needed
+          proofFlags.push(true);
+          i++;
+        } else {
+          proofFlags.push(false);
+          proof.push(pairElement);
+        }
+        parentIndices.push(Math.floor(idx / 2));
+      }
+      indices = parentIndices;
+    }
+
+    return { leaves, proof, proofFlags };
+  }
+
   getHexProof(el: Buffer): string[] {
     const proof = this.getProof(el);
 
@@ -0,0 +1,90 @@
+import { BN, web3 } from "@coral-xyz/anchor";
+import { sha256 } from "js-sha256";
+import { MerkleTree } from "./merkle-tree";
+
+function hashIntermediate(left: Buffer, right: Buffer): Buffer {
+  return Buffer.from(sha256(Buffer.concat([Buffer.from([1]), left, right])), "hex");
+}
+
+// Positional tree of the claim records of a compressed distributor, padded with zero leaves
+// The tree starts empty, the record of a claimant is written at its record index
+export class ClaimRecordTree {
+  private readonly _layers: Buffer[][];
+  private readonly _emptyNodes: Buffer[];
+
+  constructor(leaves: Buffer[], maxDepth: number) {
+    this._emptyNodes = [Buffer.alloc(32)];
+    this._layers = [[...leaves]];
+    for (let level = 0; level < maxDepth; level++) {
+      const emptyNode = this._emptyNodes[level];
+      const layer = this._layers[level];
+      const nextLayer: Buffer[] = [];
+      for (let i = 0; i < layer.length; i += 2) {
+        nextLayer.push(hashIntermediate(layer[i], layer[i + 1] ?? emptyNode));
+      }
+      this._emptyNodes.push(hashIntermediate(emptyNode, emptyNode));
+      this._layers.push(nextLayer);
+    }
+  }
+
+  static toRecordNode(
+    account: web3.PublicKey,
+    amountUnlocked: BN,
+    amountLocked: BN,
+    lockedAmountWithdrawn: BN,
+    bonusAmount: BN
+  ): Buffer {
+    const buf = Buffer.concat([
+      Buffer.from([2]),
+      account.toBuffer(),
+      new BN(amountUnlocked).toArrayLike(Buffer, "le", 8),
+      new BN(amountLocked).toArrayLike(Buffer, "le", 8),
+      new BN(lockedAmountWithdrawn).toArrayLike(Buffer, "le", 8),
+      new BN(bonusAmount).toArrayLike(Buffer, "le", 8),
+    ]);
+    return Buffer.from(sha256(buf), "hex");
+  }
+
+  // record index of an airdrop leaf, the path of the leaf in the airdrop tree under a leading 1:
+  // bit i is set when the node of level i is hashed on the right
+  static getRecordIndex(leaf: Buffer, airdropProof: Buffer[], hashType: number = 0): number {
+    let index = 1 << airdropProof.length;
+    let node = leaf;
+    airdropProof.forEach((sibling, level) => {
+      if (Buffer.compare(node, sibling) > 0) {
+        index |= 1 << level;
+      }
+      node = MerkleTree.combinedHash(node, sibling, hashType);
+    });
+    return index;
+  }
+
+  getRoot(): Buffer {
+    return this._layers[this._layers.length - 1][0] ?? this._emptyNodes[this._emptyNodes.length - 1];
+  }
+
+  getProof(index: number): Buffer[] {
+    const proof: Buffer[] = [];
+    for (let level = 0; level < this._layers.length - 1; level++) {
+      const sibling = (index >> level) ^ 1;
+      proof.push(this._layers[level][sibling] ?? this._emptyNodes[level]);
+    }
+    return proof;
+  }
+
+  // proof passed to a claim, the program reads the top canopyDepth levels from its canopy
+  getClaimProof(index: number, canopyDepth: number): Buffer[] {
+    return this.getProof(index).slice(0, this._layers.length - 1 - canopyDepth);
+  }
+
+  // replaces the leaf and recomputes its path, as the program does on claim
+  update(index: number, leaf: Buffer) {
+    this._layers[0][index] = leaf;
+    for (let level = 0; level < this._layers.length - 1; level++) {
+      const i = index >> (level + 1);
+      const left = this._layers[level][2 * i] ?? this._emptyNodes[level];
+      const right = this._layers[level][2 * i + 1] ?? this._emptyNodes[level];
+      this._layers[level + 1][i] = hashIntermediate(left, right);
+    }
+  }
+}
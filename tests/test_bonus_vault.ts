@@ -0,0 +1,167 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BalanceTree } from "./merkle_tree";
+import { Wallet, web3 } from "@coral-xyz/anchor";
+import { ADMIN, claim, clawbackBonus, createDistributorProgram, createNewDistributor, deriveClaimStatus, newBonusVault } from "./merkle_distributor";
+import { createAndFundWallet, getBlockTime, getOrCreateAssociatedTokenAccountWrap, getRandomInt, sleep } from "./common";
+import { BN } from "bn.js";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { createMint, getAccount, getAssociatedTokenAddressSync, mintTo, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { expect } from "chai";
+const provider = anchor.AnchorProvider.env();
+
+describe("Bonus vault", () => {
+    let admin = Keypair.generate();
+    let tree: BalanceTree;
+    let maxNumNodes = 2;
+    let whitelistedKPs: web3.Keypair[] = [];
+    let amountUnlockedArr: anchor.BN[] = [];
+    let amountLockedArr: anchor.BN[] = [];
+    let totalClaim = new BN(0);
+    let mint: PublicKey;
+    before(async () => {
+        await createAndFundWallet(provider.connection, ADMIN);
+        await createAndFundWallet(provider.connection, admin);
+
+        for (let i = 0; i < maxNumNodes; i++) {
+            const result = await createAndFundWallet(provider.connection);
+            whitelistedKPs.push(result.keypair);
+            let amountUnlocked = new BN(getRandomInt(1000, 20000));
+            // no locked amount, so claimants only receive unlocked amount and bonus
+            let amountLocked = new BN(0);
+
+            amountUnlockedArr.push(amountUnlocked);
+            amountLockedArr.push(amountLocked);
+            totalClaim = totalClaim.add(amountUnlocked).add(amountLocked);
+        }
+
+        tree = new BalanceTree(
+            whitelistedKPs.map((kp, index) => {
+                return { account: kp.publicKey, amountUnlocked: amountUnlockedArr[index], amountLocked: amountLockedArr[index] };
+            })
+        );
+
+        mint = await createMint(
+            provider.connection,
+            ADMIN,
+            ADMIN.publicKey,
+            null,
+            6,
+            web3.Keypair.generate(),
+            {
+                commitment: "confirmed",
+            },
+            TOKEN_PROGRAM_ID
+        );
+    })
+    it("Pay bonus from the bonus vault and clawback it independently", async () => {
+        let currentTime = await getBlockTime(provider.connection);
+        let startVestingTs = new BN(currentTime + 3);
+        let endVestingTs = new BN(currentTime + 6);
+        let clawbackStartTs = new BN(currentTime + 8);
+        let activationType = 1; // timestamp
+        let activationPoint = new BN(currentTime + 2);
+        // bonus equals the unlocked amount of each claimant
+        let totalBonus = totalClaim;
+
+        let clawbackReceiver = await getOrCreateAssociatedTokenAccountWrap(provider.connection, ADMIN, mint, ADMIN.publicKey);
+        let { distributor, tokenVault } = await createNewDistributor({
+            admin,
+            version: 0,
+            root: tree.getRoot(),
+            totalClaim,
+            maxNumNodes: new BN(maxNumNodes),
+            startVestingTs,
+            endVestingTs,
+            clawbackStartTs,
+            activationPoint,
+            activationType,
+            closable: false,
+            totalBonus,
+            bonusVestingDuration: new BN(0),
+            claimType: 0,
+            operator: web3.SystemProgram.programId,
+            locker: web3.SystemProgram.programId,
+            mint,
+            clawbackReceiver,
+        });
+        let bonusVault = await newBonusVault({ admin, distributor });
+        await mintTo(provider.connection, ADMIN, mint, tokenVault, ADMIN, totalClaim.toNumber());
+        await mintTo(provider.connection, ADMIN, mint, bonusVault, ADMIN, totalBonus.toNumber());
+
+        const program = createDistributorProgram(new Wallet(admin));
+        let distributorState = await program.account.merkleDistributor.fetch(distributor);
+        expect(distributorState.bonusVault.toString()).eq(bonusVault.toString());
+
+        while (true) {
+            const currentTime = await getBlockTime(provider.connection);
+            if (currentTime > activationPoint.toNumber()) {
+                break;
+            } else {
+                await sleep(1000);
+                console.log("Wait until activationPoint");
+            }
+        }
+
+        let getProof = (i: number) => {
+            var proofBuffers = tree.getProof(
+                whitelistedKPs[i].publicKey,
+                amountUnlockedArr[i],
+                amountLockedArr[i]
+            );
+            let proof = [];
+            proofBuffers.forEach(function (value) {
+                proof.push(Array.from(new Uint8Array(value)));
+            });
+            return proof;
+        };
+
+        // bonus is moved from the bonus vault
+        await claim({
+            distributor,
+            claimant: whitelistedKPs[0],
+            amountUnlocked: amountUnlockedArr[0],
+            amountLocked: amountLockedArr[0],
+            proof: getProof(0),
+        });
+        let claimStatus = await program.account.claimStatus.fetch(deriveClaimStatus(distributor, whitelistedKPs[0].publicKey));
+        expect(claimStatus.bonusAmount.toString()).eq(amountUnlockedArr[0].toString());
+        let claimantToken = await getAccount(provider.connection, getAssociatedTokenAddressSync(mint, whitelistedKPs[0].publicKey));
+        expect(claimantToken.amount.toString()).eq(amountUnlockedArr[0].mul(new BN(2)).toString());
+        let bonusVaultState = await getAccount(provider.connection, bonusVault);
+        expect(bonusVaultState.amount.toString()).eq(totalBonus.sub(amountUnlockedArr[0]).toString());
+        let tokenVaultState = await getAccount(provider.connection, tokenVault);
+        expect(tokenVaultState.amount.toString()).eq(totalClaim.sub(amountUnlockedArr[0]).toString());
+
+        while (true) {
+            const currentTime = await getBlockTime(provider.connection);
+            if (currentTime > clawbackStartTs.toNumber()) {
+                break;
+            } else {
+                await sleep(1000);
+                console.log("Wait until clawbackStartTs");
+            }
+        }
+
+        let clawbackReceiverBefore = await getAccount(provider.connection, clawbackReceiver);
+        await clawbackBonus({ payer: whitelistedKPs[1], distributor });
+        let clawbackReceiverAfter = await getAccount(provider.connection, clawbackReceiver);
+        expect((clawbackReceiverAfter.amount - clawbackReceiverBefore.amount).toString()).eq(totalBonus.sub(amountUnlockedArr[0]).toString());
+        bonusVaultState = await getAccount(provider.connection, bonusVault);
+        expect(bonusVaultState.amount.toString()).eq("0");
+        distributorState = await program.account.merkleDistributor.fetch(distributor);
+        expect(distributorState.bonusClawedBack).eq(1);
+
+        // later claims get no bonus
+        await claim({
+            distributor,
+            claimant: whitelistedKPs[1],
+            amountUnlocked: amountUnlockedArr[1],
+            amountLocked: amountLockedArr[1],
+            proof: getProof(1),
+        });
+        claimStatus = await program.account.claimStatus.fetch(deriveClaimStatus(distributor, whitelistedKPs[1].publicKey));
+        expect(claimStatus.bonusAmount.toString()).eq("0");
+        claimantToken = await getAccount(provider.connection, getAssociatedTokenAddressSync(mint, whitelistedKPs[1].publicKey));
+        expect(claimantToken.amount.toString()).eq(amountUnlockedArr[1].toString());
+    })
+})
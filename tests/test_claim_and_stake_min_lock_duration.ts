@@ -0,0 +1,186 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BalanceTree } from "./merkle_tree";
+import { web3 } from "@coral-xyz/anchor";
+import { ADMIN, claimAndStake, claimLockedAndStake, createNewDistributor } from "./merkle_distributor";
+import { createAndFundWallet, getBlockTime, getOrCreateAssociatedTokenAccountWrap, getRandomInt, sleep } from "./common";
+import { BN } from "bn.js";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { createMint, mintTo, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { createLockedVoterProgram, createNewEscrow, setupLocker } from "./locked_voter/setup";
+import { expect } from "chai";
+const provider = anchor.AnchorProvider.env();
+
+describe("Claim and stake with min lock duration", () => {
+    let admin = Keypair.generate();
+    let tree: BalanceTree;
+    let maxNumNodes = 3;
+    let whitelistedKPs: web3.Keypair[] = [];
+    let amountUnlockedArr: anchor.BN[] = [];
+    let amountLockedArr: anchor.BN[] = [];
+    let totalClaim = new BN(0);
+    let mint: PublicKey;
+    let locker: PublicKey;
+    let escrows: PublicKey[] = [];
+    let minLockDuration = 100;
+    before(async () => {
+        await createAndFundWallet(provider.connection, ADMIN);
+        await createAndFundWallet(provider.connection, admin);
+
+        for (let i = 0; i < maxNumNodes; i++) {
+            const result = await createAndFundWallet(provider.connection);
+            whitelistedKPs.push(result.keypair);
+            let amountUnlocked = new BN(getRandomInt(1000, 20000));
+            let amountLocked = new BN(getRandomInt(1000, 20000));
+
+            amountUnlockedArr.push(amountUnlocked);
+            amountLockedArr.push(amountLocked);
+            totalClaim = totalClaim.add(amountUnlocked).add(amountLocked);
+        }
+
+        tree = new BalanceTree(
+            whitelistedKPs.map((kp, index) => {
+                return { account: kp.publicKey, amountUnlocked: amountUnlockedArr[index], amountLocked: amountLockedArr[index] };
+            })
+        );
+
+        mint = await createMint(
+            provider.connection,
+            ADMIN,
+            ADMIN.publicKey,
+            null,
+            6,
+            web3.Keypair.generate(),
+            {
+                commitment: "confirmed",
+            },
+            TOKEN_PROGRAM_ID
+        );
+
+        locker = await setupLocker({
+            payer: ADMIN,
+            tokenMint: mint,
+            maxStakeVoteMultiplier: 1,
+            minStakeDuration: new BN(10),
+            maxStakeDuration: new BN(10000),
+            proposalActivationMinVotes: new BN(100)
+        });
+
+        // escrows are not max lock, each claimant owns its escrow
+        for (let i = 0; i < maxNumNodes; i++) {
+            escrows.push(await createNewEscrow({
+                locker,
+                escrowOwner: whitelistedKPs[i],
+            }));
+        }
+    })
+    it("Extend escrow lock when claim and stake", async () => {
+        let currentTime = await getBlockTime(provider.connection);
+        let startVestingTs = new BN(currentTime + 3);
+        let endVestingTs = new BN(currentTime + 6);
+        let clawbackStartTs = new BN(currentTime + 7);
+        let activationType = 1; // timestamp
+        let activationPoint = new BN(currentTime + 2);
+
+        let clawbackReceiver = await getOrCreateAssociatedTokenAccountWrap(provider.connection, ADMIN, mint, ADMIN.publicKey);
+
+        // the locker rejects stake durations above its max stake duration
+        let { distributor: invalidDistributor } = await createNewDistributor({
+            admin,
+            version: 1,
+            root: tree.getRoot(),
+            totalClaim,
+            maxNumNodes: new BN(maxNumNodes),
+            startVestingTs,
+            endVestingTs,
+            clawbackStartTs,
+            activationPoint,
+            activationType,
+            closable: false,
+            totalBonus: new BN(0),
+            bonusVestingDuration: new BN(0),
+            claimType: 2,
+            operator: web3.SystemProgram.programId,
+            locker,
+            mint,
+            clawbackReceiver,
+            minLockDuration: 20000,
+        });
+        expect(await provider.connection.getAccountInfo(invalidDistributor)).to.be.null;
+
+        let { distributor, tokenVault } = await createNewDistributor({
+            admin,
+            version: 0,
+            root: tree.getRoot(),
+            totalClaim,
+            maxNumNodes: new BN(maxNumNodes),
+            startVestingTs,
+            endVestingTs,
+            clawbackStartTs,
+            activationPoint,
+            activationType,
+            closable: false,
+            totalBonus: new BN(0),
+            bonusVestingDuration: new BN(0),
+            claimType: 2,
+            operator: web3.SystemProgram.programId,
+            locker,
+            mint,
+            clawbackReceiver,
+            minLockDuration,
+        });
+        await mintTo(provider.connection, ADMIN, mint, tokenVault, ADMIN, totalClaim.toNumber());
+
+        while (true) {
+            const currentTime = await getBlockTime(provider.connection);
+            if (currentTime > activationPoint.toNumber()) {
+                break;
+            } else {
+                await sleep(1000);
+                console.log("Wait until activationPoint");
+            }
+        }
+
+        const voterProgram = createLockedVoterProgram(new anchor.Wallet(ADMIN));
+        for (let i = 0; i < maxNumNodes; i++) {
+            var proofBuffers = tree.getProof(
+                whitelistedKPs[i].publicKey,
+                amountUnlockedArr[i],
+                amountLockedArr[i]
+            );
+            let proof = [];
+            proofBuffers.forEach(function (value) {
+                proof.push(Array.from(new Uint8Array(value)));
+            });
+            await claimAndStake({
+                distributor,
+                claimant: whitelistedKPs[i],
+                amountUnlocked: amountUnlockedArr[i],
+                amountLocked: amountLockedArr[i],
+                proof,
+                escrow: escrows[i],
+            })
+
+            const escrowState = await voterProgram.account.escrow.fetch(escrows[i]);
+            const blockTime = await getBlockTime(provider.connection);
+            // extended to min_lock_duration from now, not by it
+            expect(escrowState.escrowEndsAt.toNumber()).to.be.gte(blockTime + minLockDuration - 5).and.lte(blockTime + minLockDuration);
+        }
+
+        while (true) {
+            const currentTime = await getBlockTime(provider.connection);
+            if (currentTime > endVestingTs.toNumber()) {
+                break;
+            } else {
+                await sleep(1000);
+                console.log("Wait until endVestingTs");
+            }
+        }
+        for (let i = 0; i < maxNumNodes; i++) {
+            await claimLockedAndStake({
+                distributor,
+                claimant: whitelistedKPs[i],
+                escrow: escrows[i],
+            })
+        }
+    })
+})
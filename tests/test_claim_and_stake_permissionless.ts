@@ -6,7 +6,8 @@ import { createAndFundWallet, getBlockTime, getOrCreateAssociatedTokenAccountWra
 import { BN } from "bn.js";
 import { Keypair, PublicKey } from "@solana/web3.js";
 import { createMint, mintTo, TOKEN_PROGRAM_ID } from "@solana/spl-token";
-import { createNewEscrowWithMaxLock, setupLocker } from "./locked_voter/setup";
+import { createLockedVoterProgram, createNewEscrowWithMaxLock, deriveEscrow, setupLocker } from "./locked_voter/setup";
+import { expect } from "chai";
 const provider = anchor.AnchorProvider.env();
 
 describe("Claim and stake permissionless", () => {
@@ -144,6 +145,31 @@ describe("Claim and stake permissionless", () => {
             })
         }
 
+        console.log("claim and stake with escrow creation")
+        let lastIndex = maxNumNodes - 1;
+        let newEscrow = deriveEscrow(locker, whitelistedKPs[lastIndex].publicKey);
+        var proofBuffers = tree.getProof(
+            whitelistedKPs[lastIndex].publicKey,
+            amountUnlockedArr[lastIndex],
+            amountLockedArr[lastIndex]
+        );
+        let proof = [];
+        proofBuffers.forEach(function (value) {
+            proof.push(Array.from(new Uint8Array(value)));
+        });
+        await claimAndStake({
+            distributor,
+            claimant: whitelistedKPs[lastIndex],
+            amountUnlocked: amountUnlockedArr[lastIndex],
+            amountLocked: amountLockedArr[lastIndex],
+            proof,
+            escrow: newEscrow,
+        })
+        const voterProgram = createLockedVoterProgram(new anchor.Wallet(ADMIN));
+        const newEscrowState = await voterProgram.account.escrow.fetch(newEscrow);
+        expect(newEscrowState.isMaxLock).to.be.true;
+        expect(newEscrowState.owner.toBase58()).to.be.equal(whitelistedKPs[lastIndex].publicKey.toBase58());
+
         while (true) {
             const currentTime = await getBlockTime(provider.connection);
             if (currentTime > startVestingTs.toNumber()) {
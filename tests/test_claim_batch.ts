@@ -0,0 +1,160 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BalanceTree } from "./merkle_tree";
+import { Wallet, web3 } from "@coral-xyz/anchor";
+import { ADMIN, claim, claimBatch, createDistributorProgram, createNewDistributor, deriveClaimStatus } from "./merkle_distributor";
+import { createAndFundWallet, getBlockTime, getOrCreateAssociatedTokenAccountWrap, getRandomInt, sleep } from "./common";
+import { BN } from "bn.js";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { approve, createMint, getAccount, getAssociatedTokenAddressSync, mintTo, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { expect } from "chai";
+const provider = anchor.AnchorProvider.env();
+
+function toProof(proofBuffers: Buffer[]) {
+    let proof = [];
+    proofBuffers.forEach(function (value) {
+        proof.push(Array.from(new Uint8Array(value)));
+    });
+    return proof;
+}
+
+describe("Batch claim with a multiproof", () => {
+    let admin = Keypair.generate();
+    let custodian = Keypair.generate();
+    let other = Keypair.generate();
+    let tree: BalanceTree;
+    // the custodian is the delegate of the token accounts of the first half of the wallets
+    let numCustodianNodes = 4;
+    let maxNumNodes = 8;
+    let balances: { account: PublicKey, amountUnlocked: anchor.BN, amountLocked: anchor.BN }[] = [];
+    let whitelistedKPs: web3.Keypair[] = [];
+    let totalClaim = new BN(0);
+    let mint: PublicKey;
+    before(async () => {
+        await createAndFundWallet(provider.connection, ADMIN);
+        await createAndFundWallet(provider.connection, admin);
+        await createAndFundWallet(provider.connection, custodian);
+        await createAndFundWallet(provider.connection, other);
+
+        mint = await createMint(
+            provider.connection,
+            ADMIN,
+            ADMIN.publicKey,
+            null,
+            6,
+            web3.Keypair.generate(),
+            {
+                commitment: "confirmed",
+            },
+            TOKEN_PROGRAM_ID
+        );
+
+        for (let i = 0; i < maxNumNodes; i++) {
+            const result = await createAndFundWallet(provider.connection);
+            whitelistedKPs.push(result.keypair);
+            let account = result.keypair.publicKey;
+            if (i < numCustodianNodes) {
+                let tokenAccount = await getOrCreateAssociatedTokenAccountWrap(provider.connection, result.keypair, mint, account);
+                await approve(provider.connection, result.keypair, tokenAccount, custodian.publicKey, result.keypair, 1);
+            }
+            let amountUnlocked = new BN(getRandomInt(1000, 20000));
+            let amountLocked = new BN(getRandomInt(1000, 20000));
+
+            balances.push({ account, amountUnlocked, amountLocked });
+            totalClaim = totalClaim.add(amountUnlocked).add(amountLocked);
+        }
+
+        tree = new BalanceTree(balances);
+    })
+    it("Claims several leaves of token accounts delegated to the signer at once", async () => {
+        let currentTime = await getBlockTime(provider.connection);
+        let startVestingTs = new BN(currentTime + 30);
+        let endVestingTs = new BN(currentTime + 60);
+        let clawbackStartTs = new BN(currentTime + 90);
+        let activationType = 1; // timestamp
+        let activationPoint = new BN(currentTime + 2);
+
+        let clawbackReceiver = await getOrCreateAssociatedTokenAccountWrap(provider.connection, ADMIN, mint, ADMIN.publicKey);
+        let { distributor, tokenVault } = await createNewDistributor({
+            admin,
+            version: 0,
+            root: tree.getRoot(),
+            totalClaim,
+            maxNumNodes: new BN(maxNumNodes),
+            startVestingTs,
+            endVestingTs,
+            clawbackStartTs,
+            activationPoint,
+            activationType,
+            closable: false,
+            totalBonus: new BN(0),
+            bonusVestingDuration: new BN(0),
+            claimType: 0,
+            operator: web3.SystemProgram.programId,
+            locker: web3.SystemProgram.programId,
+            mint,
+            clawbackReceiver,
+        });
+        await mintTo(provider.connection, ADMIN, mint, tokenVault, ADMIN, totalClaim.toNumber());
+
+        while (true) {
+            const currentTime = await getBlockTime(provider.connection);
+            if (currentTime > activationPoint.toNumber()) {
+                break;
+            } else {
+                await sleep(1000);
+                console.log("Wait until activationPoint");
+            }
+        }
+
+        const program = createDistributorProgram(new Wallet(admin));
+        let toLeaves = (indices: number[]) => {
+            let { order, proof, proofFlags } = tree.getMultiProof(indices.map((i) => balances[i]));
+            let leaves = order.map((j) => {
+                let { account, amountUnlocked, amountLocked } = balances[indices[j]];
+                return { claimant: account, amountUnlocked, amountLocked };
+            });
+            return { leaves, proof: toProof(proof), proofFlags };
+        };
+
+        // token accounts not delegated to the signer can't be claimed
+        let err = await claimBatch({ claimant: other, distributor, ...toLeaves([0, 1, 2]) }).then(() => null, (e) => e);
+        expect(err?.error?.errorCode?.code).eq("OwnerMismatch");
+        for (let i = 0; i < 3; i++) {
+            expect(await provider.connection.getAccountInfo(deriveClaimStatus(distributor, balances[i].account))).to.be.null;
+        }
+
+        await claimBatch({ claimant: custodian, distributor, ...toLeaves([2, 0, 1]) });
+        for (let i = 0; i < 3; i++) {
+            let claimStatusState = await program.account.claimStatus.fetch(deriveClaimStatus(distributor, balances[i].account));
+            expect(claimStatusState.claimant.toBase58()).eq(balances[i].account.toBase58());
+            expect(claimStatusState.lockedAmount.toString()).eq(balances[i].amountLocked.toString());
+            let token = await getAccount(provider.connection, getAssociatedTokenAddressSync(mint, balances[i].account));
+            expect(token.amount.toString()).eq(balances[i].amountUnlocked.toString());
+        }
+
+        // a claimed leaf fails the whole batch
+        err = await claimBatch({ claimant: custodian, distributor, ...toLeaves([0, 3]) }).then(() => null, (e) => e);
+        expect(err).not.null;
+        expect(await provider.connection.getAccountInfo(deriveClaimStatus(distributor, balances[3].account))).to.be.null;
+
+        // a multiproof of a single leaf is a regular proof
+        await claimBatch({ claimant: custodian, distributor, ...toLeaves([3]) });
+        let token = await getAccount(provider.connection, getAssociatedTokenAddressSync(mint, balances[3].account));
+        expect(token.amount.toString()).eq(balances[3].amountUnlocked.toString());
+
+        // wallets of the same tree still claim one by one
+        let wallet = balances[numCustodianNodes];
+        await claim({
+            distributor,
+            claimant: whitelistedKPs[numCustodianNodes],
+            amountUnlocked: wallet.amountUnlocked,
+            amountLocked: wallet.amountLocked,
+            proof: toProof(tree.getProof(wallet.account, wallet.amountUnlocked, wallet.amountLocked)),
+        });
+        let walletToken = await getAccount(provider.connection, getAssociatedTokenAddressSync(mint, wallet.account));
+        expect(walletToken.amount.toString()).eq(wallet.amountUnlocked.toString());
+
+        let distributorState = await program.account.merkleDistributor.fetch(distributor);
+        expect(distributorState.numNodesClaimed.toNumber()).eq(numCustodianNodes + 1);
+    })
+})
@@ -0,0 +1,137 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BalanceTree } from "./merkle_tree";
+import { Wallet, web3 } from "@coral-xyz/anchor";
+import { ADMIN, claim, createDistributorProgram, createNewDistributor, deriveClaimStatus } from "./merkle_distributor";
+import { createAndFundWallet, getBlockTime, getOrCreateAssociatedTokenAccountWrap, getRandomInt, sleep } from "./common";
+import { BN } from "bn.js";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { createMint, getAccount, getAssociatedTokenAddressSync, mintTo, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { expect } from "chai";
+const provider = anchor.AnchorProvider.env();
+
+describe("Claim fee", () => {
+    let admin = Keypair.generate();
+    let feeReceiver = Keypair.generate();
+    let tree: BalanceTree;
+    let maxNumNodes = 3;
+    let whitelistedKPs: web3.Keypair[] = [];
+    let amountUnlockedArr: anchor.BN[] = [];
+    let amountLockedArr: anchor.BN[] = [];
+    let totalClaim = new BN(0);
+    let mint: PublicKey;
+    let claimFeeBps = new BN(250);
+    before(async () => {
+        await createAndFundWallet(provider.connection, ADMIN);
+        await createAndFundWallet(provider.connection, admin);
+
+        for (let i = 0; i < maxNumNodes; i++) {
+            const result = await createAndFundWallet(provider.connection);
+            whitelistedKPs.push(result.keypair);
+            let amountUnlocked = new BN(getRandomInt(1000, 20000));
+            let amountLocked = new BN(getRandomInt(1000, 20000));
+
+            amountUnlockedArr.push(amountUnlocked);
+            amountLockedArr.push(amountLocked);
+            totalClaim = totalClaim.add(amountUnlocked).add(amountLocked);
+        }
+
+        tree = new BalanceTree(
+            whitelistedKPs.map((kp, index) => {
+                return { account: kp.publicKey, amountUnlocked: amountUnlockedArr[index], amountLocked: amountLockedArr[index] };
+            })
+        );
+
+        mint = await createMint(
+            provider.connection,
+            ADMIN,
+            ADMIN.publicKey,
+            null,
+            6,
+            web3.Keypair.generate(),
+            {
+                commitment: "confirmed",
+            },
+            TOKEN_PROGRAM_ID
+        );
+    })
+    it("Charge basis points fee on new claim", async () => {
+        let currentTime = await getBlockTime(provider.connection);
+        let startVestingTs = new BN(currentTime + 3);
+        let endVestingTs = new BN(currentTime + 6);
+        let clawbackStartTs = new BN(currentTime + 7);
+        let activationType = 1; // timestamp
+        let activationPoint = new BN(currentTime + 2);
+
+        let clawbackReceiver = await getOrCreateAssociatedTokenAccountWrap(provider.connection, ADMIN, mint, ADMIN.publicKey);
+        let feeReceiverToken = await getOrCreateAssociatedTokenAccountWrap(provider.connection, ADMIN, mint, feeReceiver.publicKey);
+        let { distributor, tokenVault } = await createNewDistributor({
+            admin,
+            version: 0,
+            root: tree.getRoot(),
+            totalClaim,
+            maxNumNodes: new BN(maxNumNodes),
+            startVestingTs,
+            endVestingTs,
+            clawbackStartTs,
+            activationPoint,
+            activationType,
+            closable: false,
+            totalBonus: new BN(0),
+            bonusVestingDuration: new BN(0),
+            claimType: 0,
+            operator: web3.SystemProgram.programId,
+            locker: web3.SystemProgram.programId,
+            mint,
+            clawbackReceiver,
+            claimFeeType: 2, // basis points
+            claimFee: claimFeeBps,
+            feeReceiver: feeReceiver.publicKey,
+        });
+        await mintTo(provider.connection, ADMIN, mint, tokenVault, ADMIN, totalClaim.toNumber());
+
+        while (true) {
+            const currentTime = await getBlockTime(provider.connection);
+            if (currentTime > activationPoint.toNumber()) {
+                break;
+            } else {
+                await sleep(1000);
+                console.log("Wait until activationPoint");
+            }
+        }
+
+        const program = createDistributorProgram(new Wallet(admin));
+        let totalFee = new BN(0);
+        for (let i = 0; i < maxNumNodes; i++) {
+            var proofBuffers = tree.getProof(
+                whitelistedKPs[i].publicKey,
+                amountUnlockedArr[i],
+                amountLockedArr[i]
+            );
+            let proof = [];
+            proofBuffers.forEach(function (value) {
+                proof.push(Array.from(new Uint8Array(value)));
+            });
+            await claim({
+                distributor,
+                claimant: whitelistedKPs[i],
+                amountUnlocked: amountUnlockedArr[i],
+                amountLocked: amountLockedArr[i],
+                proof,
+            })
+
+            // vested locked amount is withdrawn in the same claim
+            let claimStatus = await program.account.claimStatus.fetch(deriveClaimStatus(distributor, whitelistedKPs[i].publicKey));
+            let grossAmount = amountUnlockedArr[i].add(claimStatus.lockedAmountWithdrawn);
+            let fee = grossAmount.mul(claimFeeBps).div(new BN(10000));
+            totalFee = totalFee.add(fee);
+
+            expect(claimStatus.feeAmount.toString()).eq(fee.toString());
+
+            let claimantToken = await getAccount(provider.connection, getAssociatedTokenAddressSync(mint, whitelistedKPs[i].publicKey));
+            expect(claimantToken.amount.toString()).eq(grossAmount.sub(fee).toString());
+        }
+
+        let feeReceiverState = await getAccount(provider.connection, feeReceiverToken);
+        expect(feeReceiverState.amount.toString()).eq(totalFee.toString());
+    })
+})
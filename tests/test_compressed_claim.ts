@@ -0,0 +1,211 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BalanceTree, ClaimRecordTree } from "./merkle_tree";
+import { Wallet, web3 } from "@coral-xyz/anchor";
+import { ADMIN, claim, claimCompressed, claimLockedCompressed, createDistributorProgram, createNewDistributor, deriveClaimStatus, newClaimRecordTree } from "./merkle_distributor";
+import { createAndFundWallet, getBlockTime, getOrCreateAssociatedTokenAccountWrap, getRandomInt, sleep } from "./common";
+import { BN } from "bn.js";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { createMint, getAccount, getAssociatedTokenAddressSync, mintTo, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { expect } from "chai";
+const provider = anchor.AnchorProvider.env();
+
+function toProof(proofBuffers: Buffer[]) {
+    let proof = [];
+    proofBuffers.forEach(function (value) {
+        proof.push(Array.from(new Uint8Array(value)));
+    });
+    return proof;
+}
+
+describe("Compressed claims recorded in the claim record tree", () => {
+    let admin = Keypair.generate();
+    let tree: BalanceTree;
+    let recordTree: ClaimRecordTree;
+    let maxNumNodes = 3;
+    // one level more than the airdrop tree, the canopy leaves 2 nodes to the record proofs
+    let maxDepth = 3;
+    let canopyDepth = 1;
+    let whitelistedKPs: web3.Keypair[] = [];
+    let amountUnlockedArr: anchor.BN[] = [];
+    let amountLockedArr: anchor.BN[] = [];
+    let totalClaim = new BN(0);
+    let mint: PublicKey;
+    before(async () => {
+        await createAndFundWallet(provider.connection, ADMIN);
+        await createAndFundWallet(provider.connection, admin);
+
+        for (let i = 0; i < maxNumNodes; i++) {
+            const result = await createAndFundWallet(provider.connection);
+            whitelistedKPs.push(result.keypair);
+            let amountUnlocked = new BN(getRandomInt(1000, 20000));
+            let amountLocked = new BN(getRandomInt(1000, 20000));
+
+            amountUnlockedArr.push(amountUnlocked);
+            amountLockedArr.push(amountLocked);
+            totalClaim = totalClaim.add(amountUnlocked).add(amountLocked);
+        }
+
+        tree = new BalanceTree(
+            whitelistedKPs.map((kp, index) => {
+                return { account: kp.publicKey, amountUnlocked: amountUnlockedArr[index], amountLocked: amountLockedArr[index] };
+            })
+        );
+        recordTree = new ClaimRecordTree([], maxDepth);
+
+        mint = await createMint(
+            provider.connection,
+            ADMIN,
+            ADMIN.publicKey,
+            null,
+            6,
+            web3.Keypair.generate(),
+            {
+                commitment: "confirmed",
+            },
+            TOKEN_PROGRAM_ID
+        );
+    })
+    it("Claims once with proofs fast-forwarded through the changelog", async () => {
+        let currentTime = await getBlockTime(provider.connection);
+        let startVestingTs = new BN(currentTime + 20);
+        let endVestingTs = new BN(currentTime + 40);
+        let clawbackStartTs = new BN(currentTime + 90);
+        let activationType = 1; // timestamp
+        let activationPoint = new BN(currentTime + 2);
+
+        let clawbackReceiver = await getOrCreateAssociatedTokenAccountWrap(provider.connection, ADMIN, mint, ADMIN.publicKey);
+        let { distributor, tokenVault } = await createNewDistributor({
+            admin,
+            version: 0,
+            root: tree.getRoot(),
+            totalClaim,
+            maxNumNodes: new BN(maxNumNodes),
+            startVestingTs,
+            endVestingTs,
+            clawbackStartTs,
+            activationPoint,
+            activationType,
+            closable: true,
+            totalBonus: new BN(0),
+            bonusVestingDuration: new BN(0),
+            claimType: 0,
+            operator: web3.SystemProgram.programId,
+            locker: web3.SystemProgram.programId,
+            mint,
+            clawbackReceiver,
+        });
+        await mintTo(provider.connection, ADMIN, mint, tokenVault, ADMIN, totalClaim.toNumber());
+        let claimRecordTree = await newClaimRecordTree({ admin, distributor, maxDepth, canopyDepth });
+
+        while (true) {
+            const currentTime = await getBlockTime(provider.connection);
+            if (currentTime > activationPoint.toNumber()) {
+                break;
+            } else {
+                await sleep(1000);
+                console.log("Wait until activationPoint");
+            }
+        }
+
+        const program = createDistributorProgram(new Wallet(admin));
+
+        // claim statuses can't be created for a compressed distributor
+        await claim({
+            distributor,
+            claimant: whitelistedKPs[0],
+            amountUnlocked: amountUnlockedArr[0],
+            amountLocked: amountLockedArr[0],
+            proof: toProof(tree.getProof(whitelistedKPs[0].publicKey, amountUnlockedArr[0], amountLockedArr[0])),
+        });
+        expect(await provider.connection.getAccountInfo(deriveClaimStatus(distributor, whitelistedKPs[0].publicKey))).to.be.null;
+
+        // the program derives the root of the empty tree
+        let claimRecordTreeState = await program.account.claimRecordTree.fetch(claimRecordTree);
+        expect(Buffer.from(claimRecordTreeState.changelog[0].root).equals(recordTree.getRoot())).to.be.true;
+
+        // the record index of a claimant is the path of its airdrop leaf
+        let airdropProofs = whitelistedKPs.map((kp, i) => tree.getProof(kp.publicKey, amountUnlockedArr[i], amountLockedArr[i]));
+        let recordIndexes = whitelistedKPs.map((kp, i) => ClaimRecordTree.getRecordIndex(BalanceTree.toNode(kp.publicKey, amountUnlockedArr[i], amountLockedArr[i]), airdropProofs[i]));
+        expect(new Set(recordIndexes).size).eq(maxNumNodes);
+
+        // every proof is built against the initial root, later claims are fast-forwarded
+        let initialRoot = recordTree.getRoot();
+        let initialProofs = recordIndexes.map((index) => toProof(recordTree.getClaimProof(index, canopyDepth)));
+        for (let i = 0; i < 2; i++) {
+            if (i == 1) {
+                // the leaf must also be proven against the airdrop root
+                await claimCompressed({
+                    distributor,
+                    claimant: whitelistedKPs[i],
+                    amountUnlocked: amountUnlockedArr[i],
+                    amountLocked: amountLockedArr[i],
+                    root: initialRoot,
+                    proof: initialProofs[i],
+                    airdropProof: toProof(airdropProofs[0]),
+                });
+                let claimRecordTreeState = await program.account.claimRecordTree.fetch(claimRecordTree);
+                expect(claimRecordTreeState.sequenceNumber.toNumber()).eq(i);
+            }
+            await claimCompressed({
+                distributor,
+                claimant: whitelistedKPs[i],
+                amountUnlocked: amountUnlockedArr[i],
+                amountLocked: amountLockedArr[i],
+                root: initialRoot,
+                proof: initialProofs[i],
+                airdropProof: toProof(airdropProofs[i]),
+            });
+            recordTree.update(recordIndexes[i], ClaimRecordTree.toRecordNode(whitelistedKPs[i].publicKey, amountUnlockedArr[i], amountLockedArr[i], new BN(0), new BN(0)));
+
+            let claimantToken = await getAccount(provider.connection, getAssociatedTokenAddressSync(mint, whitelistedKPs[i].publicKey));
+            expect(claimantToken.amount.toString()).eq(amountUnlockedArr[i].toString());
+
+            let claimRecordTreeState = await program.account.claimRecordTree.fetch(claimRecordTree);
+            expect(claimRecordTreeState.sequenceNumber.toNumber()).eq(i + 1);
+            expect(Buffer.from(claimRecordTreeState.changelog[claimRecordTreeState.activeIndex].root).equals(recordTree.getRoot())).to.be.true;
+        }
+
+        // the record slot of the airdrop leaf is no longer empty, it can't be claimed again
+        await claimCompressed({
+            distributor,
+            claimant: whitelistedKPs[0],
+            amountUnlocked: amountUnlockedArr[0],
+            amountLocked: amountLockedArr[0],
+            root: initialRoot,
+            proof: initialProofs[0],
+            airdropProof: toProof(airdropProofs[0]),
+        });
+        let claimantToken = await getAccount(provider.connection, getAssociatedTokenAddressSync(mint, whitelistedKPs[0].publicKey));
+        expect(claimantToken.amount.toString()).eq(amountUnlockedArr[0].toString());
+
+        while (true) {
+            const currentTime = await getBlockTime(provider.connection);
+            if (currentTime > endVestingTs.toNumber()) {
+                break;
+            } else {
+                await sleep(1000);
+                console.log("Wait until endVestingTs");
+            }
+        }
+
+        // the locked amount is withdrawn with the record proof
+        await claimLockedCompressed({
+            distributor,
+            claimant: whitelistedKPs[0],
+            amountUnlocked: amountUnlockedArr[0],
+            amountLocked: amountLockedArr[0],
+            lockedAmountWithdrawn: new BN(0),
+            bonusAmount: new BN(0),
+            root: recordTree.getRoot(),
+            index: recordIndexes[0],
+            proof: toProof(recordTree.getClaimProof(recordIndexes[0], canopyDepth)),
+        });
+        recordTree.update(recordIndexes[0], ClaimRecordTree.toRecordNode(whitelistedKPs[0].publicKey, amountUnlockedArr[0], amountLockedArr[0], amountLockedArr[0], new BN(0)));
+
+        claimantToken = await getAccount(provider.connection, getAssociatedTokenAddressSync(mint, whitelistedKPs[0].publicKey));
+        expect(claimantToken.amount.toString()).eq(amountUnlockedArr[0].add(amountLockedArr[0]).toString());
+
+        claimRecordTreeState = await program.account.claimRecordTree.fetch(claimRecordTree);
+        expect(Buffer.from(claimRecordTreeState.changelog[claimRecordTreeState.activeIndex].root).equals(recordTree.getRoot())).to.be.true;
+    })
+})
@@ -0,0 +1,134 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BalanceTree } from "./merkle_tree";
+import { Wallet, web3 } from "@coral-xyz/anchor";
+import { ADMIN, addToDenylist, claim, createDistributorProgram, createNewDistributor, deriveClaimStatus, newDenylist, removeFromDenylist } from "./merkle_distributor";
+import { createAndFundWallet, getBlockTime, getOrCreateAssociatedTokenAccountWrap, getRandomInt, sleep } from "./common";
+import { BN } from "bn.js";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { createMint, mintTo, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { expect } from "chai";
+const provider = anchor.AnchorProvider.env();
+
+describe("Denylist", () => {
+    let admin = Keypair.generate();
+    let tree: BalanceTree;
+    let maxNumNodes = 3;
+    let whitelistedKPs: web3.Keypair[] = [];
+    let amountUnlockedArr: anchor.BN[] = [];
+    let amountLockedArr: anchor.BN[] = [];
+    let totalClaim = new BN(0);
+    let mint: PublicKey;
+    before(async () => {
+        await createAndFundWallet(provider.connection, ADMIN);
+        await createAndFundWallet(provider.connection, admin);
+
+        for (let i = 0; i < maxNumNodes; i++) {
+            const result = await createAndFundWallet(provider.connection);
+            whitelistedKPs.push(result.keypair);
+            let amountUnlocked = new BN(getRandomInt(1000, 20000));
+            let amountLocked = new BN(getRandomInt(1000, 20000));
+
+            amountUnlockedArr.push(amountUnlocked);
+            amountLockedArr.push(amountLocked);
+            totalClaim = totalClaim.add(amountUnlocked).add(amountLocked);
+        }
+
+        tree = new BalanceTree(
+            whitelistedKPs.map((kp, index) => {
+                return { account: kp.publicKey, amountUnlocked: amountUnlockedArr[index], amountLocked: amountLockedArr[index] };
+            })
+        );
+
+        mint = await createMint(
+            provider.connection,
+            ADMIN,
+            ADMIN.publicKey,
+            null,
+            6,
+            web3.Keypair.generate(),
+            {
+                commitment: "confirmed",
+            },
+            TOKEN_PROGRAM_ID
+        );
+    })
+    it("Block denylisted claimants until removed", async () => {
+        let currentTime = await getBlockTime(provider.connection);
+        let startVestingTs = new BN(currentTime + 3);
+        let endVestingTs = new BN(currentTime + 6);
+        let clawbackStartTs = new BN(currentTime + 7);
+        let activationType = 1; // timestamp
+        let activationPoint = new BN(currentTime + 2);
+
+        let clawbackReceiver = await getOrCreateAssociatedTokenAccountWrap(provider.connection, ADMIN, mint, ADMIN.publicKey);
+        let { distributor, tokenVault } = await createNewDistributor({
+            admin,
+            version: 0,
+            root: tree.getRoot(),
+            totalClaim,
+            maxNumNodes: new BN(maxNumNodes),
+            startVestingTs,
+            endVestingTs,
+            clawbackStartTs,
+            activationPoint,
+            activationType,
+            closable: false,
+            totalBonus: new BN(0),
+            bonusVestingDuration: new BN(0),
+            claimType: 0,
+            operator: web3.SystemProgram.programId,
+            locker: web3.SystemProgram.programId,
+            mint,
+            clawbackReceiver,
+        });
+        await mintTo(provider.connection, ADMIN, mint, tokenVault, ADMIN, totalClaim.toNumber());
+
+        const program = createDistributorProgram(new Wallet(admin));
+        let denylist = await newDenylist({ admin, distributor });
+        // claimants are kept sorted whatever the insertion order
+        for (let i = maxNumNodes - 1; i >= 0; i--) {
+            await addToDenylist({ admin, distributor, claimant: whitelistedKPs[i].publicKey });
+        }
+        await removeFromDenylist({ admin, distributor, claimant: whitelistedKPs[0].publicKey });
+
+        let denylistState = await program.account.denylist.fetch(denylist);
+        expect(denylistState.len.toNumber()).eq(maxNumNodes - 1);
+
+        while (true) {
+            const currentTime = await getBlockTime(provider.connection);
+            if (currentTime > activationPoint.toNumber()) {
+                break;
+            } else {
+                await sleep(1000);
+                console.log("Wait until activationPoint");
+            }
+        }
+
+        for (let i = 0; i < maxNumNodes; i++) {
+            var proofBuffers = tree.getProof(
+                whitelistedKPs[i].publicKey,
+                amountUnlockedArr[i],
+                amountLockedArr[i]
+            );
+            let proof = [];
+            proofBuffers.forEach(function (value) {
+                proof.push(Array.from(new Uint8Array(value)));
+            });
+            await claim({
+                distributor,
+                claimant: whitelistedKPs[i],
+                amountUnlocked: amountUnlockedArr[i],
+                amountLocked: amountLockedArr[i],
+                proof,
+            })
+
+            // only the claimant removed from the denylist can claim
+            let claimStatus = await provider.connection.getAccountInfo(deriveClaimStatus(distributor, whitelistedKPs[i].publicKey));
+            if (i == 0) {
+                expect(claimStatus).to.not.be.null;
+            } else {
+                expect(claimStatus).to.be.null;
+            }
+        }
+    })
+})
@@ -0,0 +1,134 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BalanceTree } from "./merkle_tree";
+import { Wallet, web3 } from "@coral-xyz/anchor";
+import { ADMIN, claim, createDistributorProgram, createNewDistributor, deriveClaimStatus } from "./merkle_distributor";
+import { createAndFundWallet, getBlockTime, getOrCreateAssociatedTokenAccountWrap, getRandomInt, sleep } from "./common";
+import { BN } from "bn.js";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { createMint, getAccount, getAssociatedTokenAddressSync, mintTo, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { expect } from "chai";
+const provider = anchor.AnchorProvider.env();
+
+function toProof(proofBuffers: Buffer[]) {
+    let proof = [];
+    proofBuffers.forEach(function (value) {
+        proof.push(Array.from(new Uint8Array(value)));
+    });
+    return proof;
+}
+
+describe("Claim from a keccak256 tree", () => {
+    let admin = Keypair.generate();
+    let tree: BalanceTree;
+    let sha256Tree: BalanceTree;
+    let maxNumNodes = 5;
+    let whitelistedKPs: web3.Keypair[] = [];
+    let amountUnlockedArr: anchor.BN[] = [];
+    let amountLockedArr: anchor.BN[] = [];
+    let totalClaim = new BN(0);
+    let mint: PublicKey;
+    before(async () => {
+        await createAndFundWallet(provider.connection, ADMIN);
+        await createAndFundWallet(provider.connection, admin);
+
+        for (let i = 0; i < maxNumNodes; i++) {
+            const result = await createAndFundWallet(provider.connection);
+            whitelistedKPs.push(result.keypair);
+            let amountUnlocked = new BN(getRandomInt(1000, 20000));
+            let amountLocked = new BN(getRandomInt(1000, 20000));
+
+            amountUnlockedArr.push(amountUnlocked);
+            amountLockedArr.push(amountLocked);
+            totalClaim = totalClaim.add(amountUnlocked).add(amountLocked);
+        }
+
+        let balances = whitelistedKPs.map((kp, index) => {
+            return { account: kp.publicKey, amountUnlocked: amountUnlockedArr[index], amountLocked: amountLockedArr[index] };
+        });
+        tree = new BalanceTree(balances, 1);
+        sha256Tree = new BalanceTree(balances);
+
+        mint = await createMint(
+            provider.connection,
+            ADMIN,
+            ADMIN.publicKey,
+            null,
+            6,
+            web3.Keypair.generate(),
+            {
+                commitment: "confirmed",
+            },
+            TOKEN_PROGRAM_ID
+        );
+    })
+    it("Verifies proofs with the hash type of the distributor", async () => {
+        let currentTime = await getBlockTime(provider.connection);
+        let startVestingTs = new BN(currentTime + 10);
+        let endVestingTs = new BN(currentTime + 20);
+        let clawbackStartTs = new BN(currentTime + 30);
+        let activationType = 1; // timestamp
+        let activationPoint = new BN(currentTime + 2);
+
+        let clawbackReceiver = await getOrCreateAssociatedTokenAccountWrap(provider.connection, ADMIN, mint, ADMIN.publicKey);
+        let { distributor, tokenVault } = await createNewDistributor({
+            admin,
+            version: 0,
+            root: tree.getRoot(),
+            totalClaim,
+            maxNumNodes: new BN(maxNumNodes),
+            startVestingTs,
+            endVestingTs,
+            clawbackStartTs,
+            activationPoint,
+            activationType,
+            closable: false,
+            totalBonus: new BN(0),
+            bonusVestingDuration: new BN(0),
+            claimType: 0,
+            operator: web3.SystemProgram.programId,
+            locker: web3.SystemProgram.programId,
+            mint,
+            clawbackReceiver,
+            hashType: 1, // keccak256
+        });
+        await mintTo(provider.connection, ADMIN, mint, tokenVault, ADMIN, totalClaim.toNumber());
+
+        const program = createDistributorProgram(new Wallet(admin));
+        let distributorState = await program.account.merkleDistributor.fetch(distributor);
+        expect(distributorState.hashType).eq(1);
+
+        while (true) {
+            const currentTime = await getBlockTime(provider.connection);
+            if (currentTime > activationPoint.toNumber()) {
+                break;
+            } else {
+                await sleep(1000);
+                console.log("Wait until activationPoint");
+            }
+        }
+
+        // a sha256 proof doesn't verify against the keccak256 root
+        await claim({
+            distributor,
+            claimant: whitelistedKPs[0],
+            amountUnlocked: amountUnlockedArr[0],
+            amountLocked: amountLockedArr[0],
+            proof: toProof(sha256Tree.getProof(whitelistedKPs[0].publicKey, amountUnlockedArr[0], amountLockedArr[0])),
+        });
+        expect(await provider.connection.getAccountInfo(deriveClaimStatus(distributor, whitelistedKPs[0].publicKey))).to.be.null;
+
+        for (let i = 0; i < maxNumNodes; i++) {
+            let proofBuffers = tree.getProof(whitelistedKPs[i].publicKey, amountUnlockedArr[i], amountLockedArr[i]);
+            expect(BalanceTree.verifyProof(whitelistedKPs[i].publicKey, amountUnlockedArr[i], amountLockedArr[i], proofBuffers, tree.getRoot(), 1)).to.be.true;
+            await claim({
+                distributor,
+                claimant: whitelistedKPs[i],
+                amountUnlocked: amountUnlockedArr[i],
+                amountLocked: amountLockedArr[i],
+                proof: toProof(proofBuffers),
+            });
+            let claimantToken = await getAccount(provider.connection, getAssociatedTokenAddressSync(mint, whitelistedKPs[i].publicKey));
+            expect(claimantToken.amount.toString()).eq(amountUnlockedArr[i].toString());
+        }
+    })
+})
@@ -0,0 +1,122 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BalanceTree } from "./merkle_tree";
+import { Wallet, web3 } from "@coral-xyz/anchor";
+import { ADMIN, createDistributorProgram, createNewDistributor, deriveClaimStatus, migrateDistributor } from "./merkle_distributor";
+import { createAndFundWallet, getBlockTime, getOrCreateAssociatedTokenAccountWrap, getRandomInt, sleep } from "./common";
+import { BN } from "bn.js";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { createMint, getAssociatedTokenAddressSync, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { expect } from "chai";
+const provider = anchor.AnchorProvider.env();
+
+// loaded by the test validator, see Anchor.toml
+const DISTRIBUTOR_V0 = new PublicKey("FMi78qNPMFk7kE9iFscTzQDtgrVcxHFwzhLU5rC3AB1e");
+
+describe("Migrate distributor", () => {
+    let admin = Keypair.generate();
+    let tree: BalanceTree;
+    let maxNumNodes = 2;
+    let whitelistedKPs: web3.Keypair[] = [];
+    let amountUnlockedArr: anchor.BN[] = [];
+    let amountLockedArr: anchor.BN[] = [];
+    let totalClaim = new BN(0);
+    let mint: PublicKey;
+    before(async () => {
+        await createAndFundWallet(provider.connection, ADMIN);
+        await createAndFundWallet(provider.connection, admin);
+
+        for (let i = 0; i < maxNumNodes; i++) {
+            const result = await createAndFundWallet(provider.connection);
+            whitelistedKPs.push(result.keypair);
+            let amountUnlocked = new BN(getRandomInt(1000, 20000));
+            let amountLocked = new BN(getRandomInt(1000, 20000));
+
+            amountUnlockedArr.push(amountUnlocked);
+            amountLockedArr.push(amountLocked);
+            totalClaim = totalClaim.add(amountUnlocked).add(amountLocked);
+        }
+
+        tree = new BalanceTree(
+            whitelistedKPs.map((kp, index) => {
+                return { account: kp.publicKey, amountUnlocked: amountUnlockedArr[index], amountLocked: amountLockedArr[index] };
+            })
+        );
+
+        mint = await createMint(
+            provider.connection,
+            ADMIN,
+            ADMIN.publicKey,
+            null,
+            6,
+            web3.Keypair.generate(),
+            {
+                commitment: "confirmed",
+            },
+            TOKEN_PROGRAM_ID
+        );
+    })
+    it("Create distributors at the current layout version", async () => {
+        let currentTime = await getBlockTime(provider.connection);
+        let startVestingTs = new BN(currentTime + 3);
+        let endVestingTs = new BN(currentTime + 6);
+        let clawbackStartTs = new BN(currentTime + 7);
+        let activationType = 1; // timestamp
+        let activationPoint = new BN(currentTime + 2);
+
+        let clawbackReceiver = await getOrCreateAssociatedTokenAccountWrap(provider.connection, ADMIN, mint, ADMIN.publicKey);
+        let { distributor } = await createNewDistributor({
+            admin,
+            version: 0,
+            root: tree.getRoot(),
+            totalClaim,
+            maxNumNodes: new BN(maxNumNodes),
+            startVestingTs,
+            endVestingTs,
+            clawbackStartTs,
+            activationPoint,
+            activationType,
+            closable: false,
+            totalBonus: new BN(0),
+            bonusVestingDuration: new BN(0),
+            claimType: 0,
+            operator: web3.SystemProgram.programId,
+            locker: web3.SystemProgram.programId,
+            mint,
+            clawbackReceiver,
+        });
+
+        const program = createDistributorProgram(new Wallet(admin));
+        let distributorState = await program.account.merkleDistributor.fetch(distributor);
+        expect(distributorState.layoutVersion).eq(1);
+        let accountInfo = await provider.connection.getAccountInfo(distributor);
+
+        // migrating a distributor at the current layout is rejected and leaves it unchanged
+        await migrateDistributor({ admin, distributor });
+        let accountInfoAfter = await provider.connection.getAccountInfo(distributor);
+        expect(accountInfoAfter.data.length).eq(accountInfo.data.length);
+        expect(accountInfoAfter.lamports).eq(accountInfo.lamports);
+    })
+    it("Reject claims of distributors at an older layout version", async () => {
+        let accountInfo = await provider.connection.getAccountInfo(DISTRIBUTOR_V0);
+        expect(accountInfo.data.length).eq(8 + 440);
+
+        let claimant = whitelistedKPs[0];
+        const program = createDistributorProgram(new Wallet(claimant));
+        let err = await program.methods.newClaim(amountUnlockedArr[0], amountLockedArr[0], []).accounts({
+            distributor: DISTRIBUTOR_V0,
+            claimant: claimant.publicKey,
+            payer: claimant.publicKey,
+            claimStatus: deriveClaimStatus(DISTRIBUTOR_V0, claimant.publicKey),
+            from: getAssociatedTokenAddressSync(mint, DISTRIBUTOR_V0, true),
+            to: getAssociatedTokenAddressSync(mint, claimant.publicKey),
+            systemProgram: web3.SystemProgram.programId,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            operator: null,
+            feeReceiver: null,
+            denylist: null,
+            bonusVault: null,
+        }).rpc().then(() => null, (e) => e);
+        expect(err?.error?.errorCode?.code).eq("InvalidDistributorLayout");
+        expect(await provider.connection.getAccountInfo(deriveClaimStatus(DISTRIBUTOR_V0, claimant.publicKey))).to.be.null;
+    })
+})
@@ -0,0 +1,102 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BalanceTree } from "./merkle_tree";
+import { Wallet, web3 } from "@coral-xyz/anchor";
+import { ADMIN, createDistributorProgram, createNewDistributor, deriveRegistry, getRegistryEntries } from "./merkle_distributor";
+import { createAndFundWallet, getBlockTime, getOrCreateAssociatedTokenAccountWrap, getRandomInt } from "./common";
+import { BN } from "bn.js";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { createMint, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { expect } from "chai";
+const provider = anchor.AnchorProvider.env();
+
+describe("Distributor registry", () => {
+    let admin = Keypair.generate();
+    let base = Keypair.generate();
+    let tree: BalanceTree;
+    let maxNumNodes = 2;
+    let whitelistedKPs: web3.Keypair[] = [];
+    let amountUnlockedArr: anchor.BN[] = [];
+    let amountLockedArr: anchor.BN[] = [];
+    let totalClaim = new BN(0);
+    let mint: PublicKey;
+    before(async () => {
+        await createAndFundWallet(provider.connection, ADMIN);
+        await createAndFundWallet(provider.connection, admin);
+
+        for (let i = 0; i < maxNumNodes; i++) {
+            const result = await createAndFundWallet(provider.connection);
+            whitelistedKPs.push(result.keypair);
+            let amountUnlocked = new BN(getRandomInt(1000, 20000));
+            let amountLocked = new BN(getRandomInt(1000, 20000));
+
+            amountUnlockedArr.push(amountUnlocked);
+            amountLockedArr.push(amountLocked);
+            totalClaim = totalClaim.add(amountUnlocked).add(amountLocked);
+        }
+
+        tree = new BalanceTree(
+            whitelistedKPs.map((kp, index) => {
+                return { account: kp.publicKey, amountUnlocked: amountUnlockedArr[index], amountLocked: amountLockedArr[index] };
+            })
+        );
+
+        mint = await createMint(
+            provider.connection,
+            ADMIN,
+            ADMIN.publicKey,
+            null,
+            6,
+            web3.Keypair.generate(),
+            {
+                commitment: "confirmed",
+            },
+            TOKEN_PROGRAM_ID
+        );
+    })
+    it("Record every version created with a base and mint", async () => {
+        let currentTime = await getBlockTime(provider.connection);
+        let clawbackReceiver = await getOrCreateAssociatedTokenAccountWrap(provider.connection, ADMIN, mint, ADMIN.publicKey);
+
+        // versions with a gap are all discovered
+        let versions = [0, 2];
+        let distributors = [];
+        for (const version of versions) {
+            let { distributor } = await createNewDistributor({
+                admin,
+                version,
+                root: tree.getRoot(),
+                totalClaim,
+                maxNumNodes: new BN(maxNumNodes),
+                startVestingTs: new BN(currentTime + 30),
+                endVestingTs: new BN(currentTime + 60),
+                clawbackStartTs: new BN(currentTime + 90),
+                activationPoint: new BN(currentTime + 20),
+                activationType: 1, // timestamp
+                closable: false,
+                totalBonus: new BN(0),
+                bonusVestingDuration: new BN(0),
+                claimType: 0,
+                operator: web3.SystemProgram.programId,
+                locker: web3.SystemProgram.programId,
+                mint,
+                clawbackReceiver,
+                base,
+            });
+            distributors.push(distributor);
+        }
+
+        const program = createDistributorProgram(new Wallet(admin));
+        let registry = deriveRegistry(base.publicKey, mint);
+        let registryState = await program.account.registry.fetch(registry);
+        expect(registryState.base.toString()).eq(base.publicKey.toString());
+        expect(registryState.mint.toString()).eq(mint.toString());
+        expect(registryState.len.toNumber()).eq(versions.length);
+
+        let entries = await getRegistryEntries(provider.connection, registry);
+        for (let i = 0; i < versions.length; i++) {
+            expect(entries[i].version.toNumber()).eq(versions[i]);
+            expect(entries[i].distributor.toString()).eq(distributors[i].toString());
+            expect(entries[i].root).deep.eq(Array.from(new Uint8Array(tree.getRoot())));
+        }
+    })
+})
@@ -0,0 +1,165 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BalanceTree } from "./merkle_tree";
+import { Wallet, web3 } from "@coral-xyz/anchor";
+import { ADMIN, claim, closeClaimStatus, createDistributorProgram, createNewDistributor, deriveClaimStatus, setRelayer } from "./merkle_distributor";
+import { createAndFundWallet, getBlockTime, getOrCreateAssociatedTokenAccountWrap, getRandomInt, sleep } from "./common";
+import { BN } from "bn.js";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { createMint, getAccount, getAssociatedTokenAddressSync, mintTo, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { expect } from "chai";
+const provider = anchor.AnchorProvider.env();
+
+describe("Claim with a relayer paying the rent", () => {
+    let admin = Keypair.generate();
+    let relayer = Keypair.generate();
+    let tree: BalanceTree;
+    let maxNumNodes = 2;
+    let whitelistedKPs: web3.Keypair[] = [];
+    let amountUnlockedArr: anchor.BN[] = [];
+    let amountLockedArr: anchor.BN[] = [];
+    let totalClaim = new BN(0);
+    let mint: PublicKey;
+    before(async () => {
+        await createAndFundWallet(provider.connection, ADMIN);
+        await createAndFundWallet(provider.connection, admin);
+        await createAndFundWallet(provider.connection, relayer);
+
+        for (let i = 0; i < maxNumNodes; i++) {
+            const result = await createAndFundWallet(provider.connection);
+            whitelistedKPs.push(result.keypair);
+            let amountUnlocked = new BN(getRandomInt(1000, 20000));
+            let amountLocked = new BN(getRandomInt(1000, 20000));
+
+            amountUnlockedArr.push(amountUnlocked);
+            amountLockedArr.push(amountLocked);
+            totalClaim = totalClaim.add(amountUnlocked).add(amountLocked);
+        }
+
+        tree = new BalanceTree(
+            whitelistedKPs.map((kp, index) => {
+                return { account: kp.publicKey, amountUnlocked: amountUnlockedArr[index], amountLocked: amountLockedArr[index] };
+            })
+        );
+
+        mint = await createMint(
+            provider.connection,
+            ADMIN,
+            ADMIN.publicKey,
+            null,
+            6,
+            web3.Keypair.generate(),
+            {
+                commitment: "confirmed",
+            },
+            TOKEN_PROGRAM_ID
+        );
+    })
+    it("Relayer pays the claim status rent and gets it back on close", async () => {
+        let currentTime = await getBlockTime(provider.connection);
+        let startVestingTs = new BN(currentTime + 30);
+        let endVestingTs = new BN(currentTime + 60);
+        let clawbackStartTs = new BN(currentTime + 90);
+        let activationType = 1; // timestamp
+        let activationPoint = new BN(currentTime + 2);
+
+        let clawbackReceiver = await getOrCreateAssociatedTokenAccountWrap(provider.connection, ADMIN, mint, ADMIN.publicKey);
+        let { distributor, tokenVault } = await createNewDistributor({
+            admin,
+            version: 0,
+            root: tree.getRoot(),
+            totalClaim,
+            maxNumNodes: new BN(maxNumNodes),
+            startVestingTs,
+            endVestingTs,
+            clawbackStartTs,
+            activationPoint,
+            activationType,
+            closable: true,
+            totalBonus: new BN(0),
+            bonusVestingDuration: new BN(0),
+            claimType: 0,
+            operator: web3.SystemProgram.programId,
+            locker: web3.SystemProgram.programId,
+            mint,
+            clawbackReceiver,
+        });
+        await mintTo(provider.connection, ADMIN, mint, tokenVault, ADMIN, totalClaim.toNumber());
+        await setRelayer({ admin, distributor, relayer: relayer.publicKey });
+
+        while (true) {
+            const currentTime = await getBlockTime(provider.connection);
+            if (currentTime > activationPoint.toNumber()) {
+                break;
+            } else {
+                await sleep(1000);
+                console.log("Wait until activationPoint");
+            }
+        }
+
+        const program = createDistributorProgram(new Wallet(admin));
+        for (let i = 0; i < maxNumNodes; i++) {
+            var proofBuffers = tree.getProof(
+                whitelistedKPs[i].publicKey,
+                amountUnlockedArr[i],
+                amountLockedArr[i]
+            );
+            let proof = [];
+            proofBuffers.forEach(function (value) {
+                proof.push(Array.from(new Uint8Array(value)));
+            });
+            // first claimant is relayed, second pays its own rent
+            let payer = i == 0 ? relayer : whitelistedKPs[i];
+            if (i == 1) {
+                // only the relayer can pay on behalf of a claimant
+                const { keypair: stranger } = await createAndFundWallet(provider.connection);
+                await claim({
+                    distributor,
+                    claimant: whitelistedKPs[i],
+                    amountUnlocked: amountUnlockedArr[i],
+                    amountLocked: amountLockedArr[i],
+                    proof,
+                    payer: stranger,
+                });
+                let claimStatus = deriveClaimStatus(distributor, whitelistedKPs[i].publicKey);
+                expect(await provider.connection.getAccountInfo(claimStatus)).to.be.null;
+            }
+            let relayerBalanceBefore = await provider.connection.getBalance(relayer.publicKey);
+            await claim({
+                distributor,
+                claimant: whitelistedKPs[i],
+                amountUnlocked: amountUnlockedArr[i],
+                amountLocked: amountLockedArr[i],
+                proof,
+                payer,
+            });
+            let relayerBalanceAfter = await provider.connection.getBalance(relayer.publicKey);
+
+            let claimStatus = deriveClaimStatus(distributor, whitelistedKPs[i].publicKey);
+            let claimStatusState = await program.account.claimStatus.fetch(claimStatus);
+            let rent = await provider.connection.getBalance(claimStatus);
+            let claimantToken = await getAccount(provider.connection, getAssociatedTokenAddressSync(mint, whitelistedKPs[i].publicKey));
+            expect(claimantToken.amount.toString()).eq(amountUnlockedArr[i].toString());
+            expect(claimStatusState.rentPayer.toBase58()).eq(payer.publicKey.toBase58());
+            if (i == 0) {
+                expect(relayerBalanceBefore - relayerBalanceAfter).eq(rent);
+            } else {
+                expect(relayerBalanceAfter).eq(relayerBalanceBefore);
+            }
+
+            // rent goes back to whoever paid it
+            relayerBalanceBefore = await provider.connection.getBalance(relayer.publicKey);
+            let claimantBalanceBefore = await provider.connection.getBalance(whitelistedKPs[i].publicKey);
+            await closeClaimStatus({ admin, distributor, claimant: whitelistedKPs[i].publicKey });
+            relayerBalanceAfter = await provider.connection.getBalance(relayer.publicKey);
+            let claimantBalanceAfter = await provider.connection.getBalance(whitelistedKPs[i].publicKey);
+            expect(await provider.connection.getAccountInfo(claimStatus)).to.be.null;
+            if (i == 0) {
+                expect(relayerBalanceAfter - relayerBalanceBefore).eq(rent);
+                expect(claimantBalanceAfter).eq(claimantBalanceBefore);
+            } else {
+                expect(relayerBalanceAfter).eq(relayerBalanceBefore);
+                expect(claimantBalanceAfter - claimantBalanceBefore).eq(rent);
+            }
+        }
+    })
+})
@@ -0,0 +1,128 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BalanceTree } from "./merkle_tree";
+import { Wallet, web3 } from "@coral-xyz/anchor";
+import { ADMIN, claim, createDistributorProgram, createNewDistributor, deriveClaimStatus, revokeVesting } from "./merkle_distributor";
+import { createAndFundWallet, getBlockTime, getOrCreateAssociatedTokenAccountWrap, getRandomInt, sleep } from "./common";
+import { BN } from "bn.js";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { createMint, getAccount, mintTo, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { expect } from "chai";
+const provider = anchor.AnchorProvider.env();
+
+describe("Revoke vesting", () => {
+    let admin = Keypair.generate();
+    let tree: BalanceTree;
+    let maxNumNodes = 2;
+    let whitelistedKPs: web3.Keypair[] = [];
+    let amountUnlockedArr: anchor.BN[] = [];
+    let amountLockedArr: anchor.BN[] = [];
+    let totalClaim = new BN(0);
+    let mint: PublicKey;
+    before(async () => {
+        await createAndFundWallet(provider.connection, ADMIN);
+        await createAndFundWallet(provider.connection, admin);
+
+        for (let i = 0; i < maxNumNodes; i++) {
+            const result = await createAndFundWallet(provider.connection);
+            whitelistedKPs.push(result.keypair);
+            let amountUnlocked = new BN(getRandomInt(1000, 20000));
+            let amountLocked = new BN(getRandomInt(1000, 20000));
+
+            amountUnlockedArr.push(amountUnlocked);
+            amountLockedArr.push(amountLocked);
+            totalClaim = totalClaim.add(amountUnlocked).add(amountLocked);
+        }
+
+        tree = new BalanceTree(
+            whitelistedKPs.map((kp, index) => {
+                return { account: kp.publicKey, amountUnlocked: amountUnlockedArr[index], amountLocked: amountLockedArr[index] };
+            })
+        );
+
+        mint = await createMint(
+            provider.connection,
+            ADMIN,
+            ADMIN.publicKey,
+            null,
+            6,
+            web3.Keypair.generate(),
+            {
+                commitment: "confirmed",
+            },
+            TOKEN_PROGRAM_ID
+        );
+    })
+    it("Return unvested tokens to clawback receiver", async () => {
+        let currentTime = await getBlockTime(provider.connection);
+        let startVestingTs = new BN(currentTime + 3);
+        let endVestingTs = new BN(currentTime + 60);
+        let clawbackStartTs = new BN(currentTime + 61);
+        let activationType = 1; // timestamp
+        let activationPoint = new BN(currentTime + 2);
+
+        let clawbackReceiver = await getOrCreateAssociatedTokenAccountWrap(provider.connection, ADMIN, mint, ADMIN.publicKey);
+        let { distributor, tokenVault } = await createNewDistributor({
+            admin,
+            version: 0,
+            root: tree.getRoot(),
+            totalClaim,
+            maxNumNodes: new BN(maxNumNodes),
+            startVestingTs,
+            endVestingTs,
+            clawbackStartTs,
+            activationPoint,
+            activationType,
+            closable: false,
+            totalBonus: new BN(0),
+            bonusVestingDuration: new BN(0),
+            claimType: 0,
+            operator: web3.SystemProgram.programId,
+            locker: web3.SystemProgram.programId,
+            mint,
+            clawbackReceiver,
+        });
+        await mintTo(provider.connection, ADMIN, mint, tokenVault, ADMIN, totalClaim.toNumber());
+
+        while (true) {
+            const currentTime = await getBlockTime(provider.connection);
+            if (currentTime > activationPoint.toNumber()) {
+                break;
+            } else {
+                await sleep(1000);
+                console.log("Wait until activationPoint");
+            }
+        }
+
+        var proofBuffers = tree.getProof(
+            whitelistedKPs[0].publicKey,
+            amountUnlockedArr[0],
+            amountLockedArr[0]
+        );
+        let proof = [];
+        proofBuffers.forEach(function (value) {
+            proof.push(Array.from(new Uint8Array(value)));
+        });
+        await claim({
+            distributor,
+            claimant: whitelistedKPs[0],
+            amountUnlocked: amountUnlockedArr[0],
+            amountLocked: amountLockedArr[0],
+            proof,
+        })
+
+        const program = createDistributorProgram(new Wallet(admin));
+        let clawbackBefore = await getAccount(provider.connection, clawbackReceiver);
+        await revokeVesting({ admin, distributor, claimant: whitelistedKPs[0].publicKey });
+
+        let claimStatus = await program.account.claimStatus.fetch(deriveClaimStatus(distributor, whitelistedKPs[0].publicKey));
+        expect(claimStatus.revoked).eq(1);
+        let revokedAmount = amountLockedArr[0].sub(claimStatus.lockedAmount);
+        expect(revokedAmount.gtn(0)).eq(true);
+
+        let clawbackAfter = await getAccount(provider.connection, clawbackReceiver);
+        expect((clawbackAfter.amount - clawbackBefore.amount).toString()).eq(revokedAmount.toString());
+
+        let distributorState = await program.account.merkleDistributor.fetch(distributor);
+        expect(distributorState.maxTotalClaim.toString()).eq(totalClaim.sub(revokedAmount).toString());
+    })
+})
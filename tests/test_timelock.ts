@@ -0,0 +1,218 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BalanceTree } from "./merkle_tree";
+import { Wallet, web3 } from "@coral-xyz/anchor";
+import { ADMIN, cancelAction, createDistributorProgram, createNewDistributor, executeAction, queueAction } from "./merkle_distributor";
+import { createAndFundWallet, getBlockTime, getOrCreateAssociatedTokenAccountWrap, getRandomInt, sleep } from "./common";
+import { BN } from "bn.js";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { createMint, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { expect } from "chai";
+const provider = anchor.AnchorProvider.env();
+
+describe("Timelocked admin actions", () => {
+    let admin = Keypair.generate();
+    let tree: BalanceTree;
+    let maxNumNodes = 2;
+    let whitelistedKPs: web3.Keypair[] = [];
+    let amountUnlockedArr: anchor.BN[] = [];
+    let amountLockedArr: anchor.BN[] = [];
+    let totalClaim = new BN(0);
+    let mint: PublicKey;
+    let timelockDelay = 5;
+    before(async () => {
+        await createAndFundWallet(provider.connection, ADMIN);
+        await createAndFundWallet(provider.connection, admin);
+
+        for (let i = 0; i < maxNumNodes; i++) {
+            const result = await createAndFundWallet(provider.connection);
+            whitelistedKPs.push(result.keypair);
+            let amountUnlocked = new BN(getRandomInt(1000, 20000));
+            let amountLocked = new BN(getRandomInt(1000, 20000));
+
+            amountUnlockedArr.push(amountUnlocked);
+            amountLockedArr.push(amountLocked);
+            totalClaim = totalClaim.add(amountUnlocked).add(amountLocked);
+        }
+
+        tree = new BalanceTree(
+            whitelistedKPs.map((kp, index) => {
+                return { account: kp.publicKey, amountUnlocked: amountUnlockedArr[index], amountLocked: amountLockedArr[index] };
+            })
+        );
+
+        mint = await createMint(
+            provider.connection,
+            ADMIN,
+            ADMIN.publicKey,
+            null,
+            6,
+            web3.Keypair.generate(),
+            {
+                commitment: "confirmed",
+            },
+            TOKEN_PROGRAM_ID
+        );
+    })
+    it("Queue, cancel and execute admin actions", async () => {
+        let currentTime = await getBlockTime(provider.connection);
+        let startVestingTs = new BN(currentTime + 3);
+        let endVestingTs = new BN(currentTime + 6);
+        let clawbackStartTs = new BN(currentTime + 7);
+        let activationType = 1; // timestamp
+        let activationPoint = new BN(currentTime + 2);
+
+        let clawbackReceiver = await getOrCreateAssociatedTokenAccountWrap(provider.connection, ADMIN, mint, ADMIN.publicKey);
+        let { distributor } = await createNewDistributor({
+            admin,
+            version: 0,
+            root: tree.getRoot(),
+            totalClaim,
+            maxNumNodes: new BN(maxNumNodes),
+            startVestingTs,
+            endVestingTs,
+            clawbackStartTs,
+            activationPoint,
+            activationType,
+            closable: false,
+            totalBonus: new BN(0),
+            bonusVestingDuration: new BN(0),
+            claimType: 0,
+            operator: web3.SystemProgram.programId,
+            locker: web3.SystemProgram.programId,
+            mint,
+            clawbackReceiver,
+            timelockDelay,
+        });
+
+        const program = createDistributorProgram(new Wallet(admin));
+        let newActivationPoint = activationPoint.add(new BN(100));
+
+        // cancelled action is never applied
+        let cancelledAction = await queueAction({
+            admin,
+            distributor,
+            actionType: 0, // set activation point
+            newPubkey: web3.SystemProgram.programId,
+            newValue: new BN(1),
+        });
+        await cancelAction({ admin, distributor, pendingAction: cancelledAction });
+        expect(await provider.connection.getAccountInfo(cancelledAction)).to.be.null;
+
+        let pendingAction = await queueAction({
+            admin,
+            distributor,
+            actionType: 0, // set activation point
+            newPubkey: web3.SystemProgram.programId,
+            newValue: newActivationPoint,
+        });
+
+        // executing before the delay has no effect
+        await executeAction({ payer: whitelistedKPs[0], distributor, pendingAction });
+        let distributorState = await program.account.merkleDistributor.fetch(distributor);
+        expect(distributorState.activationPoint.toString()).eq(activationPoint.toString());
+
+        while (true) {
+            const currentTime = await getBlockTime(provider.connection);
+            let pendingActionState = await program.account.pendingAction.fetch(pendingAction);
+            if (currentTime > pendingActionState.executableTs.toNumber()) {
+                break;
+            } else {
+                await sleep(1000);
+                console.log("Wait until executableTs");
+            }
+        }
+
+        // anyone can execute after the delay
+        await executeAction({ payer: whitelistedKPs[0], distributor, pendingAction });
+        distributorState = await program.account.merkleDistributor.fetch(distributor);
+        expect(distributorState.activationPoint.toString()).eq(newActivationPoint.toString());
+        expect(await provider.connection.getAccountInfo(pendingAction)).to.be.null;
+    })
+    it("Actions queued by a previous admin can't be executed", async () => {
+        let currentTime = await getBlockTime(provider.connection);
+        let startVestingTs = new BN(currentTime + 3);
+        let endVestingTs = new BN(currentTime + 6);
+        let clawbackStartTs = new BN(currentTime + 7);
+        let activationType = 1; // timestamp
+        let activationPoint = new BN(currentTime + 2);
+
+        let clawbackReceiver = await getOrCreateAssociatedTokenAccountWrap(provider.connection, ADMIN, mint, ADMIN.publicKey);
+        let { distributor } = await createNewDistributor({
+            admin,
+            version: 1,
+            root: tree.getRoot(),
+            totalClaim,
+            maxNumNodes: new BN(maxNumNodes),
+            startVestingTs,
+            endVestingTs,
+            clawbackStartTs,
+            activationPoint,
+            activationType,
+            closable: false,
+            totalBonus: new BN(0),
+            bonusVestingDuration: new BN(0),
+            claimType: 0,
+            operator: web3.SystemProgram.programId,
+            locker: web3.SystemProgram.programId,
+            mint,
+            clawbackReceiver,
+            timelockDelay,
+        });
+
+        const program = createDistributorProgram(new Wallet(admin));
+        let newAdmin = Keypair.generate();
+        await createAndFundWallet(provider.connection, newAdmin);
+
+        let claimCapAction = await queueAction({
+            admin,
+            distributor,
+            actionType: 6, // set claim cap
+            newPubkey: web3.SystemProgram.programId,
+            newValue: new BN(1),
+            claimCapType: 1, // number of new claims
+            claimWindowLength: 100,
+        });
+        let setAdminAction = await queueAction({
+            admin,
+            distributor,
+            actionType: 3, // set admin
+            newPubkey: newAdmin.publicKey,
+            newValue: new BN(0),
+        });
+        let staleAction = await queueAction({
+            admin,
+            distributor,
+            actionType: 0, // set activation point
+            newPubkey: web3.SystemProgram.programId,
+            newValue: activationPoint.add(new BN(100)),
+        });
+
+        while (true) {
+            const currentTime = await getBlockTime(provider.connection);
+            let pendingActionState = await program.account.pendingAction.fetch(staleAction);
+            if (currentTime > pendingActionState.executableTs.toNumber()) {
+                break;
+            } else {
+                await sleep(1000);
+                console.log("Wait until executableTs");
+            }
+        }
+
+        await executeAction({ payer: whitelistedKPs[0], distributor, pendingAction: claimCapAction });
+        await executeAction({ payer: whitelistedKPs[0], distributor, pendingAction: setAdminAction });
+        let distributorState = await program.account.merkleDistributor.fetch(distributor);
+        expect(distributorState.claimCapType).eq(1);
+        expect(distributorState.claimCap.toNumber()).eq(1);
+        expect(distributorState.claimWindowLength).eq(100);
+        expect(distributorState.admin.toBase58()).eq(newAdmin.publicKey.toBase58());
+
+        // queued by the previous admin, the new admin can only cancel it
+        await executeAction({ payer: whitelistedKPs[0], distributor, pendingAction: staleAction });
+        distributorState = await program.account.merkleDistributor.fetch(distributor);
+        expect(distributorState.activationPoint.toString()).eq(activationPoint.toString());
+        expect(await provider.connection.getAccountInfo(staleAction)).to.not.be.null;
+
+        await cancelAction({ admin: newAdmin, distributor, pendingAction: staleAction });
+        expect(await provider.connection.getAccountInfo(staleAction)).to.be.null;
+    })
+})
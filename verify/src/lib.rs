@@ -1,4 +1,86 @@
-use solana_program::hash::hashv;
+use solana_program::{hash, keccak};
+
+/// Hash function a merkle tree is built with, stored as u8 in the distributor
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[repr(u8)]
+pub enum HashType {
+    #[default]
+    Sha256, // 0
+    Keccak256, // 1, trees shared with EVM chains
+}
+
+impl TryFrom<u8> for HashType {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(HashType::Sha256),
+            1 => Ok(HashType::Keccak256),
+            _ => Err(value),
+        }
+    }
+}
+
+impl From<HashType> for u8 {
+    fn from(hash_type: HashType) -> Self {
+        hash_type as u8
+    }
+}
+
+// We need to discern between leaf and intermediate nodes to prevent trivial second
+// pre-image attacks.
+// https://flawed.net.nz/2018/02/21/attacking-merkle-trees-with-a-second-preimage-attack
+const LEAF_PREFIX: &[u8] = &[0];
+const INTERMEDIATE_PREFIX: &[u8] = &[1];
+
+impl HashType {
+    /// Leaf of a claim in the merkle tree.
+    /// sha256: hash(0 || hash(claimant || amount_unlocked || amount_locked)), amounts in little endian.
+    /// keccak256: keccak256(keccak256(abi.encode(claimant, amount_unlocked, amount_locked))),
+    /// the leaf of an OpenZeppelin StandardMerkleTree of (bytes32, uint64, uint64), so the same
+    /// tree verifies with OpenZeppelin MerkleProof on EVM chains.
+    pub fn claim_leaf(
+        &self,
+        claimant: &[u8; 32],
+        amount_unlocked: u64,
+        amount_locked: u64,
+    ) -> [u8; 32] {
+        match self {
+            HashType::Sha256 => {
+                let node = hash::hashv(&[
+                    claimant,
+                    &amount_unlocked.to_le_bytes(),
+                    &amount_locked.to_le_bytes(),
+                ]);
+                hash::hashv(&[LEAF_PREFIX, &node.to_bytes()]).to_bytes()
+            }
+            HashType::Keccak256 => {
+                let node = keccak::hashv(&[
+                    claimant,
+                    &abi_encode_u64(amount_unlocked),
+                    &abi_encode_u64(amount_locked),
+                ]);
+                keccak::hashv(&[&node.to_bytes()]).to_bytes()
+            }
+        }
+    }
+
+    /// Parent of two nodes, prefixed with sha256, keccak256(a || b) with keccak256 as in
+    /// OpenZeppelin MerkleProof, the leaves are then told apart by being hashed twice
+    pub fn hash_pair(&self, a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+        match self {
+            HashType::Sha256 => hash::hashv(&[INTERMEDIATE_PREFIX, a, b]).to_bytes(),
+            HashType::Keccak256 => keccak::hashv(&[a, b]).to_bytes(),
+        }
+    }
+}
+
+/// ABI encoding of a uint64, left padded to 32 bytes in big endian
+pub fn abi_encode_u64(value: u64) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    word[24..].copy_from_slice(&value.to_be_bytes());
+    word
+}
 
 /// modified version of https://github.com/saber-hq/merkle-distributor/blob/ac937d1901033ecb7fa3b0db22f7b39569c8e052/programs/merkle-distributor/src/merkle_proof.rs#L8
 /// This function deals with verification of Merkle trees (hash trees).
@@ -8,16 +90,122 @@ use solana_program::hash::hashv;
 /// sibling hashes on the branch from the leaf to the root of the tree. Each
 /// pair of leaves and each pair of pre-images are assumed to be sorted.
 pub fn verify(proof: Vec<[u8; 32]>, root: [u8; 32], leaf: [u8; 32]) -> bool {
+    verify_with_hash_type(proof, root, leaf, HashType::Sha256)
+}
+
+/// Same as [verify], for a tree built with the given hash function
+pub fn verify_with_hash_type(
+    proof: Vec<[u8; 32]>,
+    root: [u8; 32],
+    leaf: [u8; 32],
+    hash_type: HashType,
+) -> bool {
     let mut computed_hash = leaf;
     for proof_element in proof.into_iter() {
         if computed_hash <= proof_element {
             // Hash(current computed hash + current element of the proof)
-            computed_hash = hashv(&[&[1u8], &computed_hash, &proof_element]).to_bytes();
+            computed_hash = hash_type.hash_pair(&computed_hash, &proof_element);
         } else {
             // Hash(current element of the proof + current computed hash)
-            computed_hash = hashv(&[&[1u8], &proof_element, &computed_hash]).to_bytes();
+            computed_hash = hash_type.hash_pair(&proof_element, &computed_hash);
         }
     }
     // Check if the computed hash (root) is equal to the provided root
     computed_hash == root
 }
+
+/// Same as [verify_with_hash_type], returns the path of the leaf if it is part of the tree.
+/// Bit i of the path is set when the node of level i is hashed on the right, under a leading
+/// 1 that tells apart the paths of proofs of different lengths. A leaf has a single valid proof,
+/// so the path is unique to the leaf, whatever the order of the pairs.
+pub fn verify_with_path(
+    proof: Vec<[u8; 32]>,
+    root: [u8; 32],
+    leaf: [u8; 32],
+    hash_type: HashType,
+) -> Option<u64> {
+    if proof.len() >= 64 {
+        return None;
+    }
+    let mut path = 1u64 << proof.len();
+    let mut computed_hash = leaf;
+    for (level, proof_element) in proof.into_iter().enumerate() {
+        if computed_hash <= proof_element {
+            computed_hash = hash_type.hash_pair(&computed_hash, &proof_element);
+        } else {
+            computed_hash = hash_type.hash_pair(&proof_element, &computed_hash);
+            path |= 1 << level;
+        }
+    }
+    (computed_hash == root).then_some(path)
+}
+
+/// Same as [verify_multiproof_with_hash_type], for a tree built with SHA-256
+pub fn verify_multiproof(
+    proof: Vec<[u8; 32]>,
+    proof_flags: Vec<bool>,
+    root: [u8; 32],
+    leaves: Vec<[u8; 32]>,
+) -> bool {
+    verify_multiproof_with_hash_type(proof, proof_flags, root, leaves, HashType::Sha256)
+}
+
+/// Returns true if all `leaves` can be proved to be part of the Merkle tree defined by `root`,
+/// with a single set of sibling hashes. Port of the OpenZeppelin multiproof verification
+/// https://github.com/OpenZeppelin/openzeppelin-contracts/blob/v4.9.3/contracts/utils/cryptography/MerkleProof.sol
+/// The leaves must be ordered by their index in the tree. Each hash computed on the way to the
+/// root takes a first operand from the leaves, then from the computed hashes, and a second
+/// operand from the same queue if its flag is set, from `proof` otherwise.
+pub fn verify_multiproof_with_hash_type(
+    proof: Vec<[u8; 32]>,
+    proof_flags: Vec<bool>,
+    root: [u8; 32],
+    leaves: Vec<[u8; 32]>,
+    hash_type: HashType,
+) -> bool {
+    if leaves.is_empty() || leaves.len() + proof.len() != proof_flags.len() + 1 {
+        return false;
+    }
+    if proof_flags.is_empty() {
+        // a single leaf that is the root
+        return leaves[0] == root;
+    }
+
+    let mut hashes: Vec<[u8; 32]> = Vec::with_capacity(proof_flags.len());
+    let mut leaf_pos = 0;
+    let mut hash_pos = 0;
+    let mut proof_pos = 0;
+    // next leaf, or next computed hash once all the leaves are consumed
+    let mut next_queued = |hashes: &Vec<[u8; 32]>| -> Option<[u8; 32]> {
+        if leaf_pos < leaves.len() {
+            leaf_pos += 1;
+            Some(leaves[leaf_pos - 1])
+        } else {
+            hash_pos += 1;
+            hashes.get(hash_pos - 1).copied()
+        }
+    };
+    for proof_flag in proof_flags.into_iter() {
+        let a = match next_queued(&hashes) {
+            Some(a) => a,
+            None => return false,
+        };
+        let b = if proof_flag {
+            next_queued(&hashes)
+        } else {
+            proof_pos += 1;
+            proof.get(proof_pos - 1).copied()
+        };
+        let b = match b {
+            Some(b) => b,
+            None => return false,
+        };
+        if a <= b {
+            hashes.push(hash_type.hash_pair(&a, &b));
+        } else {
+            hashes.push(hash_type.hash_pair(&b, &a));
+        }
+    }
+    // every proof hash must be used, otherwise leaves could be forged
+    proof_pos == proof.len() && hashes.last() == Some(&root)
+}